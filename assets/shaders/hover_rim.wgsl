@@ -0,0 +1,22 @@
+// The hovered cell's outline: bright along the shell's face edges, with a soft rim light where
+// its faces turn away from the camera, and clear everywhere else so the cell shows through
+#import bevy_pbr::forward_io::VertexOutput
+#import bevy_pbr::mesh_view_bindings::view
+
+@group(2) @binding(0) var<uniform> color: vec4<f32>;
+
+const EDGE_WIDTH: f32 = 0.04;
+const RIM_POWER: f32 = 2.0;
+const RIM_STRENGTH: f32 = 0.6;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let normal = normalize(mesh.world_normal);
+    let to_camera = normalize(view.world_position - mesh.world_position.xyz);
+    var alpha = RIM_STRENGTH * pow(1.0 - abs(dot(normal, to_camera)), RIM_POWER);
+#ifdef VERTEX_UVS_A
+    let edge = min(min(mesh.uv.x, 1.0 - mesh.uv.x), min(mesh.uv.y, 1.0 - mesh.uv.y));
+    alpha = max(alpha, 1.0 - smoothstep(EDGE_WIDTH, EDGE_WIDTH * 2.0, edge));
+#endif
+    return vec4<f32>(color.rgb, color.a * alpha);
+}
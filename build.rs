@@ -0,0 +1,62 @@
+// Lists every third-party crate in Cargo.lock with its license, for the credits screen. The
+// licenses come from the crates' own manifests in Cargo's registry cache, which a build has
+// just downloaded them into
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const REGISTRY_SOURCE: &str = "registry+";
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
+    let registries = registry_dirs();
+
+    let mut lines = Vec::new();
+    for package in lock.split("[[package]]").skip(1) {
+        let field = |key: &str| {
+            package
+                .lines()
+                .find_map(|line| line.strip_prefix(key)?.trim().strip_prefix("= ")?.trim().strip_prefix('"')?.strip_suffix('"'))
+        };
+        let (Some(name), Some(version)) = (field("name"), field("version")) else {
+            continue;
+        };
+        // Path dependencies are this project's own crates
+        if !field("source").is_some_and(|source| source.starts_with(REGISTRY_SOURCE)) {
+            continue;
+        }
+        let license = registries
+            .iter()
+            .find_map(|registry| license_of(&registry.join(format!("{}-{}", name, version)).join("Cargo.toml")))
+            .unwrap_or_else(|| "Unknown".to_string());
+        lines.push(format!("{}\t{}\t{}", name, version, license));
+    }
+
+    let out = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set for build scripts"));
+    fs::write(out.join("crate_licenses.txt"), lines.join("\n")).expect("can write to OUT_DIR");
+}
+
+// Each registry's unpacked sources under Cargo's home
+fn registry_dirs() -> Vec<PathBuf> {
+    let home = env::var_os("CARGO_HOME").map(PathBuf::from).or_else(|| {
+        env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(".cargo"))
+    });
+    let Some(Ok(entries)) = home.map(|home| fs::read_dir(home.join("registry").join("src"))) else {
+        return Vec::new();
+    };
+    entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect()
+}
+
+fn license_of(manifest: &Path) -> Option<String> {
+    let contents = fs::read_to_string(manifest).ok()?;
+    let value = |key: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.trim().strip_prefix("= ")?.trim().strip_prefix('"')?.strip_suffix('"'))
+            .map(str::to_string)
+    };
+    value("license").or_else(|| value("license-file").map(|file| format!("See {}", file)))
+}
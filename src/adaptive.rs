@@ -0,0 +1,254 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use ttt3d_core::ai::EngineKind;
+use ttt3d_core::game::{CellState, GameState, Player, Position};
+use ttt3d_core::notation::Board;
+use ttt3d_core::threats::{forks, threats};
+use ttt3d_core::Difficulty;
+use crate::puzzle::PuzzleMode;
+use crate::settings::{Profile, Settings, SettingsPanel};
+use crate::stats::PlayerStats;
+use crate::vault::ProfileVault;
+
+// The adaptive AI's strength is a level from 0 to 1, over which MCTS simulations grow
+// geometrically from the first bound to Expert's
+const MIN_SIMULATIONS: f64 = 25.0;
+const MAX_SIMULATIONS: f64 = 10_000.0;
+// Alpha-beta's depth and MCTS's blunder rate at level 0
+const MIN_SEARCH_DEPTH: u32 = 1;
+const MAX_BLUNDER_RATE: f64 = 0.4;
+const DIFFICULTIES: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Expert];
+
+// How far one game moves the level: a clean win raises it this much and a loss lowers it, so
+// the AI settles where the human wins about half the time
+const LEVEL_STEP: f64 = 0.08;
+// Share of a game's verdict that comes from the human's play rather than the result, so a
+// close loss with every threat answered lowers the level less than a careless one
+const ACCURACY_WEIGHT: f64 = 0.25;
+// Results kept for the score the settings panel shows
+const RECENT_GAMES: usize = 10;
+
+// Saved with the profile's stats
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveRecord {
+    // `None` until a game has been played with adaptive difficulty on
+    pub level: Option<f64>,
+    // The human's latest results, oldest first: 1 won, 0.5 drawn, 0 lost
+    pub recent: Vec<f64>,
+}
+
+// The level a difficulty's simulation count sits at, where adaptive play starts from
+fn level_of(difficulty: Difficulty) -> f64 {
+    ((difficulty.simulations() as f64 / MIN_SIMULATIONS).ln() / (MAX_SIMULATIONS / MIN_SIMULATIONS).ln()).clamp(0.0, 1.0)
+}
+
+// MCTS simulations, alpha-beta depth and MCTS's blunder rate at a level. The depth and blunder
+// rate keep to each difficulty's own pairing of them with simulations, and are interpolated in
+// between
+pub fn adaptive_budget(level: f64) -> (u32, u32, f64) {
+    let simulations = MIN_SIMULATIONS * (MAX_SIMULATIONS / MIN_SIMULATIONS).powf(level);
+
+    let mut below = (0.0, MIN_SEARCH_DEPTH as f64, MAX_BLUNDER_RATE);
+    let (mut depth, mut blunder_rate) = (below.1, below.2);
+    for difficulty in DIFFICULTIES {
+        let point = (level_of(difficulty), difficulty.search_depth() as f64, difficulty.blunder_rate());
+        (depth, blunder_rate) = (point.1, point.2);
+        if level <= point.0 {
+            let share = (level - below.0) / (point.0 - below.0).max(f64::EPSILON);
+            depth = below.1 + share * (point.1 - below.1);
+            blunder_rate = below.2 + share * (point.2 - below.2);
+            break;
+        }
+        below = point;
+    }
+    (simulations.round() as u32, depth.round() as u32, blunder_rate)
+}
+
+// Follows the game in progress: the human's moves that had a right answer, and how many of
+// those they found
+#[derive(Resource, Default)]
+pub struct DifficultyController {
+    generation: u64,
+    // The position last seen, to judge the next move against
+    board: Option<Board>,
+    moves: usize,
+    to_move: Option<Player>,
+    human_moves: u32,
+    critical: u32,
+    found: u32,
+    recorded: bool,
+}
+
+impl DifficultyController {
+    // Of the human's moves this game that had a right answer, how many they found, as
+    // (found, out of); `None` if there were none
+    pub fn accuracy(&self) -> Option<(u32, u32)> {
+        (self.critical > 0).then_some((self.found, self.critical))
+    }
+}
+
+// Whether a position called for one kind of move, and if so whether the human's `played` was
+// one: completing a line, blocking the AI's, making a fork or stopping the AI's. These are the
+// moves the engine plays before searching
+fn judge(board: &Board, played: Position) -> Option<bool> {
+    let (x, y, z) = played;
+    let mut after = *board;
+    after[x][y][z] = CellState::Human;
+
+    let wins = threats(board, Player::Human);
+    if !wins.is_empty() {
+        return Some(wins.contains(&played));
+    }
+    let blocks = threats(board, Player::AI);
+    if !blocks.is_empty() {
+        return Some(blocks.contains(&played));
+    }
+    let own_forks = forks(board, Player::Human);
+    if !own_forks.is_empty() {
+        return Some(own_forks.contains(&played));
+    }
+    if !forks(board, Player::AI).is_empty() {
+        // Either no fork is left, or a threat forces a reply that isn't one
+        let ai_forks = forks(&after, Player::AI);
+        return Some(ai_forks.is_empty() || matches!(threats(&after, Player::Human).as_slice(), [reply] if !ai_forks.contains(reply)));
+    }
+    None
+}
+
+// Judge each of the human's moves in two-player standard games, and once a game against the
+// adaptive AI ends, move its level by how the human did. Puzzles aren't games of their own
+pub fn track_adaptive_game(
+    settings: Res<Settings>,
+    puzzle: Res<PuzzleMode>,
+    profile: Res<Profile>,
+    vault: Res<ProfileVault>,
+    game_state: Res<GameState>,
+    mut controller: ResMut<DifficultyController>,
+    mut stats: ResMut<PlayerStats>,
+) {
+    if controller.generation != game_state.generation {
+        *controller = DifficultyController {
+            generation: game_state.generation,
+            ..default()
+        };
+    }
+
+    let rules = game_state.rules;
+    let moves = game_state.move_history.len();
+    let judged = rules.player_count() == 2 && !rules.notakto && !rules.fading && !rules.line_scoring;
+    if moves == controller.moves + 1 && controller.to_move == Some(Player::Human) && !game_state.delegated_moves.contains(&(moves - 1)) {
+        controller.human_moves += 1;
+        let before = controller.board.filter(|_| judged);
+        if let Some(found) = before.and_then(|board| judge(&board, game_state.move_history[moves - 1])) {
+            controller.critical += 1;
+            controller.found += found as u32;
+        }
+    }
+    // Kept up to date every frame, as passes and the pie rule change the position without a move
+    controller.board = Some(game_state.board);
+    controller.moves = moves;
+    controller.to_move = Some(game_state.current_player);
+
+    if !game_state.game_over || controller.recorded {
+        return;
+    }
+    controller.recorded = true;
+    let Some(level) = settings.adaptive_level else {
+        return;
+    };
+    if rules.player_count() != 2 || puzzle.active.is_some() || controller.human_moves == 0 {
+        return;
+    }
+
+    let result = match game_state.winner {
+        Some(Player::Human) => 1.0,
+        Some(_) => 0.0,
+        None => 0.5,
+    };
+    let performance = match controller.accuracy() {
+        Some((found, critical)) => (1.0 - ACCURACY_WEIGHT) * result + ACCURACY_WEIGHT * found as f64 / critical as f64,
+        None => result,
+    };
+    let record = &mut stats.adaptive;
+    record.level = Some((level + LEVEL_STEP * 2.0 * (performance - 0.5)).clamp(0.0, 1.0));
+    record.recent.push(result);
+    if record.recent.len() > RECENT_GAMES {
+        record.recent.remove(0);
+    }
+    stats.save(&profile, &vault);
+}
+
+// Hand the AI the profile's level while adaptive difficulty is on. A --difficulty flag holds
+// the AI at that difficulty for the session instead
+pub fn sync_adaptive_level(
+    stats: Res<PlayerStats>,
+    mut settings: ResMut<Settings>,
+    mut game_state: ResMut<GameState>,
+) {
+    let level = (settings.adaptive_difficulty && settings.overridden.difficulty.is_none())
+        .then(|| stats.adaptive.level.unwrap_or_else(|| level_of(settings.difficulty)));
+    if settings.adaptive_level != level {
+        settings.adaptive_level = level;
+        game_state.ai = settings.build_engine();
+    }
+}
+
+#[derive(Component)]
+pub struct AdaptiveLabel;
+
+pub fn adaptive_label(settings: &Settings, stats: &PlayerStats) -> String {
+    let Some(level) = settings.adaptive_level else {
+        return match settings.adaptive_difficulty {
+            true => "Adaptive difficulty: On, held by --difficulty [0]".to_string(),
+            false => "Adaptive difficulty: Off [0]".to_string(),
+        };
+    };
+
+    let (simulations, depth, _) = adaptive_budget(level);
+    let strength = match settings.engine {
+        EngineKind::AlphaBeta => format!("depth {}", depth),
+        EngineKind::Mcts | EngineKind::MctsStrict => format!("{} simulations", simulations),
+    };
+    let recent = &stats.adaptive.recent;
+    match recent.len() {
+        0 => format!("Adaptive difficulty: On, AI at {} [0]", strength),
+        games => format!(
+            "Adaptive difficulty: On, AI at {}, you scored {:.0}% over {} games [0]",
+            strength,
+            100.0 * recent.iter().sum::<f64>() / games as f64,
+            games
+        ),
+    }
+}
+
+pub fn toggle_adaptive_difficulty(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Digit0) {
+        return;
+    }
+
+    settings.adaptive_difficulty = !settings.adaptive_difficulty;
+    settings.save(&profile);
+}
+
+pub fn update_adaptive_label(
+    settings: Res<Settings>,
+    stats: Res<PlayerStats>,
+    mut label_query: Query<&mut Text, With<AdaptiveLabel>>,
+) {
+    if !settings.is_changed() && !stats.is_changed() {
+        return;
+    }
+
+    let label = adaptive_label(&settings, &stats);
+    for mut text in label_query.iter_mut() {
+        if text.sections[0].value != label {
+            text.sections[0].value = label.clone();
+        }
+    }
+}
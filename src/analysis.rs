@@ -0,0 +1,525 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use ttt3d_core::ai::{Engine, MCTSAi, SearchState, SearchStats};
+use ttt3d_core::explain::MoveExplanation;
+use ttt3d_core::notation::Board;
+use ttt3d_core::game::{GamePhase, GameState, Player, Position, Rules};
+use ttt3d_core::symmetry::equivalent_moves;
+use ttt3d_core::threats::{forks, threats};
+use crate::graphics::cell_position;
+use crate::replay::{Watermark, BOARD_SIZE};
+use crate::settings::Profile;
+use crate::vault::ProfileVault;
+
+pub const CHECKPOINT_FILE: &str = "analysis.ron";
+
+// Each move is searched over several frames, in slices small enough not to hitch the window;
+// the engine keeps its tree between slices, so they add up to one deeper search
+const SLICES_PER_MOVE: u32 = 8;
+const SLICE_SIMULATIONS: u32 = 250;
+
+// Disagreements listed under the summary; the rest are counted
+const MAX_LISTED_REVIEWS: usize = 8;
+
+// The key moment is the human's move that gave away the most of their chances, if one gave away
+// at least this much; smaller slips don't turn a game
+const KEY_MOMENT_MIN_LOSS: f64 = 0.1;
+// A move that passed over a win, block or fork the engine plays without searching has no search
+// to measure it by. Those decide games, so it counts as giving everything away
+const FORCED_MISS_LOSS: f64 = 1.0;
+const KEY_MOMENT_PLAYED_COLOR: Color = Color::srgb(1.0, 0.35, 0.3);
+const KEY_MOMENT_BETTER_COLOR: Color = Color::srgb(0.3, 1.0, 0.4);
+
+const PROGRESS_BAR_WIDTH: f32 = 240.0;
+
+// Everything needed to replay a finished game move by move
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub rules: Rules,
+    pub start_board: Board,
+    pub start_player: Player,
+    pub swapped: bool,
+    pub moves: Vec<Position>,
+    // Moves played before each pass, as in `GameState::passes`
+    #[serde(default)]
+    pub passes: Vec<usize>,
+}
+
+impl GameRecord {
+    fn from_game(game_state: &GameState) -> Self {
+        Self {
+            rules: game_state.rules,
+            start_board: game_state.start_board,
+            start_player: game_state.start_player,
+            swapped: game_state.swapped,
+            moves: game_state.move_history.clone(),
+            passes: game_state.passes.clone(),
+        }
+    }
+
+    // The game as it stood before move `ply` was played
+    fn position_before(&self, ply: usize) -> GameState {
+        let mut game = GameState {
+            rules: self.rules,
+            board: self.start_board,
+            current_player: self.start_player,
+            ..default()
+        };
+        for (index, &(x, y, z)) in self.moves.iter().take(ply).enumerate() {
+            self.pass_before(&mut game, index);
+            game.make_move(x, y, z);
+            if index == 0 && self.swapped {
+                game.swap_sides();
+            }
+        }
+        self.pass_before(&mut game, ply);
+        game
+    }
+
+    // Play the passes made before move `ply`; returns false if one isn't allowed
+    fn pass_before(&self, game: &mut GameState, ply: usize) -> bool {
+        self.passes.iter().filter(|&&moves| moves == ply).all(|_| game.pass())
+    }
+
+    // Replay the moves under the record's rules, failing at the first one that isn't legal there
+    fn validate(&self) -> Result<(), String> {
+        let mut game = GameState {
+            rules: self.rules,
+            board: self.start_board,
+            current_player: self.start_player,
+            ..default()
+        };
+        if self.passes.windows(2).any(|pair| pair[0] > pair[1]) || self.passes.last().is_some_and(|&moves| moves > self.moves.len()) {
+            return Err("its passes are out of order".to_string());
+        }
+        for (index, &(x, y, z)) in self.moves.iter().enumerate() {
+            if !self.pass_before(&mut game, index) {
+                return Err(format!("it passes before move {}, which its rules don't allow", index + 1));
+            }
+            if x.max(y).max(z) >= BOARD_SIZE || !game.make_move(x, y, z) {
+                return Err(format!("move {} at ({}, {}, {}) isn't legal under its rules", index + 1, x, y, z));
+            }
+            if index == 0 && self.swapped && !game.swap_sides() {
+                return Err("it swaps sides on the opening move, which its rules don't allow".to_string());
+            }
+        }
+        if !self.pass_before(&mut game, self.moves.len()) {
+            return Err("it passes after the last move, which its rules don't allow".to_string());
+        }
+        Ok(())
+    }
+}
+
+// The engine configuration the analysis runs, as recorded in the watermark
+fn analysis_engine() -> String {
+    format!("MCTS, {} x {} simulations per move", SLICES_PER_MOVE, SLICE_SIMULATIONS)
+}
+
+// The engine's verdict on one move of the game
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MoveReview {
+    pub mover: Player,
+    pub played: Position,
+    pub best: Option<Position>,
+    // Share of the mover's chances the move gave away against the engine's choice, from its
+    // search's win rates. Missing from checkpoints saved before it was measured
+    #[serde(default)]
+    pub loss: Option<f64>,
+}
+
+impl MoveReview {
+    fn agreed(&self) -> bool {
+        self.best.is_none_or(|best| best == self.played)
+    }
+}
+
+// The human's move with the largest loss, and the ply it was played at
+fn key_moment(checkpoint: &AnalysisCheckpoint) -> Option<(usize, &MoveReview)> {
+    checkpoint
+        .reviews
+        .iter()
+        .enumerate()
+        .filter(|(_, review)| review.mover == Player::Human && !review.agreed())
+        .filter(|(_, review)| review.loss.is_some_and(|loss| loss >= KEY_MOMENT_MIN_LOSS))
+        .max_by(|(_, a), (_, b)| a.loss.unwrap_or(0.0).total_cmp(&b.loss.unwrap_or(0.0)))
+}
+
+// What the key moment gave away, and why the engine's move was better where it can say
+fn describe_key_moment(record: &GameRecord, ply: usize, review: &MoveReview) -> Vec<String> {
+    let (px, py, pz) = review.played;
+    let mut lines = vec![format!(
+        "Key moment, move {}: ({}, {}, {}) gave away {:.0}% of your chances",
+        ply + 1,
+        px,
+        py,
+        pz,
+        review.loss.unwrap_or(0.0) * 100.0
+    )];
+    if let Some(best) = review.best {
+        let position = record.position_before(ply);
+        let (bx, by, bz) = best;
+        lines.push(match MoveExplanation::of(&position, best) {
+            Some(explanation) => format!("({}, {}, {}) was better: it {}", bx, by, bz, explanation.hypothetical(&position)),
+            None => format!("({}, {}, {}) was better", bx, by, bz),
+        });
+    }
+    lines
+}
+
+// A full-game analysis and the moves reviewed so far; saved after every move so quitting
+// midway loses at most the move being searched
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnalysisCheckpoint {
+    // Missing from checkpoints saved before watermarking; those are stamped when next saved
+    #[serde(default)]
+    pub watermark: Option<Watermark>,
+    pub record: GameRecord,
+    pub reviews: Vec<MoveReview>,
+}
+
+impl AnalysisCheckpoint {
+    fn is_finished(&self) -> bool {
+        self.reviews.len() >= self.record.moves.len()
+    }
+
+    fn load(profile: &Profile, vault: &ProfileVault) -> Option<Self> {
+        let path = profile.dir().join(CHECKPOINT_FILE);
+        let contents = vault.read(profile, CHECKPOINT_FILE)?;
+        let mut checkpoint: Self = ron::from_str(&contents)
+            .map_err(|err| warn!("Ignoring malformed analysis checkpoint {:?}: {}", path, err))
+            .ok()?;
+
+        let checked = match &checkpoint.watermark {
+            Some(watermark) => watermark.check(&checkpoint.record.rules),
+            None => Ok(()),
+        };
+        if let Err(err) = checked.and_then(|_| checkpoint.record.validate()) {
+            warn!("Refusing analysis checkpoint {:?}: {}", path, err);
+            return None;
+        }
+
+        match &checkpoint.watermark {
+            Some(watermark) if watermark.engine != analysis_engine() => info!(
+                "Analysis checkpoint {:?} was started with {}; the remaining moves use {}",
+                path,
+                watermark.engine,
+                analysis_engine()
+            ),
+            Some(_) => {}
+            None => info!("Analysis checkpoint {:?} has no watermark; it will be stamped when next saved", path),
+        }
+        checkpoint.watermark = Some(Watermark::new(checkpoint.record.rules, analysis_engine()));
+        Some(checkpoint)
+    }
+
+    // Left unsaved while the profile is locked; the analysis still runs to the end
+    fn save(&self, profile: &Profile, vault: &ProfileVault) {
+        if vault.is_locked() {
+            return;
+        }
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)
+            .and_then(|contents| vault.write(profile, CHECKPOINT_FILE, &contents));
+
+        if let Err(err) = result {
+            warn!("Failed to save analysis checkpoint for profile '{}': {}", profile.name, err);
+        }
+    }
+
+    fn discard(profile: &Profile, vault: &ProfileVault) {
+        if let Err(err) = vault.remove(profile, CHECKPOINT_FILE) {
+            warn!("Failed to remove the analysis checkpoint for profile '{}': {}", profile.name, err);
+        }
+    }
+}
+
+// The long-running analysis job: searched a slice per frame while running, and shown
+// with its results once finished until dismissed
+#[derive(Resource)]
+pub struct AnalysisJob {
+    pub checkpoint: Option<AnalysisCheckpoint>,
+    // Slices already searched for the move under review
+    slices: u32,
+    ai: MCTSAi,
+}
+
+impl AnalysisJob {
+    // Start on `game_state` if it's a finished game with moves to review; returns whether it did
+    pub fn start(&mut self, game_state: &GameState, profile: &Profile, vault: &ProfileVault) -> bool {
+        if !game_state.game_over || game_state.phase == GamePhase::Editing || game_state.move_history.is_empty() {
+            return false;
+        }
+
+        let checkpoint = AnalysisCheckpoint {
+            watermark: Some(Watermark::new(game_state.rules, analysis_engine())),
+            record: GameRecord::from_game(game_state),
+            reviews: Vec::new(),
+        };
+        checkpoint.save(profile, vault);
+        self.checkpoint = Some(checkpoint);
+        self.slices = 0;
+        true
+    }
+
+    // Pick up an analysis left unfinished when the game was last closed
+    pub fn resume(profile: &Profile, vault: &ProfileVault) -> Self {
+        let checkpoint = AnalysisCheckpoint::load(profile, vault).filter(|checkpoint| !checkpoint.is_finished());
+        if let Some(checkpoint) = &checkpoint {
+            info!(
+                "Resuming game analysis at move {} of {}",
+                checkpoint.reviews.len() + 1,
+                checkpoint.record.moves.len()
+            );
+        }
+
+        let mut ai = MCTSAi::new();
+        ai.simulations = SLICE_SIMULATIONS;
+        Self { checkpoint, slices: 0, ai }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.checkpoint.as_ref().is_some_and(|checkpoint| !checkpoint.is_finished())
+    }
+}
+
+#[derive(Component)]
+pub struct AnalysisPanel;
+
+#[derive(Component)]
+pub struct AnalysisText;
+
+#[derive(Component)]
+pub struct AnalysisProgressFill;
+
+pub fn setup_analysis_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(50.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            AnalysisPanel,
+        ))
+        .with_children(|row| {
+            row.spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.85).into(),
+                ..default()
+            })
+            .with_children(|panel| {
+                panel.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ),
+                    AnalysisText,
+                ));
+                panel
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(PROGRESS_BAR_WIDTH),
+                            height: Val::Px(8.0),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.25, 0.25, 0.25).into(),
+                        ..default()
+                    })
+                    .with_children(|bar| {
+                        bar.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(0.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                background_color: Color::srgb(0.3, 0.6, 1.0).into(),
+                                ..default()
+                            },
+                            AnalysisProgressFill,
+                        ));
+                    });
+            });
+        });
+}
+
+// Z analyzes the game just finished; while it runs Z cancels it, and once done Z dismisses the results
+pub fn toggle_analysis(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    profile: Res<Profile>,
+    vault: Res<ProfileVault>,
+    mut job: ResMut<AnalysisJob>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyZ) {
+        return;
+    }
+
+    if job.checkpoint.take().is_some() {
+        AnalysisCheckpoint::discard(&profile, &vault);
+        return;
+    }
+
+    job.start(&game_state, &profile, &vault);
+}
+
+pub fn run_analysis(profile: Res<Profile>, vault: Res<ProfileVault>, mut job: ResMut<AnalysisJob>) {
+    if !job.is_running() {
+        return;
+    }
+
+    let job = &mut *job;
+    let Some(checkpoint) = job.checkpoint.as_mut() else {
+        return;
+    };
+
+    let ply = checkpoint.reviews.len();
+    let position = checkpoint.record.position_before(ply);
+    let best = job.ai.best_move(&position, None);
+    job.slices += 1;
+    if job.slices < SLICES_PER_MOVE {
+        return;
+    }
+
+    job.slices = 0;
+    // The engine only searches one of a set of symmetric moves, so any of them agrees with it
+    let played = checkpoint.record.moves[ply];
+    let state = SearchState::from_game(&position);
+    let best = best.map(|best| if equivalent_moves(&state, best, played, &position.rules) { played } else { best });
+    let loss = match best {
+        Some(best) if best != played => {
+            // Either move may have been searched as a symmetric twin
+            let rating = |stats: &SearchStats, cell: Position| {
+                stats
+                    .moves
+                    .iter()
+                    .find(|rated| equivalent_moves(&state, rated.position, cell, &position.rules))
+                    .map(|rated| rated.win_rate)
+            };
+            match job.ai.search_stats().filter(|stats| stats.board == position.board) {
+                Some(stats) => rating(&stats, best).zip(rating(&stats, played)).map(|(best, played)| (best - played).max(0.0)),
+                // Another win, or another fork, is as good as the one the engine found
+                None if threats(&position.board, position.current_player).contains(&played)
+                    || forks(&position.board, position.current_player).contains(&played) =>
+                {
+                    Some(0.0)
+                }
+                None => Some(FORCED_MISS_LOSS),
+            }
+        }
+        _ => Some(0.0),
+    };
+    checkpoint.reviews.push(MoveReview {
+        mover: position.current_player,
+        played,
+        best,
+        loss,
+    });
+
+    // A finished analysis has nothing left to resume
+    if checkpoint.is_finished() {
+        AnalysisCheckpoint::discard(&profile, &vault);
+    } else {
+        checkpoint.save(&profile, &vault);
+    }
+}
+
+pub fn update_analysis_panel(
+    job: Res<AnalysisJob>,
+    mut panel_query: Query<&mut Visibility, With<AnalysisPanel>>,
+    mut text_query: Query<&mut Text, With<AnalysisText>>,
+    mut fill_query: Query<&mut Style, With<AnalysisProgressFill>>,
+) {
+    if !job.is_changed() {
+        return;
+    }
+
+    for mut visibility in panel_query.iter_mut() {
+        *visibility = if job.checkpoint.is_some() { Visibility::Visible } else { Visibility::Hidden };
+    }
+    let Some(checkpoint) = &job.checkpoint else {
+        return;
+    };
+
+    let total = checkpoint.record.moves.len();
+    let done = checkpoint.reviews.len();
+    for mut style in fill_query.iter_mut() {
+        style.width = Val::Percent(done as f32 / total.max(1) as f32 * 100.0);
+    }
+
+    let message = if checkpoint.is_finished() {
+        // Names follow the analyzed game's rules, not the current game's
+        let names = GameState {
+            rules: checkpoint.record.rules,
+            ..default()
+        };
+        let agreed = checkpoint.reviews.iter().filter(|review| review.agreed()).count();
+        let mut lines = vec![format!("Analysis: the engine agreed with {} of {} moves (Z to close)", agreed, total)];
+        match key_moment(checkpoint) {
+            Some((ply, review)) => lines.extend(describe_key_moment(&checkpoint.record, ply, review)),
+            None => lines.push("No single move of yours turned the game".to_string()),
+        }
+        let disagreements: Vec<_> = checkpoint.reviews.iter().enumerate().filter(|(_, review)| !review.agreed()).collect();
+        for (ply, review) in disagreements.iter().take(MAX_LISTED_REVIEWS) {
+            if let Some((x, y, z)) = review.best {
+                let (px, py, pz) = review.played;
+                lines.push(format!(
+                    "Move {} ({}): played ({}, {}, {}), engine prefers ({}, {}, {})",
+                    ply + 1,
+                    names.player_name(review.mover),
+                    px,
+                    py,
+                    pz,
+                    x,
+                    y,
+                    z
+                ));
+            }
+        }
+        if disagreements.len() > MAX_LISTED_REVIEWS {
+            lines.push(format!("...and {} more", disagreements.len() - MAX_LISTED_REVIEWS));
+        }
+        lines.join("\n")
+    } else {
+        format!("Analyzing game: move {} of {} (Z to cancel)", done + 1, total)
+    };
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = message.clone();
+    }
+}
+
+// Outline the key moment's cells while the results are up and the board still shows that game:
+// the move played in red, the engine's choice in green
+pub fn mark_key_moment(job: Res<AnalysisJob>, game_state: Res<GameState>, mut gizmos: Gizmos) {
+    let Some(checkpoint) = job.checkpoint.as_ref().filter(|checkpoint| checkpoint.is_finished()) else {
+        return;
+    };
+    if game_state.move_history != checkpoint.record.moves {
+        return;
+    }
+    let Some((_, review)) = key_moment(checkpoint) else {
+        return;
+    };
+
+    let (x, y, z) = review.played;
+    gizmos.cuboid(Transform::from_translation(cell_position(x, y, z)), KEY_MOMENT_PLAYED_COLOR);
+    if let Some((x, y, z)) = review.best {
+        gizmos.cuboid(Transform::from_translation(cell_position(x, y, z)), KEY_MOMENT_BETTER_COLOR);
+    }
+}
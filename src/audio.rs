@@ -0,0 +1,136 @@
+use bevy::asset::LoadState;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use ttt3d_core::game::LineKind;
+use crate::graphics::{ResetRequested, SoundEvent};
+use crate::mods::PiecePacks;
+use crate::settings::Settings;
+
+const DEFAULT_PLACE_SOUND: &str = "audio/place.mp3";
+
+// Plays the `SoundEvent`s the rest of the game sends, with the chosen piece pack's placement
+// sound. Left out of builds without the `audio` feature, where the events simply go unheard
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_game_sounds)
+            .add_systems(Update, (apply_pack_place_sound, fallback_on_failed_pack_sound, play_sound_effects).chain());
+    }
+}
+
+#[derive(Resource)]
+pub struct GameSounds {
+    pub enabled: bool,
+    pub move_place: Handle<AudioSource>,
+    default_place: Handle<AudioSource>,
+    // The pack whose placement sound is in use, and that sound if the pack brings its own
+    pack: Option<String>,
+    pack_place: Option<Handle<AudioSource>>,
+}
+
+fn load_game_sounds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let move_place = asset_server.load(DEFAULT_PLACE_SOUND);
+    commands.insert_resource(GameSounds {
+        enabled: true,
+        default_place: move_place.clone(),
+        move_place,
+        pack: None,
+        pack_place: None,
+    });
+}
+
+// Swap in the placement sound of the pack picked in the settings, or the built-in one if it has none
+fn apply_pack_place_sound(
+    settings: Res<Settings>,
+    packs: Res<PiecePacks>,
+    asset_server: Res<AssetServer>,
+    mut sounds: ResMut<GameSounds>,
+) {
+    if sounds.pack.as_deref() == Some(settings.piece_pack.as_str()) {
+        return;
+    }
+
+    let pack = packs.get(&settings.piece_pack);
+    sounds.pack_place = pack.and_then(|pack| {
+        let path = pack.manifest.place_sound.as_ref()?;
+        Some(asset_server.load(pack.asset_path(path)))
+    });
+    sounds.move_place = sounds.pack_place.clone().unwrap_or_else(|| sounds.default_place.clone());
+    sounds.pack = Some(settings.piece_pack.clone());
+}
+
+// If a pack's sound fails to load, quietly fall back to the built-in one
+fn fallback_on_failed_pack_sound(asset_server: Res<AssetServer>, mut sounds: ResMut<GameSounds>) {
+    let Some(sound) = &sounds.pack_place else {
+        return;
+    };
+    if matches!(asset_server.load_state(sound), LoadState::Failed(_)) {
+        warn!("Piece pack sound failed to load, falling back to the default sound");
+        sounds.move_place = sounds.default_place.clone();
+        sounds.pack_place = None;
+    }
+}
+
+fn play_sound_effects(
+    mut commands: Commands,
+    mut sound_events: EventReader<SoundEvent>,
+    mut resets: EventReader<ResetRequested>,
+    sounds: Res<GameSounds>,
+) {
+    if !sounds.enabled {
+        return;
+    }
+    
+    for event in sound_events.read() {
+        match event {
+            SoundEvent::MovePlace => {
+                // Play the actual placement sound
+                info!("🔊 Move placed - playing satisfying placement sound");
+                commands.spawn(AudioBundle {
+                    source: sounds.move_place.clone(),
+                    settings: PlaybackSettings::DESPAWN,
+                });
+            }
+            SoundEvent::Hover => {
+                // Play a subtle hover sound (high frequency, quiet, brief)
+                info!("🔊 Cube hovered - playing subtle highlight sound");
+                // Future: Play soft "tick.ogg" or generate 1200Hz brief tone
+            }
+            SoundEvent::Win => {
+                // Play a victory sound (ascending notes, celebratory)
+                info!("🎉 Victory! - playing win fanfare");
+                // Future: Play "victory.ogg" or generate ascending note sequence
+            }
+            SoundEvent::Lose => {
+                // Play a defeat sound (descending notes, sympathetic)
+                info!("😞 Defeat - playing lose sound");
+                // Future: Play "defeat.ogg" or generate descending note sequence
+            }
+            SoundEvent::LineComplete(kind) => {
+                // Layer the placement sound at several pitches into a chord; the rarer the
+                // line, the fuller, lower and louder it gets
+                let (pitches, volume): (&[f32], f32) = match kind {
+                    LineKind::Axis => (&[1.5], 0.8),
+                    LineKind::FaceDiagonal => (&[1.25, 1.5], 1.0),
+                    LineKind::SpaceDiagonal => (&[0.75, 1.0, 1.5], 1.4),
+                };
+                info!("🎵 {:?} completed - playing line stinger", kind);
+                for &pitch in pitches {
+                    commands.spawn(AudioBundle {
+                        source: sounds.move_place.clone(),
+                        settings: PlaybackSettings::DESPAWN
+                            .with_speed(pitch)
+                            .with_volume(Volume::new(volume)),
+                    });
+                }
+            }
+        }
+    }
+
+    if resets.read().last().is_some() {
+        // Play a reset sound (neutral beep, fresh start)
+        info!("🔄 Game reset - playing refresh sound");
+        // Future: Play "reset.ogg" or generate neutral 600Hz tone
+    }
+}
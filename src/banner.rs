@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use ttt3d_core::game::{GameState, Player};
+use crate::graphics::{CameraController, THIRD_PLAYER_COLOR};
+use crate::observer::MAIN_VIEW_LAYER;
+use crate::settings::Settings;
+
+// Size of one voxel of the block font, and how far the letters are extruded
+const VOXEL_SIZE: f32 = 0.18;
+const EXTRUDE_DEPTH: f32 = 0.3;
+
+const DROP_HEIGHT: f32 = 9.0;
+const REST_HEIGHT: f32 = 3.2;
+const GRAVITY: f32 = -25.0;
+// Fraction of speed kept on each bounce; the banner settles once bounces get this slow
+const RESTITUTION: f32 = 0.45;
+const SETTLE_SPEED: f32 = 1.0;
+
+// 5x5 block glyphs for the letters used in the announcements
+fn glyph(letter: char) -> [&'static str; 5] {
+    match letter {
+        'A' => [".###.", "#...#", "#####", "#...#", "#...#"],
+        'B' => ["####.", "#...#", "####.", "#...#", "####."],
+        'D' => ["####.", "#...#", "#...#", "#...#", "####."],
+        'E' => ["#####", "#....", "####.", "#....", "#####"],
+        'I' => ["#####", "..#..", "..#..", "..#..", "#####"],
+        'L' => ["#....", "#....", "#....", "#....", "#####"],
+        'N' => ["#...#", "##..#", "#.#.#", "#..##", "#...#"],
+        'O' => [".###.", "#...#", "#...#", "#...#", ".###."],
+        'R' => ["####.", "#...#", "####.", "#.#..", "#..##"],
+        'S' => [".####", "#....", ".###.", "....#", "####."],
+        'U' => ["#...#", "#...#", "#...#", "#...#", ".###."],
+        'W' => ["#...#", "#...#", "#.#.#", "##.##", "#...#"],
+        'Y' => ["#...#", ".#.#.", "..#..", "..#..", "..#.."],
+        _ => ["....."; 5],
+    }
+}
+
+// Falling 3D announcement; bounces to rest above the board and keeps facing the camera
+#[derive(Component)]
+pub struct WinBanner {
+    pub velocity: f32,
+    pub settled: bool,
+}
+
+fn announcement(winner: Option<Player>) -> (&'static str, Color) {
+    match winner {
+        Some(Player::Human) => ("YOU WIN", Color::srgb(0.2, 0.8, 0.2)),
+        Some(Player::AI) => ("AI WINS", Color::srgb(0.8, 0.2, 0.2)),
+        Some(Player::Third) => ("BLUE WINS", THIRD_PLAYER_COLOR),
+        None => ("DRAW", Color::srgb(0.8, 0.8, 0.2)),
+    }
+}
+
+pub fn spawn_win_banner(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    banner_query: Query<Entity, With<WinBanner>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !game_state.is_changed() {
+        return;
+    }
+
+    // A new game clears the previous announcement
+    if !game_state.game_over {
+        for entity in banner_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    if settings.reduced_motion || !banner_query.is_empty() {
+        return;
+    }
+
+    let (text, color) = announcement(game_state.winner);
+    let voxel = meshes.add(Cuboid::new(VOXEL_SIZE, VOXEL_SIZE, EXTRUDE_DEPTH));
+    let material = materials.add(StandardMaterial {
+        base_color: color,
+        metallic: 0.3,
+        perceptual_roughness: 0.4,
+        ..default()
+    });
+
+    // Each glyph is 5 voxels wide plus a 1-voxel gap; center the whole line on the origin
+    let columns = text.chars().count() * 6 - 1;
+    let left = -(columns as f32 - 1.0) * VOXEL_SIZE / 2.0;
+
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, DROP_HEIGHT, 0.0)),
+            WinBanner {
+                velocity: 0.0,
+                settled: false,
+            },
+        ))
+        .with_children(|banner| {
+            for (i, letter) in text.chars().enumerate() {
+                for (row, bits) in glyph(letter).iter().enumerate() {
+                    for (col, bit) in bits.chars().enumerate() {
+                        if bit != '#' {
+                            continue;
+                        }
+                        let x = left + (i * 6 + col) as f32 * VOXEL_SIZE;
+                        let y = (2.0 - row as f32) * VOXEL_SIZE;
+                        banner.spawn((
+                            PbrBundle {
+                                mesh: voxel.clone(),
+                                material: material.clone(),
+                                transform: Transform::from_xyz(x, y, 0.0),
+                                ..default()
+                            },
+                            // Faces the main camera, so it would read backwards in the opposite view
+                            RenderLayers::layer(MAIN_VIEW_LAYER),
+                        ));
+                    }
+                }
+            }
+        });
+}
+
+pub fn animate_win_banner(
+    time: Res<Time>,
+    camera_query: Query<&Transform, (With<CameraController>, Without<WinBanner>)>,
+    mut banner_query: Query<(&mut Transform, &mut WinBanner)>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+
+    for (mut transform, mut banner) in banner_query.iter_mut() {
+        if !banner.settled {
+            banner.velocity += GRAVITY * time.delta_seconds();
+            transform.translation.y += banner.velocity * time.delta_seconds();
+
+            if transform.translation.y <= REST_HEIGHT {
+                transform.translation.y = REST_HEIGHT;
+                banner.velocity = -banner.velocity * RESTITUTION;
+                banner.settled = banner.velocity < SETTLE_SPEED;
+            }
+        }
+
+        // Turn about the vertical axis so the letters face the camera as it orbits
+        let to_camera = camera.translation - transform.translation;
+        transform.rotation = Quat::from_rotation_y(to_camera.x.atan2(to_camera.z));
+    }
+}
@@ -0,0 +1,353 @@
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_asset::{RenderAssetUsages, RenderAssets};
+use bevy::render::render_graph::{self, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel};
+use bevy::render::render_resource::{
+    Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer, ImageDataLayout,
+    Maintain, MapMode, PipelineCache, TextureDimension, TextureFormat, TextureUsages,
+};
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::texture::{GpuImage, TextureFormatPixelInfo};
+use bevy::render::view::RenderLayers;
+use bevy::render::{Extract, Render, RenderApp, RenderSet};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::graphics::CameraController;
+use crate::settings::{Profile, Settings};
+
+// Frames to render before reading the image back, so the offscreen target is fully drawn.
+// They only count once the renderer has no pipelines left to compile
+const WARMUP_FRAMES: u32 = 3;
+
+// The capture format: 8-bit RGBA with alpha, which is what the PNG is written as
+const CAPTURE_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+// Renders the board from the current view into an offscreen image with a transparent
+// background, at a given resolution rather than the window's. F12 asks for one at the size
+// chosen in the settings and saves it as a PNG under the profile's `screenshots/` folder
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+        let pipelines_ready = PipelinesReady::default();
+        app.insert_resource(CaptureReceiver(Mutex::new(receiver)))
+            .insert_resource(pipelines_ready.clone())
+            .init_resource::<PendingCapture>()
+            .add_event::<CaptureRequest>()
+            .add_event::<CaptureDone>()
+            .add_systems(Update, (request_screenshot, start_capture, finish_capture, save_screenshots).chain());
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .insert_resource(CaptureSender(sender))
+            .insert_resource(pipelines_ready)
+            .add_systems(ExtractSchedule, extract_capture_copiers)
+            .add_systems(Render, (read_back_captures, note_pipelines_ready).after(RenderSet::Render));
+
+        let mut graph = render_app.world_mut().resource_mut::<RenderGraph>();
+        graph.add_node(CaptureCopyLabel, CaptureCopyNode);
+        graph.add_node_edge(bevy::render::graph::CameraDriverLabel, CaptureCopyLabel);
+    }
+}
+
+// Asks for a render of the board; ignored while another capture is still running
+#[derive(Event)]
+pub struct CaptureRequest {
+    pub size: UVec2,
+    // What the capture is for, handed back with the image; `None` for an F12 screenshot
+    pub label: Option<String>,
+}
+
+#[derive(Event)]
+pub struct CaptureDone {
+    pub label: Option<String>,
+    pub image: Image,
+}
+
+// Pixels read back in the render world, passed to the main world (a frame later)
+#[derive(Resource)]
+struct CaptureReceiver(Mutex<Receiver<Vec<u8>>>);
+
+#[derive(Resource)]
+struct CaptureSender(Sender<Vec<u8>>);
+
+// Whether the last frame was drawn with every pipeline compiled; until then anything whose
+// pipeline is still compiling (the whole board, the first time a view is drawn) is left out
+#[derive(Resource, Clone, Default)]
+struct PipelinesReady(Arc<AtomicBool>);
+
+struct CaptureInProgress {
+    camera: Entity,
+    copier: Entity,
+    target: Handle<Image>,
+    size: UVec2,
+    label: Option<String>,
+    frames_left: u32,
+}
+
+#[derive(Resource, Default)]
+struct PendingCapture(Option<CaptureInProgress>);
+
+// Copies the capture target into a CPU-readable buffer each frame while a capture is running
+#[derive(Component, Clone)]
+struct CaptureCopier {
+    buffer: Buffer,
+    source: Handle<Image>,
+}
+
+#[derive(Resource, Default)]
+struct CaptureCopiers(Vec<CaptureCopier>);
+
+fn request_screenshot(keyboard: Res<ButtonInput<KeyCode>>, settings: Res<Settings>, mut requests: EventWriter<CaptureRequest>) {
+    if keyboard.just_pressed(KeyCode::F12) {
+        let [width, height] = settings.capture_size;
+        requests.send(CaptureRequest {
+            size: UVec2::new(width, height),
+            label: None,
+        });
+    }
+}
+
+fn start_capture(
+    mut requests: EventReader<CaptureRequest>,
+    mut pending: ResMut<PendingCapture>,
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    render_device: Res<RenderDevice>,
+    camera_query: Query<(&Transform, &Projection, &RenderLayers), With<CameraController>>,
+) {
+    let Some(request) = requests.read().last() else {
+        return;
+    };
+    if pending.0.is_some() {
+        return;
+    }
+
+    let Ok((transform, projection, render_layers)) = camera_query.get_single() else {
+        return;
+    };
+
+    let UVec2 { x: width, y: height } = request.size;
+    let size = Extent3d {
+        width,
+        height,
+        ..default()
+    };
+    let mut target_image = Image::new_fill(size, TextureDimension::D2, &[0; 4], CAPTURE_FORMAT, RenderAssetUsages::default());
+    target_image.texture_descriptor.usage |=
+        TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING;
+    let target = images.add(target_image);
+
+    // Rows in the read-back buffer are padded to the GPU's copy alignment
+    let padded_row = RenderDevice::align_copy_bytes_per_row(width as usize * CAPTURE_FORMAT.pixel_size());
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("capture_readback"),
+        size: padded_row as u64 * height as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    // Same view as the player's camera; the UI only draws to the window, so it stays out
+    let camera = commands
+        .spawn((
+            Camera3dBundle {
+                transform: *transform,
+                projection: projection.clone(),
+                camera: Camera {
+                    target: RenderTarget::Image(target.clone()),
+                    clear_color: ClearColorConfig::Custom(Color::NONE),
+                    order: -1,
+                    ..default()
+                },
+                ..default()
+            },
+            render_layers.clone(),
+        ))
+        .id();
+    let copier = commands
+        .spawn(CaptureCopier {
+            buffer,
+            source: target.clone(),
+        })
+        .id();
+
+    pending.0 = Some(CaptureInProgress {
+        camera,
+        copier,
+        target,
+        size: request.size,
+        label: request.label.clone(),
+        frames_left: WARMUP_FRAMES,
+    });
+}
+
+fn finish_capture(
+    mut pending: ResMut<PendingCapture>,
+    receiver: Res<CaptureReceiver>,
+    pipelines_ready: Res<PipelinesReady>,
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut done: EventWriter<CaptureDone>,
+) {
+    let Some(capture) = pending.0.as_mut() else {
+        return;
+    };
+
+    // Only the newest frame matters
+    let Ok(receiver) = receiver.0.lock() else {
+        return;
+    };
+    let Some(data) = receiver.try_iter().last() else {
+        return;
+    };
+
+    if !pipelines_ready.0.load(Ordering::Relaxed) {
+        capture.frames_left = WARMUP_FRAMES;
+        return;
+    }
+    if capture.frames_left > 0 {
+        capture.frames_left -= 1;
+        return;
+    }
+
+    // Strip the row padding the GPU copy added
+    let row = capture.size.x as usize * CAPTURE_FORMAT.pixel_size();
+    let padded_row = RenderDevice::align_copy_bytes_per_row(row);
+    let pixels: Vec<u8> = data
+        .chunks(padded_row)
+        .take(capture.size.y as usize)
+        .flat_map(|chunk| &chunk[..row.min(chunk.len())])
+        .copied()
+        .collect();
+
+    let image = Image::new(
+        Extent3d {
+            width: capture.size.x,
+            height: capture.size.y,
+            ..default()
+        },
+        TextureDimension::D2,
+        pixels,
+        CAPTURE_FORMAT,
+        RenderAssetUsages::default(),
+    );
+    done.send(CaptureDone {
+        label: capture.label.clone(),
+        image,
+    });
+
+    commands.entity(capture.camera).despawn();
+    commands.entity(capture.copier).despawn();
+    images.remove(&capture.target);
+    pending.0 = None;
+}
+
+fn save_screenshots(mut done: EventReader<CaptureDone>, profile: Res<Profile>) {
+    for capture in done.read().filter(|capture| capture.label.is_none()) {
+        save_capture(&profile, &capture.image);
+    }
+}
+
+fn save_capture(profile: &Profile, image: &Image) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let dir = profile.dir().join("screenshots");
+    let path = dir.join(format!("board-{}.png", timestamp));
+
+    let rgba = match image.clone().try_into_dynamic() {
+        Ok(dynamic) => dynamic.to_rgba8(),
+        Err(err) => {
+            warn!("Failed to convert capture: {:?}", err);
+            return;
+        }
+    };
+
+    match fs::create_dir_all(&dir).map_err(|err| err.to_string()).and_then(|_| rgba.save(&path).map_err(|err| err.to_string())) {
+        Ok(()) => info!("Saved capture to {:?}", path),
+        Err(err) => warn!("Failed to save capture: {}", err),
+    }
+}
+
+fn extract_capture_copiers(mut commands: Commands, copiers: Extract<Query<&CaptureCopier>>) {
+    commands.insert_resource(CaptureCopiers(copiers.iter().cloned().collect()));
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, RenderLabel)]
+struct CaptureCopyLabel;
+
+#[derive(Default)]
+struct CaptureCopyNode;
+
+impl render_graph::Node for CaptureCopyNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let (Some(copiers), Some(gpu_images)) =
+            (world.get_resource::<CaptureCopiers>(), world.get_resource::<RenderAssets<GpuImage>>())
+        else {
+            return Ok(());
+        };
+
+        for copier in &copiers.0 {
+            // The target may not be on the GPU yet in the first frame
+            let Some(source) = gpu_images.get(&copier.source) else {
+                continue;
+            };
+
+            let padded_row = RenderDevice::align_copy_bytes_per_row(source.size.x as usize * CAPTURE_FORMAT.pixel_size());
+            let mut encoder = render_context
+                .render_device()
+                .create_command_encoder(&CommandEncoderDescriptor::default());
+            encoder.copy_texture_to_buffer(
+                source.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer: &copier.buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_row as u32),
+                        rows_per_image: None,
+                    },
+                },
+                Extent3d {
+                    width: source.size.x,
+                    height: source.size.y,
+                    depth_or_array_layers: 1,
+                },
+            );
+            world.resource::<RenderQueue>().submit(std::iter::once(encoder.finish()));
+        }
+
+        Ok(())
+    }
+}
+
+fn note_pipelines_ready(pipeline_cache: Res<PipelineCache>, ready: Res<PipelinesReady>) {
+    ready.0.store(pipeline_cache.waiting_pipelines().next().is_none(), Ordering::Relaxed);
+}
+
+// Map each copier's buffer once the frame's GPU work is done and send the pixels to the main world
+fn read_back_captures(copiers: Res<CaptureCopiers>, render_device: Res<RenderDevice>, sender: Res<CaptureSender>) {
+    for copier in &copiers.0 {
+        let slice = copier.buffer.slice(..);
+        let (mapped_sender, mapped) = mpsc::channel();
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = mapped_sender.send(result);
+        });
+
+        render_device.poll(Maintain::wait()).panic_on_timeout();
+        if let Ok(Ok(())) = mapped.recv() {
+            // The receiver is gone while the app shuts down
+            let _ = sender.0.send(slice.get_mapped_range().to_vec());
+        }
+        copier.buffer.unmap();
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{line_index, CellState, GameState};
+use ttt3d_core::threats::live_lines;
+use crate::graphics::{cell_position, CameraController, CubeMarker, HoveredCube};
+use crate::settings::Settings;
+
+// Gap between the hovered cell's centre and the label's left edge, in UI pixels
+const LABEL_OFFSET: Vec2 = Vec2::new(28.0, -12.0);
+
+#[derive(Component)]
+pub struct CellInfoText;
+
+pub fn setup_cell_info(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            visibility: Visibility::Hidden,
+            ..TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 15.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Px(4.0)),
+                ..default()
+            })
+            .with_background_color(Color::srgba(0.1, 0.1, 0.1, 0.85))
+        },
+        CellInfoText,
+    ));
+}
+
+// The move that put the piece at `marker` there, and the lines through it its owner could still
+// complete. Pieces set out before the first move, by a puzzle or the editor, have no move
+fn describe_piece(game_state: &GameState, marker: &CubeMarker) -> Option<String> {
+    let cell = game_state.board[marker.x][marker.y][marker.z];
+    if !cell.is_piece() {
+        return None;
+    }
+
+    let position = (marker.x, marker.y, marker.z);
+    let owner = match cell.owner() {
+        Some(player) => game_state.player_name(player).to_string(),
+        None => "shared".to_string(),
+    };
+    // The latest move there, as the fading rule can empty a cell for it to be played again
+    let placed = match game_state.move_history.iter().rposition(|&played| played == position) {
+        Some(index) if game_state.delegated_moves.contains(&index) => format!("Move {}, played for you", index + 1),
+        Some(index) => format!("Move {}", index + 1),
+        None => "Set out before the first move".to_string(),
+    };
+    let lines = match live_lines(&game_state.board, position) {
+        1 => "1 line".to_string(),
+        count => format!("{} lines", count),
+    };
+    let note = if cell == CellState::Neutral { "open" } else { "still open to it" };
+    Some(format!("{} ({})\nOn {} {}", placed, owner, lines, note))
+}
+
+// How many lines run through the empty cell at `marker`, and how many of them the human could
+// still complete, for the faint lines drawn through it
+fn describe_empty(game_state: &GameState, marker: &CubeMarker) -> Option<String> {
+    if game_state.board[marker.x][marker.y][marker.z] != CellState::Empty {
+        return None;
+    }
+
+    // Notakto's pieces are everyone's
+    let (own, note) = match game_state.rules.notakto {
+        true => (CellState::Neutral, "still open"),
+        false => (CellState::Human, "still open to you"),
+    };
+    let lines: Vec<_> = line_index().lines_through(marker.x, marker.y, marker.z).collect();
+    let open = lines
+        .iter()
+        .filter(|line| line.iter().all(|&(x, y, z)| game_state.board[x][y][z] == CellState::Empty || game_state.board[x][y][z] == own))
+        .count();
+    Some(format!("{} lines through here, {} {}", lines.len(), open, note))
+}
+
+// Beside an occupied cell under the cursor, where the mode lets one be hovered: inspecting with
+// L held, or the board editor. With the hover lines setting on, beside an empty one too
+pub fn update_cell_info(
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    ui_scale: Res<UiScale>,
+    hovered_query: Query<&CubeMarker, With<HoveredCube>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    mut text_query: Query<(&mut Text, &mut Style, &mut Visibility), With<CellInfoText>>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+    let info = hovered_query.iter().next().and_then(|marker| {
+        let empty = (settings.hover_lines && !keyboard.pressed(KeyCode::KeyL)).then(|| describe_empty(&game_state, marker)).flatten();
+        let description = describe_piece(&game_state, marker).or(empty)?;
+        let screen = camera.world_to_viewport(camera_transform, cell_position(marker.x, marker.y, marker.z))?;
+        Some((description, screen))
+    });
+
+    for (mut text, mut style, mut visibility) in text_query.iter_mut() {
+        let Some((description, screen)) = &info else {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        };
+
+        *visibility = Visibility::Visible;
+        style.left = Val::Px(screen.x / ui_scale.0 + LABEL_OFFSET.x);
+        style.top = Val::Px(screen.y / ui_scale.0 + LABEL_OFFSET.y);
+        if text.sections[0].value != *description {
+            text.sections[0].value = description.clone();
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::path::PathBuf;
+use ttt3d_core::ai::EngineKind;
+use ttt3d_core::game::Rules;
+use ttt3d_core::Difficulty;
+use crate::settings::Settings;
+use crate::train;
+use crate::tune::DEFAULT_GAMES;
+
+const USAGE: &str = "\
+Usage: tictactoe-3d [OPTIONS]
+
+Options:
+  --difficulty <easy|normal|hard|expert>    AI search strength for this session
+  --engine <mcts|strict|alphabeta>          AI engine for this session
+  --variant <classic|notakto|fading|scoring|pass>
+                                             Rule variant to start in
+  --size <n>                                 Board size (only 3 is supported)
+  --seed <n>                                 Seed for the lighting variation
+  --ai-seed <n>                              Seed for the AI, so its moves can be reproduced
+  --fullscreen                               Start in borderless fullscreen
+  --load <name>                              Load a saved game (not supported yet)
+  --tune                                     Play AI-vs-AI games over a grid of AI parameters
+                                             and print win rates, without opening a window
+  --tune-games <n>                           Games per configuration when tuning (default 20)
+  --train-eval                               Play MCTS against itself, fit the learned evaluation
+                                             to the results and save it, without opening a window
+  --train-games <n>                          Self-play games when training (default 200)
+  --visual-check                             Render reference scenes offscreen and compare them
+                                             with tests/visual/, without opening a window
+  --visual-bless                             Like --visual-check, but rewrite the references
+  --check-puzzles                            Check every puzzle has the one solution it claims
+                                             and print any that don't, without opening a window
+  --puzzle-dir <path>                        Folder of puzzles to check (default assets/puzzles)
+  --help                                     Print this message
+
+Flags only last for this session; saved settings are left untouched.";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Classic,
+    Notakto,
+    Fading,
+    Scoring,
+    Pass,
+}
+
+// Passes each player gets when the pass variant is chosen on the command line or the main menu
+const VARIANT_PASSES: u32 = 2;
+
+impl Variant {
+    // In the order the main menu cycles through them
+    pub const ALL: [Variant; 5] = [Variant::Classic, Variant::Notakto, Variant::Fading, Variant::Scoring, Variant::Pass];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Classic => "Classic",
+            Variant::Notakto => "Notakto",
+            Variant::Fading => "Fading pieces",
+            Variant::Scoring => "Line scoring",
+            Variant::Pass => "Passing",
+        }
+    }
+
+    // `rules` with this variant on and the others off; the pie rule, third player and blocked
+    // cells are left as they were
+    pub fn apply(self, rules: Rules) -> Rules {
+        Rules {
+            notakto: self == Variant::Notakto,
+            fading: self == Variant::Fading,
+            line_scoring: self == Variant::Scoring,
+            passes: if self == Variant::Pass { VARIANT_PASSES } else { 0 },
+            ..rules
+        }
+    }
+
+    // The variant `rules` play as, the first that's on if several are
+    pub fn of(rules: &Rules) -> Self {
+        if rules.notakto {
+            Variant::Notakto
+        } else if rules.fading {
+            Variant::Fading
+        } else if rules.line_scoring {
+            Variant::Scoring
+        } else if rules.passes > 0 {
+            Variant::Pass
+        } else {
+            Variant::Classic
+        }
+    }
+}
+
+// Launch configuration from the command line, for testers and scripts
+#[derive(Clone, Debug, Default)]
+pub struct LaunchOptions {
+    pub difficulty: Option<Difficulty>,
+    pub engine: Option<EngineKind>,
+    pub variant: Option<Variant>,
+    pub seed: Option<u64>,
+    pub ai_seed: Option<u64>,
+    pub fullscreen: bool,
+    // Headless self-play tuning, with the number of games per configuration
+    pub tune: Option<u32>,
+    // Headless self-play training of the learned evaluation, with the number of games
+    pub train_eval: Option<u32>,
+    // Headless visual regression check, and whether to rewrite the references instead
+    pub visual_check: Option<bool>,
+    // Headless puzzle check, and the folder to check instead of the game's own puzzles
+    pub check_puzzles: bool,
+    pub puzzle_dir: Option<PathBuf>,
+    // Problems that don't stop the launch, such as options this build can't honour yet
+    pub warnings: Vec<String>,
+}
+
+impl LaunchOptions {
+    // Parse the process arguments, printing usage and exiting on --help or a malformed flag
+    pub fn from_env() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(Some(options)) => {
+                for warning in &options.warnings {
+                    eprintln!("warning: {}", warning);
+                }
+                options
+            }
+            Ok(None) => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("error: {}\n\n{}", err, USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // Returns Ok(None) when --help was requested
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = LaunchOptions::default();
+
+        while let Some(flag) = args.next() {
+            let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+
+            match flag.as_str() {
+                "--help" | "-h" => return Ok(None),
+                "--fullscreen" => options.fullscreen = true,
+                "--visual-check" => {
+                    options.visual_check.get_or_insert(false);
+                }
+                "--visual-bless" => options.visual_check = Some(true),
+                "--check-puzzles" | "--puzzle-dir" if !cfg!(feature = "solver") => {
+                    return Err(format!("{} needs a build with the solver feature", flag));
+                }
+                "--check-puzzles" => options.check_puzzles = true,
+                "--puzzle-dir" => {
+                    options.puzzle_dir = Some(PathBuf::from(value("--puzzle-dir")?));
+                    options.check_puzzles = true;
+                }
+                "--tune" => {
+                    options.tune.get_or_insert(DEFAULT_GAMES);
+                }
+                "--tune-games" => {
+                    let games: u32 = value("--tune-games")?
+                        .parse()
+                        .ok()
+                        .filter(|&games| games > 0)
+                        .ok_or_else(|| "--tune-games needs a positive number".to_string())?;
+                    options.tune = Some(games);
+                }
+                "--train-eval" => {
+                    options.train_eval.get_or_insert(train::DEFAULT_GAMES);
+                }
+                "--train-games" => {
+                    let games: u32 = value("--train-games")?
+                        .parse()
+                        .ok()
+                        .filter(|&games| games > 0)
+                        .ok_or_else(|| "--train-games needs a positive number".to_string())?;
+                    options.train_eval = Some(games);
+                }
+                "--difficulty" => {
+                    options.difficulty = Some(match value("--difficulty")?.as_str() {
+                        "easy" => Difficulty::Easy,
+                        "normal" => Difficulty::Normal,
+                        "hard" => Difficulty::Hard,
+                        "expert" => Difficulty::Expert,
+                        other => return Err(format!("unknown difficulty '{}'", other)),
+                    });
+                }
+                "--engine" => {
+                    let engine = value("--engine")?;
+                    options.engine = Some(
+                        EngineKind::from_id(&engine)
+                            .ok_or_else(|| format!("unknown engine '{}' (expected one of {})", engine, EngineKind::ids()))?,
+                    );
+                }
+                "--variant" => match value("--variant")?.as_str() {
+                    "classic" => options.variant = Some(Variant::Classic),
+                    "notakto" => options.variant = Some(Variant::Notakto),
+                    "fading" => options.variant = Some(Variant::Fading),
+                    "scoring" => options.variant = Some(Variant::Scoring),
+                    "pass" => options.variant = Some(Variant::Pass),
+                    other => options.warnings.push(format!("variant '{}' isn't available, ignoring --variant", other)),
+                },
+                "--size" => {
+                    let size: usize = value("--size")?
+                        .parse()
+                        .map_err(|_| "--size needs a number".to_string())?;
+                    if size != 3 {
+                        options.warnings.push(format!("only 3x3x3 boards are supported, ignoring --size {}", size));
+                    }
+                }
+                "--seed" => {
+                    let seed = value("--seed")?
+                        .parse()
+                        .map_err(|_| "--seed needs a non-negative number".to_string())?;
+                    options.seed = Some(seed);
+                }
+                "--ai-seed" => {
+                    let seed = value("--ai-seed")?
+                        .parse()
+                        .map_err(|_| "--ai-seed needs a non-negative number".to_string())?;
+                    options.ai_seed = Some(seed);
+                }
+                "--load" => {
+                    let name = value("--load")?;
+                    options.warnings.push(format!("saved games aren't supported yet, ignoring --load {}", name));
+                }
+                other => return Err(format!("unknown option '{}'", other)),
+            }
+        }
+
+        Ok(Some(options))
+    }
+
+    // Apply the overrides for this session, remembering the saved values they displace
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(difficulty) = self.difficulty {
+            settings.overridden.difficulty.get_or_insert(settings.difficulty);
+            settings.difficulty = difficulty;
+        }
+
+        if let Some(engine) = self.engine {
+            settings.overridden.engine.get_or_insert(settings.engine);
+            settings.engine = engine;
+        }
+
+        if let Some(variant) = self.variant {
+            settings.overridden.rules.get_or_insert(settings.rules);
+            settings.rules = variant.apply(settings.rules);
+        }
+
+        if let Some(seed) = self.seed {
+            settings.overridden.light_salt.get_or_insert(settings.light_salt);
+            settings.light_salt = seed;
+        }
+
+        if let Some(seed) = self.ai_seed {
+            settings.overridden.ai_seed.get_or_insert(settings.ai_seed);
+            settings.ai_seed = Some(seed);
+        }
+    }
+}
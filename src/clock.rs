@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{GamePhase, GameState, Player};
+use crate::settings::Settings;
+
+// Per-move countdown: each player gets `limit` seconds for every move
+#[derive(Resource, Default)]
+pub struct TurnClock {
+    pub limit: Option<f32>,
+    pub remaining: f32,
+    // Which turn the countdown belongs to, so it restarts whenever the turn changes
+    turn: (usize, Option<Player>),
+}
+
+impl TurnClock {
+    pub fn restart(&mut self) {
+        self.remaining = self.limit.unwrap_or(0.0);
+    }
+}
+
+#[derive(Component)]
+pub struct TurnClockText;
+
+pub fn setup_turn_clock_ui(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 24.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        TurnClockText,
+    ));
+}
+
+pub fn sync_turn_clock_settings(settings: Res<Settings>, mut clock: ResMut<TurnClock>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let limit = (settings.turn_time_limit > 0).then_some(settings.turn_time_limit as f32);
+    if clock.limit != limit {
+        clock.limit = limit;
+        clock.restart();
+    }
+}
+
+pub fn tick_turn_clock(
+    mut game_state: ResMut<GameState>,
+    mut clock: ResMut<TurnClock>,
+    time: Res<Time>,
+) {
+    if clock.limit.is_none() {
+        return;
+    }
+
+    let turn = (game_state.move_history.len(), (!game_state.game_over).then_some(game_state.current_player));
+    // A reset before the first move keeps the same turn key, so also restart on a fresh board
+    if clock.turn != turn || (game_state.is_changed() && game_state.move_history.is_empty()) {
+        clock.turn = turn;
+        clock.restart();
+    }
+
+    if game_state.game_over {
+        return;
+    }
+
+    // Nobody is on the clock while a position is being set up
+    if game_state.phase == GamePhase::Editing {
+        clock.restart();
+        return;
+    }
+
+    clock.remaining = (clock.remaining - time.delta_seconds()).max(0.0);
+    if clock.remaining <= 0.0 {
+        game_state.forfeit_on_time();
+    }
+}
+
+pub fn update_turn_clock_text(
+    clock: Res<TurnClock>,
+    game_state: Res<GameState>,
+    mut text_query: Query<&mut Text, With<TurnClockText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    match clock.limit {
+        Some(_) if !game_state.game_over => {
+            let who = game_state.player_name(game_state.current_player);
+            text.sections[0].value = format!("{}: {:.1}s", who, clock.remaining);
+            // Warn the player when their time is nearly up
+            text.sections[0].style.color = if clock.remaining < 5.0 {
+                Color::srgb(0.9, 0.3, 0.2)
+            } else {
+                Color::WHITE
+            };
+        }
+        _ => text.sections[0].value.clear(),
+    }
+}
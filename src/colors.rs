@@ -0,0 +1,257 @@
+use bevy::color::Oklaba;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use ttt3d_core::game::Player;
+use crate::mods::{PiecePack, PiecePacks};
+use crate::settings::{Profile, Settings, SettingsPanel};
+
+// Piece colors players can pick in place of their piece pack's
+pub const PIECE_COLORS: [(&str, [f32; 3]); 9] = [
+    ("Green", [0.2, 0.7, 0.2]),
+    ("Red", [0.7, 0.2, 0.2]),
+    ("Blue", [0.2, 0.4, 0.8]),
+    ("Orange", [0.95, 0.55, 0.1]),
+    ("Purple", [0.55, 0.25, 0.75]),
+    ("Yellow", [0.95, 0.85, 0.2]),
+    ("Cyan", [0.2, 0.8, 0.85]),
+    ("Pink", [0.95, 0.45, 0.7]),
+    ("White", [0.95, 0.95, 0.95]),
+];
+
+// Smallest perceptual (Oklab) distance a piece color must keep from the other players' pieces,
+// and from the empty cells, blocked cells and background. Green and red are 0.35 apart;
+// blue and purple, at 0.15, are too easily mixed up across the board
+const MIN_PLAYER_DISTANCE: f32 = 0.17;
+const MIN_BOARD_DISTANCE: f32 = 0.16;
+
+// Sky blue, orange and yellow stay apart for red-green and blue-yellow color blindness alike,
+// and apart from every theme's cells and background
+pub const COLORBLIND_PIECE_COLORS: [[f32; 3]; 3] = [[0.35, 0.7, 0.95], [0.9, 0.6, 0.0], [0.94, 0.89, 0.26]];
+
+const PLAYERS: [Player; 3] = [Player::Human, Player::AI, Player::Third];
+
+// The look of the whole board. Classic keeps the piece pack's own colors; the others replace
+// them, along with the cells, the hover highlight and the background
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorTheme {
+    #[default]
+    Classic,
+    Neon,
+    Pastel,
+    HighContrast,
+}
+
+// The colors a theme puts in place of the pack's, where it has its own
+pub struct Palette {
+    pub pieces: Option<[[f32; 3]; 3]>,
+    pub neutral: Option<[f32; 3]>,
+    pub empty: Option<[f32; 4]>,
+    // The hovered cell's outline
+    pub hovered: [f32; 4],
+    pub background: Color,
+    // Share of each piece's color it gives off as light
+    pub glow: f32,
+}
+
+impl Palette {
+    pub fn piece_colors(&self, pack: &PiecePack) -> [[f32; 3]; 3] {
+        self.pieces.unwrap_or_else(|| pack.piece_colors())
+    }
+
+    pub fn empty_color(&self, pack: &PiecePack) -> [f32; 4] {
+        self.empty.unwrap_or_else(|| pack.empty_color())
+    }
+
+    pub fn board(&self, pack: &PiecePack) -> Board {
+        Board::new(self.empty_color(pack), self.background)
+    }
+}
+
+impl ColorTheme {
+    // The theme the board is drawn in: high-contrast mode stands in for the chosen one
+    pub fn in_use(settings: &Settings) -> ColorTheme {
+        if settings.high_contrast { ColorTheme::HighContrast } else { settings.color_theme }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorTheme::Classic => "Classic",
+            ColorTheme::Neon => "Neon",
+            ColorTheme::Pastel => "Pastel",
+            ColorTheme::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn next(self) -> ColorTheme {
+        match self {
+            ColorTheme::Classic => ColorTheme::Neon,
+            ColorTheme::Neon => ColorTheme::Pastel,
+            ColorTheme::Pastel => ColorTheme::HighContrast,
+            ColorTheme::HighContrast => ColorTheme::Classic,
+        }
+    }
+
+    // Each theme's pieces keep the contrast checks' distances from each other and the board
+    pub fn palette(self) -> Palette {
+        match self {
+            ColorTheme::Classic => Palette {
+                pieces: None,
+                neutral: None,
+                empty: None,
+                hovered: [0.6, 0.6, 0.6, 0.8],
+                background: ClearColor::default().0,
+                glow: 0.0,
+            },
+            ColorTheme::Neon => Palette {
+                pieces: Some([[0.2, 1.0, 0.4], [1.0, 0.15, 0.6], [0.2, 0.7, 1.0]]),
+                neutral: Some([1.0, 0.9, 0.3]),
+                empty: Some([0.25, 0.15, 0.45, 0.35]),
+                hovered: [0.7, 0.5, 1.0, 0.8],
+                background: Color::srgb(0.02, 0.0, 0.06),
+                glow: 0.6,
+            },
+            ColorTheme::Pastel => Palette {
+                pieces: Some([[0.55, 0.85, 0.6], [0.95, 0.6, 0.6], [0.6, 0.7, 0.98]]),
+                neutral: Some([0.95, 0.9, 0.7]),
+                empty: Some([0.5, 0.5, 0.58, 0.3]),
+                hovered: [0.9, 0.85, 0.95, 0.8],
+                background: Color::srgb(0.2, 0.2, 0.26),
+                glow: 0.0,
+            },
+            ColorTheme::HighContrast => Palette {
+                pieces: Some([[0.0, 0.9, 0.0], [1.0, 0.1, 0.1], [0.1, 0.4, 1.0]]),
+                neutral: Some([1.0, 1.0, 1.0]),
+                empty: Some([0.12, 0.12, 0.12, 1.0]),
+                hovered: [1.0, 1.0, 0.6, 1.0],
+                background: Color::BLACK,
+                glow: 0.0,
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PieceColorLabel(pub Player);
+
+// The board colors pieces must stand out from
+pub struct Board {
+    pub empty: [f32; 3],
+    pub blocked: [f32; 3],
+    pub background: [f32; 3],
+}
+
+impl Board {
+    // Empty cells are drawn see-through, but judging them by their own color is close enough
+    pub fn new(empty: [f32; 4], background: Color) -> Self {
+        let background = background.to_srgba();
+        Self {
+            empty: [empty[0], empty[1], empty[2]],
+            // Matches the blocked cell material in graphics.rs
+            blocked: [0.05, 0.05, 0.05],
+            background: [background.red, background.green, background.blue],
+        }
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let a = Oklaba::from(Color::srgb(a[0], a[1], a[2]));
+    let b = Oklaba::from(Color::srgb(b[0], b[1], b[2]));
+    ((a.lightness - b.lightness).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+}
+
+pub fn color_name(rgb: [f32; 3]) -> &'static str {
+    PIECE_COLORS
+        .iter()
+        .find(|(_, preset)| preset.iter().zip(rgb).all(|(a, b)| (a - b).abs() < 0.01))
+        .map_or("Custom", |(name, _)| name)
+}
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::Human => "your",
+        Player::AI => "the AI's",
+        Player::Third => "the third player's",
+    }
+}
+
+// Why `color` can't be used for `player`'s pieces, if it can't; `colors` are every player's
+// current piece colors, indexed by `Player::index`
+pub fn contrast_problem(player: Player, color: [f32; 3], colors: &[[f32; 3]; 3], board: &Board) -> Option<String> {
+    for other in PLAYERS.into_iter().filter(|&other| other != player) {
+        if distance(color, colors[other.index()]) < MIN_PLAYER_DISTANCE {
+            return Some(format!("too close to {} pieces", player_name(other)));
+        }
+    }
+    let surroundings = [(board.empty, "the empty cells"), (board.blocked, "the blocked cells"), (board.background, "the background")];
+    surroundings
+        .into_iter()
+        .find(|&(surrounding, _)| distance(color, surrounding) < MIN_BOARD_DISTANCE)
+        .map(|(_, name)| format!("too close to {}", name))
+}
+
+// Each player's piece color: their choice where it passes the contrast check, otherwise the
+// pack's. Choices are checked from the third player up, so the human's wins a clash
+pub fn resolve_piece_colors(pack: [[f32; 3]; 3], choices: [Option<[f32; 3]>; 3], board: &Board) -> [[f32; 3]; 3] {
+    let mut colors: [[f32; 3]; 3] = std::array::from_fn(|i| choices[i].unwrap_or(pack[i]));
+    for player in PLAYERS.into_iter().rev() {
+        let i = player.index();
+        let Some(choice) = choices[i] else {
+            continue;
+        };
+        if let Some(problem) = contrast_problem(player, choice, &colors, board) {
+            warn!("Ignoring {} piece color {}: {}", player_name(player), color_name(choice), problem);
+            colors[i] = pack[i];
+        }
+    }
+    colors
+}
+
+pub fn piece_color_label(player: Player, choice: Option<[f32; 3]>) -> String {
+    let (who, key) = match player {
+        Player::Human => ("Your pieces", 1),
+        Player::AI => ("AI pieces", 2),
+        Player::Third => ("Third player pieces", 3),
+    };
+    let color = choice.map_or("Pack color", color_name);
+    format!("{}: {} [{}]", who, color, key)
+}
+
+// 1, 2 and 3 step each player's piece color through the presets and back to the pack's,
+// skipping any that would clash with another player's pieces or the board
+pub fn cycle_piece_colors(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    packs: Res<PiecePacks>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<(&mut Text, &PieceColorLabel)>,
+) {
+    if !panel.open {
+        return;
+    }
+    let keys = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3];
+    let Some(player) = PLAYERS.into_iter().find(|player| keyboard.just_pressed(keys[player.index()])) else {
+        return;
+    };
+
+    let pack = packs.get(&settings.piece_pack).unwrap_or(&packs.packs[0]);
+    let palette = settings.color_theme.palette();
+    let board = palette.board(pack);
+    let colors = resolve_piece_colors(palette.piece_colors(pack), settings.piece_colors, &board);
+
+    // The pack's color sits at the end of the cycle and is always allowed
+    let i = player.index();
+    let current = settings.piece_colors[i].and_then(|rgb| PIECE_COLORS.iter().position(|(name, _)| *name == color_name(rgb)));
+    let start = current.map_or(0, |index| index + 1);
+    settings.piece_colors[i] = PIECE_COLORS[start.min(PIECE_COLORS.len())..]
+        .iter()
+        .map(|&(_, rgb)| rgb)
+        .find(|&rgb| contrast_problem(player, rgb, &colors, &board).is_none());
+    settings.save(&profile);
+
+    for (mut text, label) in label_query.iter_mut() {
+        if label.0 == player {
+            text.sections[0].value = piece_color_label(player, settings.piece_colors[i]);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use ttt3d_core::game::Position;
+use crate::graphics::{cell_position, CameraController};
+use crate::settings::SettingsPanel;
+use crate::slice::LayerSlice;
+
+const LABEL_WIDTH: f32 = 60.0;
+const LABEL_HEIGHT: f32 = 16.0;
+
+const CELL_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+// Labels on the far side of the board fade to this share of full strength
+const FAR_ALPHA: f32 = 0.35;
+const AXIS_COLORS: [Color; 3] = [Color::srgb(1.0, 0.45, 0.45), Color::srgb(0.45, 1.0, 0.45), Color::srgb(0.5, 0.65, 1.0)];
+const AXIS_NAMES: [&str; 3] = ["x", "y", "z"];
+// How far outside the board, along both other axes, the axes are drawn from the (0, 0, 0) corner
+const AXIS_OFFSET: f32 = 1.6;
+
+// N labels every cell with its coordinates as moves are written, "(x, y, z)" counting from 0,
+// and draws the three axes along the edges of the board from the (0, 0, 0) corner
+#[derive(Resource, Default)]
+pub struct CoordinateOverlay {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+pub enum CoordinateLabel {
+    Cell(Position),
+    // An axis and the coordinate along it
+    Axis(usize, usize),
+}
+
+impl CoordinateLabel {
+    fn world_position(&self) -> Vec3 {
+        match *self {
+            CoordinateLabel::Cell((x, y, z)) => cell_position(x, y, z),
+            CoordinateLabel::Axis(axis, value) => axis_point(axis, value as f32),
+        }
+    }
+}
+
+// The point `value` cells along `axis`'s drawn line, which runs beside the board's edge
+fn axis_point(axis: usize, value: f32) -> Vec3 {
+    let corner = cell_position(0, 0, 0) - Vec3::splat(AXIS_OFFSET);
+    let mut point = corner;
+    point[axis] = (value - 1.0) * 2.0;
+    point
+}
+
+pub fn setup_coordinate_overlay(mut commands: Commands) {
+    let mut spawn_label = |label: CoordinateLabel, text: String, color: Color| {
+        commands.spawn((
+            TextBundle {
+                visibility: Visibility::Hidden,
+                ..TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 13.0,
+                        color,
+                        ..default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center)
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(LABEL_WIDTH),
+                    ..default()
+                })
+            },
+            label,
+        ));
+    };
+
+    for x in 0..3 {
+        for y in 0..3 {
+            for z in 0..3 {
+                spawn_label(CoordinateLabel::Cell((x, y, z)), format!("({}, {}, {})", x, y, z), CELL_COLOR);
+            }
+        }
+    }
+    for (axis, name) in AXIS_NAMES.iter().enumerate() {
+        for value in 0..3 {
+            spawn_label(CoordinateLabel::Axis(axis, value), format!("{} {}", name, value), AXIS_COLORS[axis]);
+        }
+    }
+}
+
+pub fn toggle_coordinate_overlay(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut overlay: ResMut<CoordinateOverlay>,
+) {
+    // N in the settings panel is one of its own options
+    if !panel.open && keyboard.just_pressed(KeyCode::KeyN) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+// Pin each label over its point, fainter the further it is from the camera so the near side of
+// the board reads first. Cells peeled away keep no label
+pub fn update_coordinate_labels(
+    overlay: Res<CoordinateOverlay>,
+    slice: Res<LayerSlice>,
+    ui_scale: Res<UiScale>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    mut label_query: Query<(&CoordinateLabel, &mut Text, &mut Style, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    // The board's nearest and furthest corners, to scale the fading between
+    let eye = camera_transform.translation();
+    let reach = cell_position(0, 0, 0).length();
+    let (near, far) = (eye.length() - reach, eye.length() + reach);
+
+    for (label, mut text, mut style, mut visibility) in label_query.iter_mut() {
+        let peeled = matches!(*label, CoordinateLabel::Cell(position) if slice.hides(position));
+        let world = label.world_position();
+        let screen = camera.world_to_viewport(camera_transform, world).filter(|_| overlay.enabled && !peeled);
+        let Some(screen) = screen else {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        };
+
+        *visibility = Visibility::Visible;
+        style.left = Val::Px(screen.x / ui_scale.0 - LABEL_WIDTH / 2.0);
+        style.top = Val::Px(screen.y / ui_scale.0 - LABEL_HEIGHT / 2.0);
+        let depth = ((eye.distance(world) - near) / (far - near)).clamp(0.0, 1.0);
+        let color = text.sections[0].style.color;
+        text.sections[0].style.color = color.with_alpha(1.0 - (1.0 - FAR_ALPHA) * depth);
+    }
+}
+
+#[allow(clippy::needless_range_loop)]
+pub fn draw_coordinate_axes(overlay: Res<CoordinateOverlay>, mut gizmos: Gizmos) {
+    if !overlay.enabled {
+        return;
+    }
+
+    for axis in 0..3 {
+        gizmos.arrow(axis_point(axis, -0.5), axis_point(axis, 2.7), AXIS_COLORS[axis]);
+    }
+}
@@ -0,0 +1,216 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use crate::modal::{spawn_modal, swallow_input, PANEL_PADDING};
+use crate::settings::SettingsPanel;
+
+// Contributors and bundled assets, kept by hand, and every third-party crate with its license,
+// listed from Cargo.lock by build.rs. Both are built into the binary
+const CREDITS: &str = include_str!("../assets/credits.ron");
+const CRATE_LICENSES: &str = include_str!(concat!(env!("OUT_DIR"), "/crate_licenses.txt"));
+
+pub const CREDITS_LABEL: &str = "Credits and licenses [,]";
+
+// Pixels scrolled by an arrow key or a notch of the mouse wheel, and by Page Up or Page Down
+const SCROLL_STEP: f32 = 40.0;
+const SCROLL_PAGE: f32 = 400.0;
+
+const HEADING_COLOR: Color = Color::srgb(0.9, 0.8, 0.4);
+const BODY_COLOR: Color = Color::srgb(0.85, 0.85, 0.85);
+
+#[derive(Deserialize)]
+struct CreditsFile {
+    contributors: Vec<String>,
+    assets: Vec<AssetCredit>,
+}
+
+#[derive(Deserialize)]
+struct AssetCredit {
+    name: String,
+    source: String,
+    license: Option<String>,
+}
+
+#[derive(Resource, Default)]
+pub struct CreditsScreen {
+    pub open: bool,
+    // How far down the text has been scrolled, in UI pixels
+    scroll: f32,
+}
+
+impl CreditsScreen {
+    // Open from the top, from the settings panel or the main menu
+    pub fn show(&mut self) {
+        *self = CreditsScreen { open: true, scroll: 0.0 };
+    }
+}
+
+#[derive(Component)]
+pub struct CreditsScreenRoot;
+
+#[derive(Component)]
+pub struct CreditsText;
+
+// "MIT/Apache-2.0", "Apache-2.0 OR MIT" and "MIT OR Apache-2.0" are the same choice, so crates
+// offering it are listed together. Anything with AND, WITH or brackets is kept as written
+fn normalize_license(license: &str) -> String {
+    let license = license.replace(" / ", " OR ").replace('/', " OR ");
+    if license.contains(" AND ") || license.contains(" WITH ") || license.contains('(') {
+        return license;
+    }
+    let mut choices: Vec<&str> = license.split(" OR ").map(str::trim).collect();
+    choices.sort();
+    choices.dedup();
+    choices.join(" OR ")
+}
+
+fn heading(text: &str) -> TextSection {
+    TextSection::new(
+        format!("{}\n", text),
+        TextStyle {
+            font_size: 18.0,
+            color: HEADING_COLOR,
+            ..default()
+        },
+    )
+}
+
+fn body(text: String) -> TextSection {
+    TextSection::new(
+        format!("{}\n\n", text),
+        TextStyle {
+            font_size: 14.0,
+            color: BODY_COLOR,
+            ..default()
+        },
+    )
+}
+
+fn credits_sections() -> Vec<TextSection> {
+    let mut sections = vec![body("Up, Down, Page Up, Page Down or the mouse wheel to scroll - Esc to close".to_string())];
+
+    match ron::from_str::<CreditsFile>(CREDITS) {
+        Ok(credits) => {
+            sections.push(heading("Contributors"));
+            sections.push(body(credits.contributors.join("\n")));
+            sections.push(heading("Assets"));
+            let assets: Vec<String> = credits
+                .assets
+                .iter()
+                .map(|asset| {
+                    let license = asset.license.as_deref().unwrap_or("license not recorded");
+                    format!("{} - {} - {}", asset.name, asset.source, license)
+                })
+                .collect();
+            sections.push(body(assets.join("\n")));
+        }
+        Err(err) => warn!("Failed to read the built-in credits: {}", err),
+    }
+
+    let mut by_license: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for line in CRATE_LICENSES.lines() {
+        let mut fields = line.split('\t');
+        let (Some(name), Some(version), Some(license)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        by_license.entry(normalize_license(license)).or_default().push(format!("{} {}", name, version));
+    }
+    let crates: usize = by_license.values().map(Vec::len).sum();
+    sections.push(heading(&format!("Third-party crates ({})", crates)));
+    sections.push(body(
+        "The game is built on these open-source Rust crates, grouped by license. Their license texts come with \
+         each crate's source on crates.io"
+            .to_string(),
+    ));
+    // The licenses most of them share first
+    let mut groups: Vec<_> = by_license.into_iter().collect();
+    groups.sort_by_key(|(_, names)| std::cmp::Reverse(names.len()));
+    for (license, names) in groups {
+        sections.push(heading(&format!("{} ({})", license, names.len())));
+        sections.push(body(names.join(", ")));
+    }
+    sections
+}
+
+pub fn setup_credits_screen(mut commands: Commands) {
+    let text = spawn_modal(&mut commands, CreditsScreenRoot, CreditsText, 720.0);
+    commands.entity(text).insert(Text::from_sections(credits_sections()));
+}
+
+// , in the settings panel opens the screen, from the top
+pub fn open_credits_screen(keyboard: Res<ButtonInput<KeyCode>>, panel: Res<SettingsPanel>, mut credits: ResMut<CreditsScreen>) {
+    if panel.open && keyboard.just_pressed(KeyCode::Comma) {
+        credits.show();
+    }
+}
+
+// Scroll with the keys or the wheel, and close with Esc. Like the other screens, it takes every
+// key and click while it's up
+pub fn answer_credits_screen(
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut credits: ResMut<CreditsScreen>,
+) {
+    if !credits.open {
+        wheel.clear();
+        return;
+    }
+
+    let keys = [
+        (KeyCode::ArrowUp, -SCROLL_STEP),
+        (KeyCode::ArrowDown, SCROLL_STEP),
+        (KeyCode::PageUp, -SCROLL_PAGE),
+        (KeyCode::PageDown, SCROLL_PAGE),
+    ];
+    let mut scroll: f32 = keys.iter().filter(|(key, _)| keyboard.just_pressed(*key)).map(|(_, step)| step).sum();
+    for event in wheel.read() {
+        scroll -= match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_STEP,
+            MouseScrollUnit::Pixel => event.y,
+        };
+    }
+    let close = keyboard.just_pressed(KeyCode::Escape);
+    swallow_input(&mut keyboard, &mut mouse);
+
+    if close {
+        credits.open = false;
+    }
+    if scroll != 0.0 {
+        credits.scroll = (credits.scroll + scroll).max(0.0);
+    }
+}
+
+// Show the screen and move the text up by the scroll, which stops once the end of the text is in
+// view
+pub fn update_credits_screen(
+    mut credits: ResMut<CreditsScreen>,
+    mut root_query: Query<&mut Visibility, With<CreditsScreenRoot>>,
+    viewport_query: Query<&Node>,
+    mut text_query: Query<(&Node, &Parent, &mut Style), With<CreditsText>>,
+) {
+    for mut visibility in root_query.iter_mut() {
+        let shown = if credits.open { Visibility::Visible } else { Visibility::Hidden };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+    // The text scrolls within the modal's panel
+    let Ok((text, panel, mut style)) = text_query.get_single_mut() else {
+        return;
+    };
+    let Ok(viewport) = viewport_query.get(panel.get()) else {
+        return;
+    };
+
+    // The panel's height includes its padding, top and bottom
+    let end = (text.size().y - viewport.size().y + 2.0 * PANEL_PADDING).max(0.0);
+    if credits.scroll > end {
+        credits.scroll = end;
+    }
+    let top = Val::Px(-credits.scroll);
+    if style.top != top {
+        style.top = top;
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{CellState, GamePhase, GameState, Position};
+use crate::graphics::{cell_position, CameraController};
+use crate::quiz::{DifficultyQuiz, QuizStage};
+use crate::settings::SettingsPanel;
+use crate::slice::LayerSlice;
+use crate::tournament::Tournament;
+
+const CURSOR_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+
+// Which way on screen a key or pad button moves the cell cursor
+#[derive(Clone, Copy)]
+enum ScreenDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+    // Into the screen, away from the camera, and back out towards it
+    Away,
+    Towards,
+}
+
+const KEYS: [(KeyCode, ScreenDirection); 6] = [
+    (KeyCode::ArrowLeft, ScreenDirection::Left),
+    (KeyCode::ArrowRight, ScreenDirection::Right),
+    (KeyCode::ArrowUp, ScreenDirection::Up),
+    (KeyCode::ArrowDown, ScreenDirection::Down),
+    (KeyCode::PageUp, ScreenDirection::Away),
+    (KeyCode::PageDown, ScreenDirection::Towards),
+];
+
+const PAD_BUTTONS: [(GamepadButtonType, ScreenDirection); 6] = [
+    (GamepadButtonType::DPadLeft, ScreenDirection::Left),
+    (GamepadButtonType::DPadRight, ScreenDirection::Right),
+    (GamepadButtonType::DPadUp, ScreenDirection::Up),
+    (GamepadButtonType::DPadDown, ScreenDirection::Down),
+    (GamepadButtonType::RightTrigger, ScreenDirection::Away),
+    (GamepadButtonType::LeftTrigger, ScreenDirection::Towards),
+];
+
+impl ScreenDirection {
+    fn in_world(self, camera: &GlobalTransform) -> Vec3 {
+        match self {
+            ScreenDirection::Left => *camera.left(),
+            ScreenDirection::Right => *camera.right(),
+            ScreenDirection::Up => *camera.up(),
+            ScreenDirection::Down => *camera.down(),
+            ScreenDirection::Away => *camera.forward(),
+            ScreenDirection::Towards => *camera.back(),
+        }
+    }
+}
+
+// One cell along whichever board axis points most nearly `direction`, staying on the board
+fn step(position: Position, direction: Vec3) -> Position {
+    let axis = (0..3).max_by(|&a, &b| direction[a].abs().total_cmp(&direction[b].abs())).unwrap_or(0);
+    let mut coordinates = [position.0, position.1, position.2];
+    coordinates[axis] = if direction[axis] > 0.0 {
+        (coordinates[axis] + 1).min(2)
+    } else {
+        coordinates[axis].saturating_sub(1)
+    };
+    (coordinates[0], coordinates[1], coordinates[2])
+}
+
+// The arrow keys or a pad's d-pad move the selected cell the way they point on screen,
+// worked out from the camera at each press, so "right" is whatever looks right from where the
+// camera has turned to; Page Up and Page Down, or the triggers, go into and out of the screen.
+// The selection stays put while the camera turns. Enter or the pad's south button plays the
+// selected cell on the human's turn, as a click would
+#[allow(clippy::too_many_arguments)]
+pub fn move_cell_cursor(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    pad_buttons: Res<ButtonInput<GamepadButton>>,
+    panel: Res<SettingsPanel>,
+    tournament: Res<Tournament>,
+    quiz: Res<DifficultyQuiz>,
+    slice: Res<LayerSlice>,
+    camera_query: Query<&GlobalTransform, With<CameraController>>,
+    mut game_state: ResMut<GameState>,
+) {
+    if panel.open || tournament.screen_open {
+        return;
+    }
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+
+    let pressed = |(key, pad): (Option<KeyCode>, Option<GamepadButtonType>)| {
+        key.is_some_and(|key| keyboard.just_pressed(key))
+            || pad.is_some_and(|pad| gamepads.iter().any(|gamepad| pad_buttons.just_pressed(GamepadButton::new(gamepad, pad))))
+    };
+    let moves = KEYS
+        .iter()
+        .map(|&(key, direction)| (Some(key), None, direction))
+        .chain(PAD_BUTTONS.iter().map(|&(pad, direction)| (None, Some(pad), direction)))
+        .filter(|&(key, pad, _)| pressed((key, pad)));
+    for (_, _, direction) in moves {
+        // The first press picks the centre, where every line can be reached in a step or two
+        let position = match game_state.selected_cube {
+            Some(position) => step(position, direction.in_world(camera)),
+            None => (1, 1, 1),
+        };
+        game_state.selected_cube = Some(position);
+    }
+
+    // The quiz's own prompts take Enter
+    let quiz_prompt = matches!(quiz.stage, QuizStage::Offered | QuizStage::Finished(_));
+    let play = (pressed((Some(KeyCode::Enter), None)) && !quiz_prompt) || pressed((None, Some(GamepadButtonType::South)));
+    let Some((x, y, z)) = game_state.selected_cube.filter(|_| play) else {
+        return;
+    };
+    if game_state.game_over
+        || !game_state.is_human_turn()
+        || game_state.phase == GamePhase::Editing
+        || game_state.board[x][y][z] != CellState::Empty
+        || slice.hides((x, y, z))
+    {
+        return;
+    }
+    game_state.make_move(x, y, z);
+}
+
+// An outline round the selected cell, whatever is in it
+pub fn draw_cell_cursor(game_state: Res<GameState>, slice: Res<LayerSlice>, mut gizmos: Gizmos) {
+    let Some((x, y, z)) = game_state.selected_cube.filter(|&position| !slice.hides(position)) else {
+        return;
+    };
+    gizmos.cuboid(Transform::from_translation(cell_position(x, y, z)).with_scale(Vec3::splat(0.95)), CURSOR_COLOR);
+}
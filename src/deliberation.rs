@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use rand::distributions::{Distribution, WeightedIndex};
+use ttt3d_core::game::{GameState, Position};
+use crate::graphics::{cell_position, CubeMaterials, PendingAiMove};
+use crate::settings::Settings;
+
+// How often a cell the search favors is picked to flash, and how long each flash takes to fade
+const SAMPLE_INTERVAL: f32 = 0.1;
+const FLASH_SECONDS: f32 = 0.3;
+const FLASH_SIZE: f32 = 0.9;
+
+// While the AI thinks for a set time, cells flash briefly in its piece color, each picked in
+// proportion to the visits the search has given it so far, so its attention can be seen moving
+// between the moves it's weighing. Only MCTS rates each move, and a search by difficulty is over
+// too quickly to be worth watching. Reduced motion turns the flashes off
+#[derive(Default)]
+pub struct Deliberation {
+    since_sample: f32,
+    // Cells flashing, with how long ago each was picked
+    flashes: Vec<(Position, f32)>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn flash_considered_cells(
+    time: Res<Time>,
+    pending: Res<PendingAiMove>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    cube_materials: Res<CubeMaterials>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut deliberation: Local<Deliberation>,
+    mut gizmos: Gizmos,
+) {
+    let thinking = pending.search.is_some() && settings.think_time_ms > 0 && !settings.reduced_motion;
+    if !thinking {
+        deliberation.since_sample = 0.0;
+        deliberation.flashes.clear();
+        return;
+    }
+
+    let delta = time.delta_seconds();
+    for (_, age) in deliberation.flashes.iter_mut() {
+        *age += delta;
+    }
+    deliberation.flashes.retain(|&(_, age)| age < FLASH_SECONDS);
+
+    // The live statistics are only the search's own once it has published them for this board
+    deliberation.since_sample += delta;
+    if deliberation.since_sample >= SAMPLE_INTERVAL {
+        deliberation.since_sample = 0.0;
+        let moves = game_state.ai.search_stats().filter(|stats| stats.board == game_state.board).map(|stats| stats.moves).unwrap_or_default();
+        if let Ok(weights) = WeightedIndex::new(moves.iter().map(|stats| stats.visits)) {
+            let picked = moves[weights.sample(&mut rand::thread_rng())].position;
+            deliberation.flashes.retain(|&(position, _)| position != picked);
+            deliberation.flashes.push((picked, 0.0));
+        }
+    }
+
+    let Some(color) = cube_materials
+        .piece(game_state.rules.piece(game_state.current_player))
+        .and_then(|handle| materials.get(&handle))
+        .map(|material| material.base_color)
+    else {
+        return;
+    };
+    for &((x, y, z), age) in &deliberation.flashes {
+        let fade = 1.0 - age / FLASH_SECONDS;
+        gizmos.cuboid(
+            Transform::from_translation(cell_position(x, y, z)).with_scale(Vec3::splat(FLASH_SIZE)),
+            color.with_alpha(fade),
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{CellState, GamePhase, GameState, Player, Position};
+use ttt3d_core::notation::{has_line, Board};
+use crate::graphics::{cell_position, CubeMarker, HoveredCube};
+use crate::puzzle::PuzzleMode;
+
+// Side to move and the latest result shown in the editor panel
+#[derive(Resource)]
+pub struct BoardEditor {
+    pub to_move: Player,
+    pub message: String,
+    // Engine's suggestion from the last "Analyze", highlighted on the board
+    pub analysis: Option<Position>,
+}
+
+impl Default for BoardEditor {
+    fn default() -> Self {
+        Self {
+            to_move: Player::Human,
+            message: String::new(),
+            analysis: None,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum EditorButton {
+    ToMove,
+    PlayFromHere,
+    Analyze,
+}
+
+#[derive(Component)]
+pub struct EditorRoot;
+
+#[derive(Component)]
+pub struct EditorMessageText;
+
+#[derive(Component)]
+pub struct EditorToMoveLabel;
+
+fn to_move_label(player: Player) -> String {
+    match player {
+        Player::AI => "To move: AI".to_string(),
+        _ => "To move: You".to_string(),
+    }
+}
+
+// Why a position can't be reached in a real game, if it can't
+fn position_error(board: &Board, to_move: Player) -> Option<&'static str> {
+    let count = |cell: CellState| board.iter().flatten().flatten().filter(|&&c| c == cell).count();
+    let (human, ai) = (count(CellState::Human), count(CellState::AI));
+    let (mover, other) = if to_move == Player::Human { (human, ai) } else { (ai, human) };
+
+    if human.abs_diff(ai) > 1 {
+        Some("Piece counts can differ by at most one")
+    } else if mover > other {
+        Some("The side to move can't have more pieces")
+    } else if has_line(board, CellState::Human) || has_line(board, CellState::AI) {
+        Some("A line is already complete")
+    } else if count(CellState::Empty) == 0 {
+        Some("The board is full")
+    } else {
+        None
+    }
+}
+
+pub fn setup_board_editor_ui(mut commands: Commands) {
+    let text_style = |size: f32| TextStyle {
+        font_size: size,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(90.0),
+                    left: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.85).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            EditorRoot,
+        ))
+        .with_children(|panel| {
+            panel.spawn(TextBundle::from_section(
+                "Board editor (F2 to close)\nClick a cell: empty -> you -> AI",
+                text_style(16.0),
+            ));
+            panel.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.9, 0.8, 0.4),
+                        ..default()
+                    },
+                ),
+                EditorMessageText,
+            ));
+
+            for (button, label) in [
+                (EditorButton::ToMove, to_move_label(Player::Human)),
+                (EditorButton::PlayFromHere, "Play from here".to_string()),
+                (EditorButton::Analyze, "Analyze".to_string()),
+            ] {
+                panel
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                                ..default()
+                            },
+                            background_color: Color::srgba(0.2, 0.2, 0.3, 0.85).into(),
+                            ..default()
+                        },
+                        button,
+                    ))
+                    .with_children(|parent| {
+                        let mut text = parent.spawn(TextBundle::from_section(label, text_style(16.0)));
+                        if button == EditorButton::ToMove {
+                            text.insert(EditorToMoveLabel);
+                        }
+                    });
+            }
+        });
+}
+
+// F2 opens the editor on an empty board, or closes it and starts a normal game
+pub fn toggle_board_editor(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<BoardEditor>,
+    mut puzzle: ResMut<PuzzleMode>,
+    mut game_state: ResMut<GameState>,
+) {
+    if !keyboard.just_pressed(KeyCode::F2) {
+        return;
+    }
+
+    if game_state.phase == GamePhase::Editing {
+        game_state.reset();
+        return;
+    }
+
+    puzzle.active = None;
+    *editor = BoardEditor::default();
+    game_state.start_editing();
+}
+
+pub fn edit_cells(
+    buttons: Res<ButtonInput<MouseButton>>,
+    hovered_cubes: Query<&CubeMarker, With<HoveredCube>>,
+    editor_buttons: Query<&Interaction, With<EditorButton>>,
+    mut editor: ResMut<BoardEditor>,
+    mut game_state: ResMut<GameState>,
+) {
+    if game_state.phase != GamePhase::Editing || !buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Clicks on the panel's buttons shouldn't also land on a cube behind them
+    if editor_buttons.iter().any(|interaction| *interaction != Interaction::None) {
+        return;
+    }
+
+    if let Some(cube) = hovered_cubes.iter().next() {
+        let next = match game_state.board[cube.x][cube.y][cube.z] {
+            CellState::Empty => CellState::Human,
+            CellState::Human => CellState::AI,
+            _ => CellState::Empty,
+        };
+        game_state.edit_cell(cube.x, cube.y, cube.z, next);
+        editor.analysis = None;
+        editor.message.clear();
+    }
+}
+
+pub fn handle_editor_buttons(
+    button_query: Query<(&Interaction, &EditorButton), Changed<Interaction>>,
+    mut editor: ResMut<BoardEditor>,
+    mut game_state: ResMut<GameState>,
+) {
+    if game_state.phase != GamePhase::Editing {
+        return;
+    }
+
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if *button == EditorButton::ToMove {
+            editor.to_move = if editor.to_move == Player::Human { Player::AI } else { Player::Human };
+            editor.analysis = None;
+            editor.message.clear();
+            continue;
+        }
+
+        let board = game_state.board;
+        if let Some(error) = position_error(&board, editor.to_move) {
+            editor.message = error.to_string();
+            continue;
+        }
+
+        if *button == EditorButton::PlayFromHere {
+            // Hand over to normal play against the AI
+            game_state.load_position(board, editor.to_move);
+            continue;
+        }
+
+        // Ask the engine for the side to move without touching the game being edited
+        let probe = GameState {
+            board,
+            current_player: editor.to_move,
+            ..default()
+        };
+        editor.analysis = game_state.ai.best_move(&probe, None);
+        editor.message = match editor.analysis {
+            Some((x, y, z)) => format!("Best move for {}: ({}, {}, {})", probe.player_name(editor.to_move), x, y, z),
+            None => "No moves left to analyze".to_string(),
+        };
+    }
+}
+
+pub fn update_board_editor_ui(
+    editor: Res<BoardEditor>,
+    game_state: Res<GameState>,
+    mut root_query: Query<&mut Visibility, With<EditorRoot>>,
+    mut message_query: Query<&mut Text, (With<EditorMessageText>, Without<EditorToMoveLabel>)>,
+    mut to_move_query: Query<&mut Text, With<EditorToMoveLabel>>,
+) {
+    if !editor.is_changed() && !game_state.is_changed() {
+        return;
+    }
+
+    let editing = game_state.phase == GamePhase::Editing;
+    for mut visibility in root_query.iter_mut() {
+        *visibility = if editing { Visibility::Visible } else { Visibility::Hidden };
+    }
+    for mut text in message_query.iter_mut() {
+        text.sections[0].value = editor.message.clone();
+    }
+    for mut text in to_move_query.iter_mut() {
+        text.sections[0].value = to_move_label(editor.to_move);
+    }
+}
+
+// Outline the cell under the cursor (pieces hide the hover color) and the engine's suggestion
+pub fn draw_editor_gizmos(
+    editor: Res<BoardEditor>,
+    game_state: Res<GameState>,
+    hovered_cubes: Query<&CubeMarker, With<HoveredCube>>,
+    mut gizmos: Gizmos,
+) {
+    if game_state.phase != GamePhase::Editing {
+        return;
+    }
+
+    for cube in hovered_cubes.iter() {
+        gizmos.cuboid(Transform::from_translation(cell_position(cube.x, cube.y, cube.z)), Color::WHITE);
+    }
+    if let Some((x, y, z)) = editor.analysis {
+        gizmos.cuboid(Transform::from_translation(cell_position(x, y, z)), Color::srgb(0.3, 1.0, 0.4));
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use ttt3d_core::ai::{MCTSAi, SearchState};
+use ttt3d_core::game::{GamePhase, GameState, Player};
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+
+// Rollouts per background batch, and the total after which the estimate is left alone
+const ROLLOUTS_PER_BATCH: u32 = 100;
+const MAX_ROLLOUTS: u32 = 3000;
+
+// How quickly the bar follows the estimate, per second; higher is snappier
+const SMOOTHING_RATE: f32 = 4.0;
+
+const BAR_HEIGHT: f32 = 200.0;
+
+// The human's chances against the AI, estimated from quick rollouts of the current position
+// on the async compute pool. Batches keep adding up while the position stands, so the estimate
+// sharpens until it is replaced
+#[derive(Resource)]
+pub struct Evaluation {
+    position: Option<SearchState>,
+    // Sum of the human's rollout rewards (1 win, 0 draw, -1 loss) and how many there were
+    total_reward: f64,
+    rollouts: u32,
+    batch: Option<Task<(f64, u32)>>,
+    // The bar's current reading, easing towards the estimate
+    pub shown: f32,
+}
+
+impl Default for Evaluation {
+    fn default() -> Self {
+        Self {
+            position: None,
+            total_reward: 0.0,
+            rollouts: 0,
+            batch: None,
+            shown: 0.5,
+        }
+    }
+}
+
+impl Evaluation {
+    // Chance of the human winning, with draws counting half
+    fn estimate(&self) -> Option<f32> {
+        (self.rollouts > 0).then(|| ((self.total_reward / self.rollouts as f64 + 1.0) / 2.0) as f32)
+    }
+}
+
+#[derive(Component)]
+pub struct EvaluationBar;
+
+#[derive(Component)]
+pub struct EvaluationFill;
+
+#[derive(Component)]
+pub struct EvaluationText;
+
+// A vertical bar on the left edge: the human's share fills from the bottom in their color
+pub fn setup_evaluation_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(10.0),
+                    top: Val::Percent(35.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            EvaluationBar,
+        ))
+        .with_children(|column| {
+            column
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(16.0),
+                        height: Val::Px(BAR_HEIGHT),
+                        flex_direction: FlexDirection::ColumnReverse,
+                        ..default()
+                    },
+                    background_color: Color::srgb(0.7, 0.2, 0.2).into(),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(50.0),
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.7, 0.2).into(),
+                            ..default()
+                        },
+                        EvaluationFill,
+                    ));
+                });
+            column.spawn((
+                TextBundle::from_section(
+                    "50%",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                EvaluationText,
+            ));
+        });
+}
+
+// Whether the bar has anything meaningful to show: a two-player game against the AI, outside
+// the editor, and not while puzzles or the quiz are testing the player
+fn shows_evaluation(game_state: &GameState, puzzle: &PuzzleMode, quiz: &DifficultyQuiz) -> bool {
+    game_state.phase != GamePhase::Editing
+        && game_state.rules.player_count() == 2
+        && !puzzle.is_solving()
+        && !quiz.is_asking()
+}
+
+pub fn run_evaluation(
+    game_state: Res<GameState>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut evaluation: ResMut<Evaluation>,
+) {
+    if !shows_evaluation(&game_state, &puzzle, &quiz) || game_state.game_over {
+        evaluation.batch = None;
+        return;
+    }
+
+    // A new position drops the old estimate, and any batch still running for it
+    let position = SearchState::from_game(&game_state);
+    if evaluation.position.as_ref() != Some(&position) {
+        evaluation.position = Some(position.clone());
+        evaluation.total_reward = 0.0;
+        evaluation.rollouts = 0;
+        evaluation.batch = None;
+    }
+
+    if let Some(batch) = evaluation.batch.as_mut() {
+        let Some((total_reward, rollouts)) = block_on(future::poll_once(batch)) else {
+            return;
+        };
+        evaluation.batch = None;
+        evaluation.total_reward += total_reward;
+        evaluation.rollouts += rollouts;
+    }
+
+    if evaluation.rollouts >= MAX_ROLLOUTS {
+        return;
+    }
+    let rules = game_state.rules;
+    evaluation.batch = Some(AsyncComputeTaskPool::get().spawn(async move {
+        let ai = MCTSAi::new();
+        let mut rng = ai.search_rng(&position);
+        let total: f64 = (0..ROLLOUTS_PER_BATCH)
+            .map(|_| ai.simulate_smart_game(position.clone(), rules, &mut rng)[Player::Human.index()])
+            .sum();
+        (total, ROLLOUTS_PER_BATCH)
+    }));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_evaluation_bar(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut evaluation: ResMut<Evaluation>,
+    mut bar_query: Query<&mut Visibility, With<EvaluationBar>>,
+    mut fill_query: Query<&mut Style, With<EvaluationFill>>,
+    mut text_query: Query<&mut Text, With<EvaluationText>>,
+) {
+    let visible = shows_evaluation(&game_state, &puzzle, &quiz);
+    for mut visibility in bar_query.iter_mut() {
+        let wanted = if visible { Visibility::Visible } else { Visibility::Hidden };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+    if !visible {
+        return;
+    }
+
+    // A finished game needs no estimate
+    let target = if game_state.game_over {
+        match game_state.winner {
+            Some(Player::Human) => 1.0,
+            Some(_) => 0.0,
+            None => 0.5,
+        }
+    } else {
+        evaluation.estimate().unwrap_or(evaluation.shown)
+    };
+
+    // Ease towards the estimate so one noisy batch doesn't make the bar jump
+    let blend = 1.0 - (-SMOOTHING_RATE * time.delta_seconds()).exp();
+    let shown = evaluation.shown + (target - evaluation.shown) * blend;
+    if (shown - evaluation.shown).abs() < 1e-4 {
+        return;
+    }
+    evaluation.shown = shown;
+
+    for mut style in fill_query.iter_mut() {
+        style.height = Val::Percent(shown * 100.0);
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!("{:.0}%", shown * 100.0);
+    }
+}
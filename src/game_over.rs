@@ -0,0 +1,252 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{GameOverReason, GameState, Player};
+use crate::analysis::AnalysisJob;
+use crate::graphics::ResetRequested;
+use crate::menu::AppState;
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+use crate::settings::Profile;
+use crate::vault::ProfileVault;
+
+// The card shown over the board once a game ends, with what happened and what to do next
+#[derive(Resource, Default)]
+pub struct GameOverOverlay {
+    generation: u64,
+    // Seconds spent playing the game, not counting pauses or menus
+    elapsed: f32,
+    // Put away to review the game
+    dismissed: bool,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum GameOverButton {
+    Rematch,
+    Review,
+    MainMenu,
+}
+
+#[derive(Component)]
+pub struct GameOverRoot;
+
+#[derive(Component)]
+pub struct GameOverText;
+
+#[derive(Component)]
+pub struct RematchLabel;
+
+pub fn setup_game_over_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(60.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(6),
+                ..default()
+            },
+            GameOverRoot,
+        ))
+        .with_children(|root| {
+            // Kept low on the screen, so the board and the banner above it stay in view
+            root.spawn(NodeBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(16.0)),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.9).into(),
+                ..default()
+            })
+            .with_children(|card| {
+                card.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 22.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    )
+                    .with_text_justify(JustifyText::Center),
+                    GameOverText,
+                ));
+                card.spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (button, label) in [
+                        (GameOverButton::Rematch, "Rematch"),
+                        (GameOverButton::Review, "Review Game"),
+                        (GameOverButton::MainMenu, "Main Menu"),
+                    ] {
+                        row.spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                                    ..default()
+                                },
+                                background_color: Color::srgba(0.2, 0.2, 0.3, 0.85).into(),
+                                ..default()
+                            },
+                            button,
+                        ))
+                        .with_children(|parent| {
+                            let mut text = parent.spawn(TextBundle::from_section(
+                                label,
+                                TextStyle {
+                                    font_size: 18.0,
+                                    color: Color::WHITE,
+                                    ..default()
+                                },
+                            ));
+                            if button == GameOverButton::Rematch {
+                                text.insert(RematchLabel);
+                            }
+                        });
+                    }
+                });
+            });
+        });
+}
+
+// Time each game while it's being played, and bring the card back for a new one
+pub fn time_game(
+    time: Res<Time>,
+    state: Res<State<AppState>>,
+    game_state: Res<GameState>,
+    mut overlay: ResMut<GameOverOverlay>,
+) {
+    if overlay.generation != game_state.generation {
+        *overlay = GameOverOverlay {
+            generation: game_state.generation,
+            ..default()
+        };
+    }
+    if *state.get() == AppState::Playing && !game_state.game_over {
+        overlay.elapsed += time.delta_seconds();
+    }
+}
+
+// Who starts the rematch: the next player round from whoever started this game
+fn rematch_starter(game_state: &GameState) -> Player {
+    game_state.first_player.next(game_state.rules.player_count())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn press_game_over_buttons(
+    button_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    profile: Res<Profile>,
+    vault: Res<ProfileVault>,
+    mut game_state: ResMut<GameState>,
+    mut job: ResMut<AnalysisJob>,
+    mut overlay: ResMut<GameOverOverlay>,
+    mut resets: EventWriter<ResetRequested>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            // The first move changes hands, and stays with them for the games after until the
+            // next rematch or a new game from the main menu
+            GameOverButton::Rematch => {
+                game_state.first_player = rematch_starter(&game_state);
+                resets.send(ResetRequested);
+            }
+            // The same as Z: the card makes way for the analysis panel
+            GameOverButton::Review => {
+                if job.checkpoint.is_none() {
+                    job.start(&game_state, &profile, &vault);
+                }
+                overlay.dismissed = true;
+            }
+            GameOverButton::MainMenu => next_state.set(AppState::MainMenu),
+        }
+    }
+}
+
+fn result_line(game_state: &GameState) -> String {
+    let winner = game_state.winner.map(|winner| match winner {
+        Player::Human => "You win".to_string(),
+        player => format!("{} wins", game_state.player_name(player)),
+    });
+    let scores = || {
+        let players = [Player::Human, Player::AI, Player::Third];
+        let scores: Vec<String> = players[..game_state.rules.player_count()]
+            .iter()
+            .map(|player| game_state.scores[player.index()].to_string())
+            .collect();
+        scores.join(" - ")
+    };
+    let loser = game_state.player_name(game_state.current_player);
+    match (winner, game_state.game_over_reason) {
+        (Some(winner), Some(GameOverReason::Time)) => format!("{} - {} ran out of time", winner, loser),
+        (Some(winner), Some(GameOverReason::Resignation)) => format!("{} - {} resigned", winner, loser),
+        (Some(winner), Some(GameOverReason::Scores | GameOverReason::Passes)) if game_state.rules.line_scoring => {
+            format!("{} on lines, {}", winner, scores())
+        }
+        // Under Notakto the player who completed the line is the one left to move
+        (Some(winner), _) if game_state.rules.notakto => format!("{} - {} completed a line", winner, loser),
+        (Some(winner), _) => format!("{} with a line", winner),
+        (None, Some(GameOverReason::DrawAgreed)) => "Draw agreed".to_string(),
+        (None, Some(GameOverReason::Passes)) if !game_state.rules.line_scoring => "Draw - everyone passed".to_string(),
+        (None, _) if game_state.rules.line_scoring => format!("Draw on lines, {}", scores()),
+        (None, _) => "Draw - the board is full".to_string(),
+    }
+}
+
+// Shown in GameOver until put away for a review. Puzzles and the quiz have their own endings
+#[allow(clippy::too_many_arguments)]
+pub fn update_game_over_overlay(
+    state: Res<State<AppState>>,
+    game_state: Res<GameState>,
+    overlay: Res<GameOverOverlay>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut root_query: Query<&mut Visibility, With<GameOverRoot>>,
+    mut text_query: Query<&mut Text, (With<GameOverText>, Without<RematchLabel>)>,
+    mut rematch_query: Query<&mut Text, With<RematchLabel>>,
+) {
+    let shown = *state.get() == AppState::GameOver && !overlay.dismissed && puzzle.active.is_none() && !quiz.is_asking();
+    for mut visibility in root_query.iter_mut() {
+        let visibility_now = if shown { Visibility::Visible } else { Visibility::Hidden };
+        if *visibility != visibility_now {
+            *visibility = visibility_now;
+        }
+    }
+    if !shown || !(state.is_changed() || game_state.is_changed()) {
+        return;
+    }
+
+    let seconds = overlay.elapsed as u32;
+    let message = format!(
+        "{}\n{} moves in {}:{:02}",
+        result_line(&game_state),
+        game_state.move_history.len(),
+        seconds / 60,
+        seconds % 60
+    );
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = message.clone();
+    }
+    let rematch = match rematch_starter(&game_state) {
+        Player::Human => "Rematch, you start".to_string(),
+        player => format!("Rematch, {} starts", game_state.player_name(player)),
+    };
+    for mut text in rematch_query.iter_mut() {
+        text.sections[0].value = rematch.clone();
+    }
+}
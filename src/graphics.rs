@@ -1,7 +1,29 @@
+use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
+use bevy::diagnostic::Diagnostics;
 use bevy::input::mouse::MouseMotion;
-use rand::Rng;
-use crate::game::{GameState, Player, CellState};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::view::RenderLayers;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use std::time::{Duration, Instant};
+use ttt3d_core::ai::EngineKind;
+use ttt3d_core::game::{line_index, line_kind, CellState, GameOverReason, GamePhase, GameState, LineKind, Player, Position};
+use crate::clock::TurnClock;
+use crate::colors::ColorTheme;
+use crate::hint::HintHighlight;
+use crate::minimap::MinimapCell;
+use crate::observer::MAIN_VIEW_LAYER;
+use crate::profiler::AI_SEARCH_TIME;
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+use crate::resign::AiOutlook;
+use crate::settings::{Settings, SettingsPanel};
+use crate::slice::LayerSlice;
+use crate::snapping::{record_snap_ray, snap_target, SnapDebug};
+use crate::threat_overlay::ThreatOverlay;
+use crate::tournament::Tournament;
+use crate::xray::{XRayHover, XRAY_ALPHA};
 
 // Helper function for ray-box intersection
 fn ray_box_intersection(ray_origin: Vec3, ray_dir: Vec3, box_min: Vec3, box_max: Vec3) -> Option<f32> {
@@ -60,37 +82,16 @@ fn ray_box_intersection(ray_origin: Vec3, ray_dir: Vec3, box_min: Vec3, box_max:
     }
 }
 
-// Generate a random light position that provides good illumination
-fn generate_random_light_position() -> Vec3 {
-    let mut rng = rand::thread_rng();
-    
-    // Generate random spherical coordinates around the cube
-    let distance: f32 = rng.gen_range(6.0..12.0); // Distance from center
-    let azimuth: f32 = rng.gen_range(0.0..std::f32::consts::TAU); // Rotation around Y axis
-    let elevation: f32 = rng.gen_range(0.3..1.2); // Angle from horizontal (avoid too low or too high)
-    
-    // Convert spherical to cartesian coordinates
-    let x = distance * elevation.cos() * azimuth.cos();
-    let y = distance * elevation.sin() + rng.gen_range(2.0..6.0) as f32; // Add some height bias
-    let z = distance * elevation.cos() * azimuth.sin();
-    
-    Vec3::new(x, y, z)
+// World-space center of the cube for a board cell
+pub fn cell_position(x: usize, y: usize, z: usize) -> Vec3 {
+    Vec3::new(
+        (x as f32 - 1.0) * 2.0,
+        (y as f32 - 1.0) * 2.0,
+        (z as f32 - 1.0) * 2.0,
+    )
 }
 
-// Generate a random light color with slight warm/cool variations
-fn generate_random_light_color() -> Color {
-    let mut rng = rand::thread_rng();
-    
-    // Create subtle color variations - mostly white but with slight tints
-    let base_intensity: f32 = 0.95;
-    let variation: f32 = 0.1;
-    
-    let r = (base_intensity + rng.gen_range(-variation..variation)).clamp(0.0, 1.0);
-    let g = (base_intensity + rng.gen_range(-variation..variation)).clamp(0.0, 1.0);
-    let b = (base_intensity + rng.gen_range(-variation..variation)).clamp(0.0, 1.0);
-    
-    Color::srgb(r, g, b)
-}
+pub const THIRD_PLAYER_COLOR: Color = Color::srgb(0.2, 0.4, 0.8);
 
 #[derive(Component)]
 pub struct CubeMarker {
@@ -102,6 +103,44 @@ pub struct CubeMarker {
 #[derive(Component)]
 pub struct HoveredCube;
 
+// Which cells the cursor can pick, by what the board is being used for. Looking at a position
+// (the lines through a cell with L held, and any later analysis or annotation tools) can target
+// any cell, while placing a piece only ever lands on an empty one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickMode {
+    Play,
+    // The board editor changes pieces, but blocked cells stay put
+    Edit,
+    Inspect,
+}
+
+impl PickMode {
+    // `None` when nothing can be picked, such as on the AI's turn outside of inspecting
+    pub fn current(game_state: &GameState, keyboard: &ButtonInput<KeyCode>) -> Option<Self> {
+        if keyboard.pressed(KeyCode::KeyL) {
+            Some(PickMode::Inspect)
+        } else if game_state.phase == GamePhase::Editing {
+            Some(PickMode::Edit)
+        } else if game_state.game_over || !game_state.is_human_turn() {
+            None
+        } else {
+            Some(PickMode::Play)
+        }
+    }
+
+    pub fn can_pick(self, cell: CellState) -> bool {
+        match self {
+            PickMode::Play => cell == CellState::Empty,
+            PickMode::Edit => cell != CellState::Blocked,
+            PickMode::Inspect => true,
+        }
+    }
+}
+
+// Gizmo group for thick cell outlines in high-contrast mode
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct OutlineGizmos;
+
 #[derive(Component)]
 pub struct GameLight;
 
@@ -111,7 +150,6 @@ pub struct MoveAnimation {
     pub duration: f32,
     pub initial_scale: f32,
     pub target_scale: f32,
-    pub rotation_speed: f32,
 }
 
 impl MoveAnimation {
@@ -121,53 +159,294 @@ impl MoveAnimation {
             duration: 0.5, // Animation duration in seconds
             initial_scale: 0.1,
             target_scale: 1.0,
-            rotation_speed: 8.0, // Rotations per second
         }
     }
 }
 
+// Piece drop: fall speed, fraction of speed kept on each bounce, and the speed below which it rests
+const DROP_GRAVITY: f32 = -30.0;
+const DROP_RESTITUTION: f32 = 0.35;
+const DROP_SETTLE_SPEED: f32 = 1.5;
+// How far above the top layer a piece starts when nothing is in the way
+const DROP_CLEARANCE: f32 = 3.0;
+// Half the cube size; pieces collide along these faces
+const CUBE_HALF_SIZE: f32 = 0.4;
+
+// A piece falling into its cell and bouncing to rest, used instead of `MoveAnimation`
+// when piece drop is on
+#[derive(Component)]
+pub struct DropAnimation {
+    // Height above the cell's resting position
+    pub offset: f32,
+    pub velocity: f32,
+}
+
+impl DropAnimation {
+    // Start above the board, or just under the lowest piece above the cell in its column,
+    // so the falling piece never passes through another one
+    pub fn new(board: &[[[CellState; 3]; 3]; 3], (x, y, z): Position) -> Self {
+        let rest = cell_position(x, y, z).y;
+        let offset = match (y + 1..3).find(|&above| board[x][above][z] != CellState::Empty) {
+            Some(above) => (cell_position(x, above, z).y - rest) - 2.0 * CUBE_HALF_SIZE,
+            None => cell_position(x, 2, z).y - rest + DROP_CLEARANCE,
+        };
+        Self { offset, velocity: 0.0 }
+    }
+}
+
+// A piece shrinking away after the fading rule took it off the board, despawned once it is gone
+#[derive(Component)]
+pub struct RemovalAnimation {
+    pub timer: f32,
+    pub duration: f32,
+}
+
+impl RemovalAnimation {
+    pub fn new() -> Self {
+        Self {
+            timer: 0.0,
+            duration: 0.4,
+        }
+    }
+}
+
+// A player's piece, spawned inside the cell it stands on. The cell's cube is hidden meanwhile
+#[derive(Component)]
+pub struct Piece {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+    pub cell: CellState,
+}
+
+impl Piece {
+    pub fn position(&self) -> Position {
+        (self.x, self.y, self.z)
+    }
+}
+
 #[derive(Component)]
 pub struct CameraController {
     pub sensitivity: f32,
     pub distance: f32,
     pub yaw: f32,
     pub pitch: f32,
+    // How fast the camera is turning, in radians a second of yaw and pitch. It eases towards
+    // what the mouse or keys ask for, and carries on, slowing, once they let go
+    pub velocity: Vec2,
+    // Set while the camera glides to a preset view; `None` while it's under the player's control
+    pub tween: Option<CameraTween>,
+    pub mode: CameraMode,
+}
+
+// F switches between orbiting the board and flying freely round it, for watching AI games or
+// framing screenshots. The orbit's angles are kept while flying, so switching back returns there
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraMode {
+    #[default]
+    Orbit,
+    FreeFly,
+}
+
+// How far a preset view's glide has come, from the angles it started at to the preset's
+#[derive(Clone, Copy)]
+pub struct CameraTween {
+    from: Vec2,
+    to: Vec2,
+    elapsed: f32,
+    seconds: f32,
+    // A cinematic holds the camera still this long once there, and the mouse and keys can't
+    // take it over until it's done
+    hold: f32,
+    locked: bool,
 }
 
+const CAMERA_TWEEN_SECONDS: f32 = 0.4;
+// Free flight's speed in world units a second, and radians of turn per pixel of mouse movement
+// for each unit of the controller's sensitivity
+const FLY_SPEED: f32 = 6.0;
+const FLY_LOOK_SCALE: f32 = 0.01;
+// The flight to the winning line at the end of a game, and how long the view holds it
+const WIN_FLIGHT_SECONDS: f32 = 1.2;
+const WIN_HOLD_SECONDS: f32 = 1.5;
+
+// Two rings close together read as one thick one around the last piece played, just clear of
+// the piece itself
+const LAST_MOVE_RADII: [f32; 2] = [0.5, 0.53];
+const LAST_MOVE_LIGHTEN: f32 = 0.35;
+// How quickly the camera's turn catches up with the mouse or keys: the gap shrinks by a factor
+// of e every 1/this seconds
+const CAMERA_ACCELERATION: f32 = 20.0;
+// Slower than this, in radians a second, a gliding camera has come to rest
+const CAMERA_REST_SPEED: f32 = 0.01;
+// As far as the pitch goes; straight up or down would leave `look_at` without a way to tell up
+const MAX_CAMERA_PITCH: f32 = 1.5;
+
+// Yaw and pitch of the preset views: 1 to 6 face the front, back, right, left, top and bottom of
+// the cube, and 0 goes back to the isometric view of three faces at once
+const CAMERA_PRESETS: [(KeyCode, f32, f32); 7] = [
+    (KeyCode::Digit1, std::f32::consts::FRAC_PI_2, 0.0),
+    (KeyCode::Digit2, -std::f32::consts::FRAC_PI_2, 0.0),
+    (KeyCode::Digit3, 0.0, 0.0),
+    (KeyCode::Digit4, std::f32::consts::PI, 0.0),
+    (KeyCode::Digit5, std::f32::consts::FRAC_PI_2, MAX_CAMERA_PITCH),
+    (KeyCode::Digit6, std::f32::consts::FRAC_PI_2, -MAX_CAMERA_PITCH),
+    // Halfway between the front and the right, looking down along the cube's diagonal
+    (KeyCode::Digit0, std::f32::consts::FRAC_PI_4, 0.615_479_7),
+];
+
 #[derive(Resource)]
 pub struct CubeMaterials {
     pub empty: Handle<StandardMaterial>,
     pub human: Handle<StandardMaterial>,
     pub ai: Handle<StandardMaterial>,
+    pub third: Handle<StandardMaterial>,
+    pub neutral: Handle<StandardMaterial>,
+    pub blocked: Handle<StandardMaterial>,
     pub selected: Handle<StandardMaterial>,
-    pub hovered: Handle<StandardMaterial>,
+    // The cell a hint suggests
+    pub hint: Handle<StandardMaterial>,
+    // See-through shells patterned per player, drawn around pieces with the colorblind aid on so
+    // pieces can be told apart without their colors: stripes, checks and dots
+    pub human_pattern: Handle<StandardMaterial>,
+    pub ai_pattern: Handle<StandardMaterial>,
+    pub third_pattern: Handle<StandardMaterial>,
+    // Tints of each player's piece color for the cells of their threat lines
+    pub human_threat: Handle<StandardMaterial>,
+    pub ai_threat: Handle<StandardMaterial>,
+    pub third_threat: Handle<StandardMaterial>,
+    // A fainter empty cube, for those in front of a cube X-ray hover looks through to
+    pub xray: Handle<StandardMaterial>,
+    // Empty cells in the layer scrolled to for picking
+    pub active_layer: Handle<StandardMaterial>,
+}
+
+impl CubeMaterials {
+    pub fn piece(&self, cell: CellState) -> Option<Handle<StandardMaterial>> {
+        match cell {
+            CellState::Human => Some(self.human.clone()),
+            CellState::AI => Some(self.ai.clone()),
+            CellState::Third => Some(self.third.clone()),
+            CellState::Neutral => Some(self.neutral.clone()),
+            CellState::Empty | CellState::Blocked => None,
+        }
+    }
+
+    // Notakto's shared pieces all belong to one side, so they need no pattern
+    pub fn pattern(&self, cell: CellState) -> Option<Handle<StandardMaterial>> {
+        match cell {
+            CellState::Human => Some(self.human_pattern.clone()),
+            CellState::AI => Some(self.ai_pattern.clone()),
+            CellState::Third => Some(self.third_pattern.clone()),
+            CellState::Neutral | CellState::Empty | CellState::Blocked => None,
+        }
+    }
+
+    pub fn threat(&self, player: Player) -> Handle<StandardMaterial> {
+        match player {
+            Player::Human => self.human_threat.clone(),
+            Player::AI => self.ai_threat.clone(),
+            Player::Third => self.third_threat.clone(),
+        }
+    }
+}
+
+// How opaque the threat tints are, so the pieces show through them
+pub const THREAT_TINT_ALPHA: f32 = 0.35;
+
+fn threat_material(materials: &mut Assets<StandardMaterial>, color: Color) -> Handle<StandardMaterial> {
+    materials.add(StandardMaterial {
+        base_color: color.with_alpha(THREAT_TINT_ALPHA),
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    })
+}
+
+const PATTERN_SIZE: u32 = 32;
+
+// A white pattern on a clear ground, filled where `filled` says, for a pattern shell
+fn pattern_material(
+    images: &mut Assets<Image>,
+    materials: &mut Assets<StandardMaterial>,
+    filled: fn(u32, u32) -> bool,
+) -> Handle<StandardMaterial> {
+    let data = (0..PATTERN_SIZE * PATTERN_SIZE)
+        .flat_map(|i| match filled(i % PATTERN_SIZE, i / PATTERN_SIZE) {
+            true => [255, 255, 255, 200],
+            false => [255, 255, 255, 0],
+        })
+        .collect();
+    let image = Image::new(
+        Extent3d {
+            width: PATTERN_SIZE,
+            height: PATTERN_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    materials.add(StandardMaterial {
+        base_color: Color::srgb(0.9, 0.9, 0.9),
+        base_color_texture: Some(images.add(image)),
+        alpha_mode: AlphaMode::Blend,
+        cull_mode: None,
+        unlit: true,
+        ..default()
+    })
 }
 
 #[derive(Resource)]
 pub struct GameMeshes {
     pub cube: Handle<Mesh>,
+    // Crossed bars for the human's X and a torus for the AI's O; the third player gets a
+    // tetrahedron, and Notakto's shared pieces are all X's
+    pub cross: Handle<Mesh>,
+    pub ring: Handle<Mesh>,
+    pub tetrahedron: Handle<Mesh>,
 }
 
+impl GameMeshes {
+    pub fn piece(&self, cell: CellState) -> Option<Handle<Mesh>> {
+        match cell {
+            CellState::Human | CellState::Neutral => Some(self.cross.clone()),
+            CellState::AI => Some(self.ring.clone()),
+            CellState::Third => Some(self.tetrahedron.clone()),
+            CellState::Empty | CellState::Blocked => None,
+        }
+    }
+}
+
+// Sent whatever the build; only builds with the `audio` feature play them
 #[derive(Event)]
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
 pub enum SoundEvent {
     MovePlace,
     Hover,
     Win,
     Lose,
-    Reset,
+    // Stinger for the geometry of the line that ended the game
+    LineComplete(LineKind),
 }
 
-#[derive(Resource)]
-pub struct GameSounds {
-    pub enabled: bool,
-    pub move_place: Handle<AudioSource>,
+// Asks for a new game. Each system with something to start over reads it for itself, so a
+// reset reaches all of them in the same frame. The AI's search, pondering and outlook follow
+// the game's `generation` instead, which the editor, puzzles and quiz bump when they reset too
+#[derive(Event)]
+pub struct ResetRequested;
+
+pub fn request_reset(keyboard: Res<ButtonInput<KeyCode>>, mut resets: EventWriter<ResetRequested>) {
+    if keyboard.just_pressed(KeyCode::KeyR) {
+        resets.send(ResetRequested);
+    }
 }
 
 pub fn setup_scene(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
 ) {
     // Create materials
     let cube_materials = CubeMaterials {
@@ -184,12 +463,45 @@ pub fn setup_scene(
             base_color: Color::srgb(0.7, 0.2, 0.2),
             ..default()
         }),
+        third: materials.add(StandardMaterial {
+            base_color: THIRD_PLAYER_COLOR,
+            ..default()
+        }),
+        neutral: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.85, 0.85, 0.85),
+            ..default()
+        }),
+        blocked: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.05, 0.05, 0.05),
+            perceptual_roughness: 0.9,
+            ..default()
+        }),
         selected: materials.add(StandardMaterial {
             base_color: Color::srgb(0.8, 0.8, 0.2),
             ..default()
         }),
-        hovered: materials.add(StandardMaterial {
-            base_color: Color::srgba(0.6, 0.6, 0.6, 0.8),
+        hint: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.2, 0.8, 1.0, 0.85),
+            emissive: LinearRgba::rgb(0.1, 0.5, 0.7),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+        human_pattern: pattern_material(&mut images, &mut materials, |x, _| x % 8 < 3),
+        ai_pattern: pattern_material(&mut images, &mut materials, |x, y| (x / 8 + y / 8) % 2 == 0),
+        third_pattern: pattern_material(&mut images, &mut materials, |x, y| {
+            let (dx, dy) = ((x % 8) as i32 - 4, (y % 8) as i32 - 4);
+            dx * dx + dy * dy <= 5
+        }),
+        human_threat: threat_material(&mut materials, Color::srgb(0.2, 0.7, 0.2)),
+        ai_threat: threat_material(&mut materials, Color::srgb(0.7, 0.2, 0.2)),
+        third_threat: threat_material(&mut materials, THIRD_PLAYER_COLOR),
+        xray: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.3, 0.3, 0.3, 0.5 * XRAY_ALPHA),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+        active_layer: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.3, 0.4, 0.55, 0.55),
             alpha_mode: AlphaMode::Blend,
             ..default()
         }),
@@ -197,12 +509,23 @@ pub fn setup_scene(
 
     // Create mesh
     let cube_mesh = meshes.add(Mesh::from(Cuboid::new(0.8, 0.8, 0.8)));
-    
+
+    // Pieces face the starting camera and fit inside a cube
+    let bar = Mesh::from(Cuboid::new(0.8, 0.16, 0.16));
+    let mut cross = bar.clone().rotated_by(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4));
+    cross.merge(&bar.rotated_by(Quat::from_rotation_z(-std::f32::consts::FRAC_PI_4)));
+    let ring = Mesh::from(Torus::new(0.2, 0.36)).rotated_by(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2));
+    let tetrahedron = Mesh::from(Tetrahedron::default()).scaled_by(Vec3::splat(0.7));
+
     let game_meshes = GameMeshes {
         cube: cube_mesh.clone(),
+        cross: meshes.add(cross),
+        ring: meshes.add(ring),
+        tetrahedron: meshes.add(tetrahedron),
     };
 
-    // Create the 3x3x3 grid of cubes
+    // Create the 3x3x3 grid of cubes. They all share one mesh, and each cell state one material,
+    // so Bevy draws them in a handful of batched, instanced draws rather than one per cube
     for x in 0..3 {
         for y in 0..3 {
             for z in 0..3 {
@@ -210,11 +533,7 @@ pub fn setup_scene(
                     PbrBundle {
                         mesh: cube_mesh.clone(),
                         material: cube_materials.empty.clone(),
-                        transform: Transform::from_xyz(
-                            (x as f32 - 1.0) * 2.0,
-                            (y as f32 - 1.0) * 2.0,
-                            (z as f32 - 1.0) * 2.0,
-                        ),
+                        transform: Transform::from_translation(cell_position(x, y, z)),
                         ..default()
                     },
                     CubeMarker { x, y, z },
@@ -229,26 +548,30 @@ pub fn setup_scene(
             transform: Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
             ..default()
         },
+        // The UI belongs to the main view, not the picture-in-picture inset
+        IsDefaultUiCamera,
+        RenderLayers::from_layers(&[0, MAIN_VIEW_LAYER]),
+        // Facing the front, which is where (0, 0, 10) sits on the controller's sphere
         CameraController {
             sensitivity: 0.5,
             distance: 10.0,
-            yaw: 0.0,
+            yaw: std::f32::consts::FRAC_PI_2,
             pitch: 0.0,
+            velocity: Vec2::ZERO,
+            tween: None,
+            mode: CameraMode::Orbit,
         },
     ));
 
-    // Random light position and color for variety
-    let light_position = generate_random_light_position();
-    let light_color = generate_random_light_color();
+    // Placed and colored by the active lighting theme once the game starts
     commands.spawn((
         DirectionalLightBundle {
             directional_light: DirectionalLight {
-                color: light_color,
                 illuminance: 3000.0,
                 shadows_enabled: true,
                 ..default()
             },
-            transform: Transform::from_translation(light_position).looking_at(Vec3::ZERO, Vec3::Y),
+            transform: Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
             ..default()
         },
         GameLight,
@@ -263,7 +586,7 @@ pub fn setup_scene(
     // UI Text
     commands.spawn(
         TextBundle::from_section(
-            "3D Tic-Tac-Toe\nHover over cubes to highlight them\nClick highlighted cubes to play!\nWASD + Mouse to rotate camera\nR to reset game + randomize lighting",
+            "3D Tic-Tac-Toe\nHover over cubes to highlight them\nClick highlighted cubes to play!\nWASD + Mouse to rotate camera\nR to reset game + vary lighting",
             TextStyle {
                 font_size: 20.0,
                 color: Color::WHITE,
@@ -297,36 +620,92 @@ pub fn setup_scene(
         GameStatusText,
     ));
 
+    // Running line tally for the line scoring variant, just above the turn clock
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 22.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(40.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        ScoreText,
+    ));
+
+    // Hands the human's current move to the engine
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(50.0),
+                    left: Val::Px(10.0),
+                    padding: UiRect::axes(Val::Px(10.0), Val::Px(6.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.2, 0.2, 0.3, 0.85).into(),
+                ..default()
+            },
+            PlayForMeButton,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                "Play for me [E]",
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+
     commands.insert_resource(cube_materials);
     commands.insert_resource(game_meshes);
-    
-    // Initialize sound system with actual audio files
-    let game_sounds = GameSounds {
-        enabled: true,
-        move_place: asset_server.load("audio/place.mp3"),
-    };
-    commands.insert_resource(game_sounds);
 }
 
 #[derive(Component)]
 pub struct GameStatusText;
 
+#[derive(Component)]
+pub struct PlayForMeButton;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+#[allow(clippy::too_many_arguments)]
 pub fn handle_hover(
     windows: Query<&Window>,
-    camera_query: Query<(&Camera, &GlobalTransform)>,
-    cubes_query: Query<(Entity, &GlobalTransform, &CubeMarker), Without<HoveredCube>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    cubes_query: Query<(Entity, &GlobalTransform, &CubeMarker)>,
     hovered_cubes: Query<Entity, With<HoveredCube>>,
     mut commands: Commands,
     game_state: Res<GameState>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    settings_panel: Res<SettingsPanel>,
+    tournament: Res<Tournament>,
+    slice: Res<LayerSlice>,
+    mut xray: ResMut<XRayHover>,
+    mut snap_debug: ResMut<SnapDebug>,
     mut sound_events: EventWriter<SoundEvent>,
 ) {
-    if game_state.game_over || game_state.current_player != Player::Human {
-        // Remove all hover highlights when it's not the player's turn
+    let mode = PickMode::current(&game_state, &keyboard).filter(|_| !settings_panel.open && !tournament.screen_open);
+    let Some(mode) = mode else {
+        // Remove all hover highlights when nothing can be picked or a panel has focus
         for entity in hovered_cubes.iter() {
             commands.entity(entity).remove::<HoveredCube>();
         }
+        xray.clear();
+        record_snap_ray(&mut snap_debug, None, []);
         return;
-    }
+    };
 
     let window = windows.single();
     if let Some(cursor_position) = window.cursor_position() {
@@ -337,40 +716,61 @@ pub fn handle_hover(
             let ray_origin = ray.origin;
             let ray_dir = *ray.direction;
             
-            let mut closest_cube = None;
-            let mut closest_distance = f32::INFINITY;
+            // Every cube the ray passes through, with how far along it and whether it can be picked
+            let mut hits = Vec::new();
+            let mut pickable = Vec::new();
             
             // Check intersection with all cubes
             for (entity, cube_transform, cube_marker) in cubes_query.iter() {
-                // Only check empty cubes
-                if game_state.board[cube_marker.x][cube_marker.y][cube_marker.z] != CellState::Empty {
+                let position = (cube_marker.x, cube_marker.y, cube_marker.z);
+                if !slice.pickable(position) {
                     continue;
                 }
+                let can_pick = mode.can_pick(game_state.board[cube_marker.x][cube_marker.y][cube_marker.z]);
                 
                 let cube_pos = cube_transform.translation();
-                let cube_size = 0.4; // Half the cube size (0.8 / 2)
+                if can_pick {
+                    pickable.push((entity, cube_pos));
+                }
+                // Half the cube size (0.8 / 2), padded by the accessibility hit margin
+                let cube_size = 0.4 + settings.hit_margin;
                 let box_min = cube_pos - Vec3::splat(cube_size);
                 let box_max = cube_pos + Vec3::splat(cube_size);
                 
                 if let Some(distance) = ray_box_intersection(ray_origin, ray_dir, box_min, box_max) {
-                    if distance < closest_distance {
-                        closest_distance = distance;
-                        closest_cube = Some(entity);
-                    }
+                    hits.push((distance, entity, position, can_pick));
                 }
             }
+            hits.sort_by(|a, b| a.0.total_cmp(&b.0));
             
-            // Remove hover from all cubes
-            for entity in hovered_cubes.iter() {
-                commands.entity(entity).remove::<HoveredCube>();
+            // The nearest pickable cube, or with Alt pressed the next one behind it
+            let along = hits.iter().filter(|hit| hit.3).map(|hit| hit.1).collect();
+            let step = keyboard.any_just_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+            let mut closest_cube = xray.follow(along, step);
+            let occluders = match closest_cube {
+                Some(target) if xray.depth > 0 => hits.iter().take_while(|hit| hit.1 != target).map(|hit| hit.2).collect(),
+                _ => Vec::new(),
+            };
+            if xray.occluders != occluders {
+                xray.occluders = occluders;
             }
             
-            // Add hover to the closest cube
-            if let Some(entity) = closest_cube {
+            // A near miss at a grazing angle still picks the one cell it passes close to
+            if closest_cube.is_none() {
+                closest_cube = snap_target(ray, pickable.iter().copied(), settings.snap_angle);
+            }
+            record_snap_ray(&mut snap_debug, Some(ray), pickable.iter().map(|&(_, center)| center));
+            
+            // Move the hover to the closest cube
+            let previous = hovered_cubes.iter().next();
+            for entity in hovered_cubes.iter().filter(|&entity| Some(entity) != closest_cube) {
+                commands.entity(entity).remove::<HoveredCube>();
+            }
+            if let Some(entity) = closest_cube.filter(|&entity| Some(entity) != previous) {
                 commands.entity(entity).insert(HoveredCube);
                 
                 // Play hover sound (only if no cube was previously hovered to avoid spam)
-                if hovered_cubes.is_empty() {
+                if previous.is_none() {
                     sound_events.send(SoundEvent::Hover);
                 }
             }
@@ -380,46 +780,196 @@ pub fn handle_hover(
         for entity in hovered_cubes.iter() {
             commands.entity(entity).remove::<HoveredCube>();
         }
+        xray.clear();
+        record_snap_ray(&mut snap_debug, None, []);
     }
 }
 
+// Resets are taken whatever the app is doing, so a finished game can be reset from GameOver
+pub fn reset_game(mut game_state: ResMut<GameState>, mut resets: EventReader<ResetRequested>) {
+    if resets.read().last().is_some() {
+        game_state.reset();
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::never_loop)]
 pub fn handle_input(
     buttons: Res<ButtonInput<MouseButton>>,
     keyboard: Res<ButtonInput<KeyCode>>,
     hovered_cubes: Query<&CubeMarker, With<HoveredCube>>,
+    play_for_me_button: Query<&Interaction, Or<(With<PlayForMeButton>, With<MinimapCell>)>>,
     mut game_state: ResMut<GameState>,
-    mut sound_events: EventWriter<SoundEvent>,
 ) {
-    if keyboard.just_pressed(KeyCode::KeyR) {
-        game_state.reset();
-        sound_events.send(SoundEvent::Reset);
+    // The board editor handles its own clicks
+    if game_state.game_over || !game_state.is_human_turn() || game_state.phase == GamePhase::Editing {
         return;
     }
 
-    if game_state.game_over || game_state.current_player != Player::Human {
+    if game_state.phase == GamePhase::SwapOffer && keyboard.just_pressed(KeyCode::KeyX) {
+        game_state.swap_sides();
         return;
     }
 
-    if buttons.just_pressed(MouseButton::Left) {
+    if keyboard.just_pressed(KeyCode::Space) && game_state.pass() {
+        return;
+    }
+
+    // Clicks on the play-for-me button or the minimap shouldn't also land on a cube behind them
+    let over_button = play_for_me_button.iter().any(|interaction| *interaction != Interaction::None);
+    if buttons.just_pressed(MouseButton::Left) && !over_button {
         // Only allow selection of hovered cubes for accurate hit detection
         for cube_marker in hovered_cubes.iter() {
-            // Make the move on the hovered cube
-            game_state.make_move(cube_marker.x, cube_marker.y, cube_marker.z);
+            // Inspecting can hover occupied cells, but only an empty one takes a piece
+            if game_state.board[cube_marker.x][cube_marker.y][cube_marker.z] != CellState::Empty {
+                break;
+            }
+            // Shift+click before the first move blocks the cell as a handicap instead
+            if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                game_state.block_cell(cube_marker.x, cube_marker.y, cube_marker.z);
+            } else {
+                // Make the move on the hovered cube
+                game_state.make_move(cube_marker.x, cube_marker.y, cube_marker.z);
+            }
             break; // Only one cube can be hovered at a time
         }
     }
 }
 
+// Number keys snap the camera to a preset view while the settings panel, which has its own use
+// for them, is closed. The camera glides there unless reduced motion is on
+pub fn choose_camera_preset(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    settings: Res<Settings>,
+    mut camera_query: Query<(&mut Transform, &mut CameraController)>,
+) {
+    if panel.open {
+        return;
+    }
+    let Some(&(_, yaw, pitch)) = CAMERA_PRESETS.iter().find(|(key, _, _)| keyboard.just_pressed(*key)) else {
+        return;
+    };
+    let Ok((mut transform, mut controller)) = camera_query.get_single_mut() else {
+        return;
+    };
+    if controller.mode != CameraMode::Orbit {
+        return;
+    }
+
+    let from = Vec2::new(controller.yaw, controller.pitch);
+    let to = short_turn(from, Vec2::new(yaw, pitch));
+    controller.velocity = Vec2::ZERO;
+    if settings.reduced_motion {
+        controller.tween = None;
+        place_camera(&mut transform, &mut controller, to);
+    } else {
+        controller.tween = Some(CameraTween {
+            from,
+            to,
+            elapsed: 0.0,
+            seconds: CAMERA_TWEEN_SECONDS,
+            hold: 0.0,
+            locked: false,
+        });
+    }
+}
+
+// The short way round from `from` to the view `to`, rather than through however many turns the
+// yaw has wound up
+fn short_turn(from: Vec2, to: Vec2) -> Vec2 {
+    let turn = (to.x - from.x + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+    Vec2::new(from.x + turn, to.y)
+}
+
+// When a game is won, fly the camera round to face the winning line side on, so all three of
+// its cells show, hold there a moment and hand the camera back. Of the views square to the
+// line, it takes the one nearest where the camera already is
+pub fn fly_to_winning_line(
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    mut camera_query: Query<&mut CameraController>,
+    mut shown: Local<Option<u64>>,
+) {
+    let Some(line) = game_state.winning_line.filter(|_| game_state.game_over) else {
+        return;
+    };
+    if *shown == Some(game_state.generation) || settings.reduced_motion {
+        return;
+    }
+    *shown = Some(game_state.generation);
+    let Ok(mut controller) = camera_query.get_single_mut() else {
+        return;
+    };
+    if controller.mode != CameraMode::Orbit {
+        return;
+    }
+
+    let along = (cell_position(line[2].0, line[2].1, line[2].2) - cell_position(line[0].0, line[0].1, line[0].2)).normalize();
+    let from = Vec2::new(controller.yaw, controller.pitch);
+    let camera = Vec3::new(from.x.cos() * from.y.cos(), from.y.sin(), from.x.sin() * from.y.cos());
+    let square = (camera - camera.dot(along) * along).try_normalize().unwrap_or_else(|| along.any_orthonormal_vector());
+    let view = Vec2::new(square.z.atan2(square.x), square.y.asin().clamp(-MAX_CAMERA_PITCH, MAX_CAMERA_PITCH));
+
+    controller.velocity = Vec2::ZERO;
+    controller.tween = Some(CameraTween {
+        from,
+        to: short_turn(from, view),
+        elapsed: 0.0,
+        seconds: WIN_FLIGHT_SECONDS,
+        hold: WIN_HOLD_SECONDS,
+        locked: true,
+    });
+}
+
+// Put the camera at `angles` (yaw, pitch) on its sphere around the board, facing the centre
+fn place_camera(transform: &mut Transform, controller: &mut CameraController, angles: Vec2) {
+    controller.yaw = angles.x;
+    controller.pitch = angles.y;
+    orbit_camera(transform, controller.distance, angles);
+}
+
+// Put the camera `distance` from the centre at `angles` without touching its controller, for
+// views that only lean away from where the controller has it, such as the board tilt
+pub fn orbit_camera(transform: &mut Transform, distance: f32, angles: Vec2) {
+    let (yaw, pitch) = (angles.x, angles.y.clamp(-MAX_CAMERA_PITCH, MAX_CAMERA_PITCH));
+
+    // Update camera position based on spherical coordinates
+    let x = distance * yaw.cos() * pitch.cos();
+    let y = distance * pitch.sin();
+    let z = distance * yaw.sin() * pitch.cos();
+
+    transform.translation = Vec3::new(x, y, z);
+    transform.look_at(Vec3::ZERO, Vec3::Y);
+}
+
+// The mouse and keys set the speed the camera turns at rather than moving it directly, so it
+// speeds up smoothly and, with damping on, glides to a stop once let go
 pub fn rotate_camera(
     mut motion_events: EventReader<MouseMotion>,
     mut camera_query: Query<(&mut Transform, &mut CameraController)>,
     keyboard: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
     buttons: Res<ButtonInput<MouseButton>>,
+    settings: Res<Settings>,
 ) {
     if let Ok((mut transform, mut controller)) = camera_query.get_single_mut() {
+        if controller.mode != CameraMode::Orbit {
+            return;
+        }
+
+        // A cinematic has the camera to itself until it's over
+        if controller.tween.is_some_and(|tween| tween.locked) {
+            motion_events.clear();
+            controller.velocity = Vec2::ZERO;
+            advance_camera_tween(&mut transform, &mut controller, time.delta_seconds());
+            return;
+        }
+
         let mut rotation_delta = Vec2::ZERO;
 
+        // Held still, the mouse or keys hold the camera still too; only letting go lets it glide
+        let steering = buttons.pressed(MouseButton::Right) || keyboard.any_pressed([KeyCode::KeyA, KeyCode::KeyD, KeyCode::KeyW, KeyCode::KeyS]);
+
         // Mouse look (when right mouse button is held)
         if buttons.pressed(MouseButton::Right) {
             for event in motion_events.read() {
@@ -442,80 +992,305 @@ pub fn rotate_camera(
             rotation_delta.y += rotation_speed * time.delta_seconds() * 100.0;
         }
 
-        if rotation_delta.length() > 0.0 {
-            controller.yaw -= rotation_delta.x * controller.sensitivity * time.delta_seconds();
-            controller.pitch -= rotation_delta.y * controller.sensitivity * time.delta_seconds();
-            controller.pitch = controller.pitch.clamp(-1.5, 1.5);
+        let dt = time.delta_seconds();
+        let wanted = -rotation_delta * controller.sensitivity;
+        let damping = if settings.reduced_motion { 0.0 } else { settings.camera_damping };
+        controller.velocity = if damping <= 0.0 {
+            wanted
+        } else if steering {
+            controller.velocity.lerp(wanted, 1.0 - (-CAMERA_ACCELERATION * dt).exp())
+        } else {
+            controller.velocity * (-damping * dt).exp()
+        };
+        if !steering && controller.velocity.length() < CAMERA_REST_SPEED {
+            controller.velocity = Vec2::ZERO;
+        }
+
+        if controller.velocity != Vec2::ZERO {
+            // Turning the camera by hand takes it back from a preset's glide, where it is
+            controller.tween = None;
+            let yaw = controller.yaw + controller.velocity.x * dt;
+            let pitch = controller.pitch + controller.velocity.y * dt;
+            if pitch.abs() > MAX_CAMERA_PITCH {
+                controller.velocity.y = 0.0;
+            }
+            place_camera(&mut transform, &mut controller, Vec2::new(yaw, pitch.clamp(-MAX_CAMERA_PITCH, MAX_CAMERA_PITCH)));
+        } else {
+            advance_camera_tween(&mut transform, &mut controller, dt);
+        }
+    }
+}
+
+pub fn toggle_camera_mode(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut camera_query: Query<(&mut Transform, &mut CameraController)>,
+) {
+    if panel.open || !keyboard.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Ok((mut transform, mut controller)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    controller.velocity = Vec2::ZERO;
+    controller.tween = None;
+    controller.mode = match controller.mode {
+        CameraMode::Orbit => CameraMode::FreeFly,
+        CameraMode::FreeFly => {
+            let angles = Vec2::new(controller.yaw, controller.pitch);
+            place_camera(&mut transform, &mut controller, angles);
+            CameraMode::Orbit
+        }
+    };
+}
+
+// Free flight: W and S move along the view, A and D sideways, and the right mouse button looks
+// around, all from wherever the camera is
+pub fn fly_camera(
+    mut motion_events: EventReader<MouseMotion>,
+    mut camera_query: Query<(&mut Transform, &CameraController)>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
+) {
+    let Ok((mut transform, controller)) = camera_query.get_single_mut() else {
+        return;
+    };
+    if controller.mode != CameraMode::FreeFly {
+        return;
+    }
 
-            // Update camera position based on spherical coordinates
-            let x = controller.distance * controller.yaw.cos() * controller.pitch.cos();
-            let y = controller.distance * controller.pitch.sin();
-            let z = controller.distance * controller.yaw.sin() * controller.pitch.cos();
+    if buttons.pressed(MouseButton::Right) {
+        let delta: Vec2 = motion_events.read().map(|event| event.delta).sum();
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        let turn = delta * controller.sensitivity * FLY_LOOK_SCALE;
+        let pitch = (pitch - turn.y).clamp(-MAX_CAMERA_PITCH, MAX_CAMERA_PITCH);
+        transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw - turn.x, pitch, 0.0);
+    } else {
+        motion_events.clear();
+    }
 
-            transform.translation = Vec3::new(x, y, z);
-            transform.look_at(Vec3::ZERO, Vec3::Y);
+    let mut direction = Vec3::ZERO;
+    for (key, step) in [
+        (KeyCode::KeyW, *transform.forward()),
+        (KeyCode::KeyS, *transform.back()),
+        (KeyCode::KeyA, *transform.left()),
+        (KeyCode::KeyD, *transform.right()),
+    ] {
+        if keyboard.pressed(key) {
+            direction += step;
         }
     }
+    transform.translation += direction.normalize_or_zero() * FLY_SPEED * time.delta_seconds();
 }
 
+fn advance_camera_tween(transform: &mut Transform, controller: &mut CameraController, dt: f32) {
+    let Some(mut tween) = controller.tween else {
+        return;
+    };
+    tween.elapsed += dt;
+    let t = (tween.elapsed / tween.seconds).min(1.0);
+    // Eased in and out, so the glide neither jerks off nor stops dead
+    let eased = t * t * (3.0 - 2.0 * t);
+    controller.tween = (tween.elapsed < tween.seconds + tween.hold).then_some(tween);
+    place_camera(transform, controller, tween.from.lerp(tween.to, eased));
+}
+
+// Spawn the piece of a new move inside its cell, dropping or popping it in, and start the
+// removal of a piece the fading rule took off
 pub fn trigger_move_animations(
     mut commands: Commands,
-    mut cube_query: Query<(Entity, &mut Transform, &CubeMarker), Without<MoveAnimation>>,
+    piece_query: Query<(Entity, &Piece), Without<RemovalAnimation>>,
     game_state: Res<GameState>,
+    settings: Res<Settings>,
+    game_meshes: Res<GameMeshes>,
+    materials: Res<CubeMaterials>,
     mut sound_events: EventWriter<SoundEvent>,
 ) {
     if !game_state.is_changed() {
         return;
     }
-    
-    // Check all cubes for newly placed pieces
-    for (entity, mut transform, cube_marker) in cube_query.iter_mut() {
-        let cell_state = game_state.board[cube_marker.x][cube_marker.y][cube_marker.z];
-        
-        // If this cube was just placed (not empty and game state changed), start animation
-        if cell_state != CellState::Empty {
-            // Check if this cube was the last move made
-            if let Some(last_move) = game_state.last_move {
-                if (cube_marker.x, cube_marker.y, cube_marker.z) == last_move {
-                    // Start animation from small scale
-                    transform.scale = Vec3::splat(0.1);
-                    transform.rotation = Quat::IDENTITY;
-                    
-                    // Add animation component
-                    commands.entity(entity).insert(MoveAnimation::new());
-                    
-                    // Play move sound
-                    sound_events.send(SoundEvent::MovePlace);
+
+    if let Some(last_move) = game_state.last_move {
+        let (x, y, z) = last_move;
+        let cell = game_state.board[x][y][z];
+        let placed = piece_query.iter().any(|(_, piece)| piece.position() == last_move && piece.cell == cell);
+        if !placed {
+            let rest = Transform::from_translation(cell_position(x, y, z));
+            let piece = if settings.piece_drop && !settings.reduced_motion {
+                let drop = DropAnimation::new(&game_state.board, last_move);
+                let start = rest.with_translation(rest.translation + Vec3::Y * drop.offset);
+                spawn_piece(&mut commands, &game_meshes, &materials, last_move, cell, start).map(|mut piece| {
+                    piece.insert(drop);
+                })
+            } else {
+                // Start animation from small scale
+                spawn_piece(&mut commands, &game_meshes, &materials, last_move, cell, rest.with_scale(Vec3::splat(0.1))).map(|mut piece| {
+                    piece.insert(MoveAnimation::new());
+                })
+            };
+
+            // Play move sound
+            if piece.is_some() {
+                sound_events.send(SoundEvent::MovePlace);
+            }
+        }
+    }
+
+    if let Some((removed, _)) = game_state.last_removed {
+        for (entity, piece) in piece_query.iter() {
+            if piece.position() == removed && game_state.board[removed.0][removed.1][removed.2] != piece.cell {
+                commands.entity(entity).insert(RemovalAnimation::new());
+            }
+        }
+    }
+}
+
+// Keep exactly one piece on every cell that holds one, whatever changed the board: a move
+// handled above, or an undo, a reset, the editor, a loaded position or the pie rule's swap
+#[allow(clippy::needless_range_loop)]
+pub fn sync_pieces(
+    mut commands: Commands,
+    piece_query: Query<(Entity, &Piece), Without<RemovalAnimation>>,
+    game_state: Res<GameState>,
+    game_meshes: Res<GameMeshes>,
+    materials: Res<CubeMaterials>,
+) {
+    if !game_state.is_changed() {
+        return;
+    }
+
+    let mut shown = [[[false; 3]; 3]; 3];
+    for (entity, piece) in piece_query.iter() {
+        let (x, y, z) = piece.position();
+        if game_state.board[x][y][z] == piece.cell && !shown[x][y][z] {
+            shown[x][y][z] = true;
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for x in 0..3 {
+        for y in 0..3 {
+            for z in 0..3 {
+                if !shown[x][y][z] {
+                    let rest = Transform::from_translation(cell_position(x, y, z));
+                    spawn_piece(&mut commands, &game_meshes, &materials, (x, y, z), game_state.board[x][y][z], rest);
                 }
             }
         }
     }
 }
 
+// `None` for a cell that takes no piece: an empty or blocked one
+fn spawn_piece<'a>(
+    commands: &'a mut Commands,
+    game_meshes: &GameMeshes,
+    materials: &CubeMaterials,
+    (x, y, z): Position,
+    cell: CellState,
+    transform: Transform,
+) -> Option<EntityCommands<'a>> {
+    let (mesh, material) = game_meshes.piece(cell).zip(materials.piece(cell))?;
+    Some(commands.spawn((
+        PbrBundle {
+            mesh,
+            material,
+            transform,
+            ..default()
+        },
+        Piece { x, y, z, cell },
+    )))
+}
+
 pub fn update_cube_materials(
-    mut cube_query: Query<(&mut Handle<StandardMaterial>, &CubeMarker, Option<&HoveredCube>, Option<&MoveAnimation>)>,
+    mut cube_query: Query<(
+        &mut Handle<StandardMaterial>,
+        &mut Visibility,
+        &CubeMarker,
+        Option<&HintHighlight>,
+    )>,
     game_state: Res<GameState>,
     materials: Res<CubeMaterials>,
+    slice: Res<LayerSlice>,
+    settings: Res<Settings>,
+    threats: Res<ThreatOverlay>,
+    xray: Res<XRayHover>,
 ) {
-    for (mut material, cube_marker, hovered, _animating) in cube_query.iter_mut() {
+    // The hovered cell keeps its material, outlined by `update_hover_rim`
+    for (mut material, mut visibility, cube_marker, hint) in cube_query.iter_mut() {
         let cell_state = game_state.board[cube_marker.x][cube_marker.y][cube_marker.z];
-        
+
+        // A cell holding a piece shows the piece instead, or with the colorblind aid on the
+        // piece inside its owner's pattern shell, and a peeled layer shows nothing. Cells on a
+        // threat line are tinted, pieces and all, while the threat overlay is on
+        let threat = threats.cells[cube_marker.x][cube_marker.y][cube_marker.z].map(|player| materials.threat(player));
+        let pattern = threat.clone().or_else(|| materials.pattern(cell_state).filter(|_| settings.colorblind));
+        // Cells in front of an X-rayed target are faint, and pieces among them are outlined
+        // by `draw_xray_occluders` instead
+        let occluding = xray.occludes((cube_marker.x, cube_marker.y, cube_marker.z));
+        let shown = match cell_state {
+            _ if slice.hides((cube_marker.x, cube_marker.y, cube_marker.z)) => Visibility::Hidden,
+            CellState::Empty | CellState::Blocked if occluding => Visibility::Inherited,
+            _ if occluding => Visibility::Hidden,
+            CellState::Empty | CellState::Blocked => Visibility::Inherited,
+            _ if pattern.is_some() => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+
         *material = match cell_state {
+            _ if occluding => materials.xray.clone(),
             CellState::Empty => {
-                if hovered.is_some() && game_state.current_player == Player::Human && !game_state.game_over {
-                    materials.hovered.clone()
+                if hint.is_some() {
+                    materials.hint.clone()
                 } else if Some((cube_marker.x, cube_marker.y, cube_marker.z)) == game_state.selected_cube {
                     materials.selected.clone()
+                } else if let Some(threat) = threat {
+                    threat
+                } else if slice.in_active_layer((cube_marker.x, cube_marker.y, cube_marker.z)) {
+                    materials.active_layer.clone()
                 } else {
                     materials.empty.clone()
                 }
             }
-            CellState::Human => materials.human.clone(),
-            CellState::AI => materials.ai.clone(),
+            CellState::Blocked => materials.blocked.clone(),
+            CellState::Human | CellState::AI | CellState::Third | CellState::Neutral => pattern.unwrap_or_else(|| materials.empty.clone()),
         };
     }
 }
 
+pub fn update_score_text(
+    game_state: Res<GameState>,
+    mut text_query: Query<&mut Text, With<ScoreText>>,
+) {
+    if !game_state.is_changed() {
+        return;
+    }
+
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    if !game_state.rules.line_scoring {
+        text.sections[0].value.clear();
+        return;
+    }
+
+    let mut players = vec![Player::Human, Player::AI];
+    if game_state.rules.player_count() > 2 {
+        players.push(Player::Third);
+    }
+    let tally: Vec<_> = players
+        .iter()
+        .map(|&player| format!("{} {}", game_state.player_name(player), game_state.scores[player.index()]))
+        .collect();
+    text.sections[0].value = format!("Lines: {}", tally.join("  "));
+}
+
 pub fn check_game_over(
     game_state: Res<GameState>,
     mut status_text_query: Query<&mut Text, With<GameStatusText>>,
@@ -527,7 +1302,30 @@ pub fn check_game_over(
 
     if let Ok(mut text) = status_text_query.get_single_mut() {
         if game_state.game_over {
+            let reason = game_state.game_over_reason;
             match game_state.winner {
+                Some(Player::Human) if reason == Some(GameOverReason::Resignation) => {
+                    text.sections[0].value = format!(
+                        "{} resigned - you win! Press R to restart",
+                        game_state.player_name(game_state.current_player)
+                    );
+                    text.sections[0].style.color = Color::srgb(0.2, 0.7, 0.2);
+                    sound_events.send(SoundEvent::Win);
+                }
+                Some(Player::Human) if reason == Some(GameOverReason::Time) => {
+                    // The player who ran out of time is still the one whose turn it was
+                    text.sections[0].value = format!(
+                        "{} ran out of time - you win! Press R to restart",
+                        game_state.player_name(game_state.current_player)
+                    );
+                    text.sections[0].style.color = Color::srgb(0.2, 0.7, 0.2);
+                    sound_events.send(SoundEvent::Win);
+                }
+                Some(Player::AI) if reason == Some(GameOverReason::Time) => {
+                    text.sections[0].value = "Time's up - AI wins! Press R to restart".to_string();
+                    text.sections[0].style.color = Color::srgb(0.7, 0.2, 0.2);
+                    sound_events.send(SoundEvent::Lose);
+                }
                 Some(Player::Human) => {
                     text.sections[0].value = "You win! Press R to restart".to_string();
                     text.sections[0].style.color = Color::srgb(0.2, 0.7, 0.2);
@@ -538,13 +1336,53 @@ pub fn check_game_over(
                     text.sections[0].style.color = Color::srgb(0.7, 0.2, 0.2);
                     sound_events.send(SoundEvent::Lose);
                 }
+                Some(Player::Third) if reason == Some(GameOverReason::Time) => {
+                    text.sections[0].value = format!("Time's up - {} wins! Press R to restart", game_state.player_name(Player::Third));
+                    text.sections[0].style.color = THIRD_PLAYER_COLOR;
+                    sound_events.send(SoundEvent::Lose);
+                }
+                Some(Player::Third) => {
+                    text.sections[0].value = format!("{} wins! Press R to restart", game_state.player_name(Player::Third));
+                    text.sections[0].style.color = THIRD_PLAYER_COLOR;
+                    sound_events.send(SoundEvent::Lose);
+                }
+                None if reason == Some(GameOverReason::DrawAgreed) => {
+                    text.sections[0].value = "Draw agreed. Press R to restart".to_string();
+                    text.sections[0].style.color = Color::srgb(0.7, 0.7, 0.2);
+                }
                 None => {
                     text.sections[0].value = "It's a draw! Press R to restart".to_string();
                     text.sections[0].style.color = Color::srgb(0.7, 0.7, 0.2);
                 }
             }
+
+            if let Some(line) = game_state.winning_line {
+                sound_events.send(SoundEvent::LineComplete(line_kind(&line)));
+            }
         } else {
             match game_state.current_player {
+                _ if game_state.phase == GamePhase::Editing => {
+                    text.sections[0].value = "Board editor: click cells to set up a position".to_string();
+                    text.sections[0].style.color = Color::srgb(0.6, 0.8, 1.0);
+                }
+                Player::Human if game_state.phase == GamePhase::SwapOffer => {
+                    text.sections[0].value = "Pie rule: press X to take over the AI's move, or play your own".to_string();
+                    text.sections[0].style.color = Color::srgb(0.8, 0.8, 0.2);
+                }
+                Player::Human if game_state.swapped && game_state.move_history.len() == 1 => {
+                    text.sections[0].value = "AI swapped sides and took your move - your turn!".to_string();
+                    text.sections[0].style.color = Color::srgb(0.2, 0.7, 0.2);
+                }
+                Player::Human if game_state.rules.passes > 0 => {
+                    let turn = if game_state.consecutive_passes() > 0 { "Passed to you - your turn!" } else { "Your turn!" };
+                    let left = game_state.rules.passes - game_state.passes_used[Player::Human.index()];
+                    text.sections[0].value = match left {
+                        0 => format!("{} (no passes left)", turn),
+                        _ if game_state.pass_ends_game() => format!("{} Space passes and ends the game ({} left)", turn, left),
+                        _ => format!("{} Space passes ({} left)", turn, left),
+                    };
+                    text.sections[0].style.color = Color::srgb(0.2, 0.7, 0.2);
+                }
                 Player::Human => {
                     text.sections[0].value = "Your turn!".to_string();
                     text.sections[0].style.color = Color::srgb(0.2, 0.7, 0.2);
@@ -553,43 +1391,281 @@ pub fn check_game_over(
                     text.sections[0].value = "Smart AI calculating...".to_string();
                     text.sections[0].style.color = Color::srgb(0.7, 0.2, 0.2);
                 }
+                Player::Third if game_state.is_ai_turn() => {
+                    text.sections[0].value = format!("{} calculating...", game_state.player_name(Player::Third));
+                    text.sections[0].style.color = THIRD_PLAYER_COLOR;
+                }
+                Player::Third => {
+                    text.sections[0].value = format!("{}'s turn!", game_state.player_name(Player::Third));
+                    text.sections[0].style.color = THIRD_PLAYER_COLOR;
+                }
             }
         }
+
+        // Say why the AI played the move just made
+        if let Some(explanation) = game_state.last_explanation() {
+            text.sections[0].value = format!("{}\n{}", text.sections[0].value, explanation.summary(&game_state));
+        }
     }
 }
 
+// The AI's in-flight turn, tagged with the game generation it was started for
+#[derive(Resource, Default)]
+pub struct PendingAiMove {
+    pub generation: u64,
+    pub think_timer: f32,
+    // Search running on the async compute pool, and when it started
+    pub search: Option<(Task<Option<Position>>, Instant)>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn ai_move_system(
     mut game_state: ResMut<GameState>,
+    mut pending: ResMut<PendingAiMove>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    clock: Res<TurnClock>,
     time: Res<Time>,
+    mut outlook: ResMut<AiOutlook>,
+    mut diagnostics: Diagnostics,
 ) {
-    if game_state.game_over || game_state.current_player != Player::AI {
+    // Puzzles answer the player's moves themselves until the attempt is decided, and quiz
+    // answers get no reply at all
+    if game_state.game_over || !game_state.is_ai_turn() || puzzle.is_solving() || quiz.is_asking() {
+        pending.think_timer = 0.0;
+        pending.search = None;
+        return;
+    }
+
+    // A reset since this turn started cancels it; begin thinking afresh for the new game
+    if pending.generation != game_state.generation {
+        pending.generation = game_state.generation;
+        pending.think_timer = 0.0;
+        pending.search = None;
+    }
+
+    // Check on a running search without waiting for it
+    if let Some((task, search_started)) = pending.search.as_mut() {
+        let Some(best_move) = block_on(future::poll_once(task)) else {
+            return;
+        };
+        let search_started = *search_started;
+        pending.search = None;
+        diagnostics.add_measurement(&AI_SEARCH_TIME, || search_started.elapsed().as_secs_f64() * 1000.0);
+
+        if outlook.record_search(&game_state) {
+            game_state.resign();
+            return;
+        }
+        if let Some((x, y, z)) = best_move {
+            game_state.make_explained_move(pending.generation, x, y, z);
+            outlook.offer_if_dead_drawn(&game_state);
+        }
+        return;
+    }
+
+    // AI delay, shortened when the turn clock leaves less room to think
+    let delay = match clock.limit {
+        Some(_) => 1.5_f32.min(clock.remaining * 0.25),
+        None => 1.5,
+    };
+    pending.think_timer += time.delta_seconds();
+    if pending.think_timer < delay {
         return;
     }
+    pending.think_timer = 0.0;
 
-    // AI delay
-    static mut AI_TIMER: f32 = 0.0;
-    unsafe {
-        AI_TIMER += time.delta_seconds();
-        if AI_TIMER < 1.5 {
+    if game_state.phase == GamePhase::SwapOffer {
+        if game_state.ai.should_swap(&game_state) {
+            game_state.swap_sides();
             return;
         }
-        AI_TIMER = 0.0;
+        game_state.decline_swap();
+    }
+
+    if game_state.ai.should_pass(&game_state) {
+        game_state.pass();
+        return;
+    }
+
+    // Keep the search well inside whatever is left on the AI's own clock
+    let deadline = clock.limit.map(|_| Instant::now() + Duration::from_secs_f32(clock.remaining * 0.5));
+    let snapshot = game_state.clone();
+    let task = AsyncComputeTaskPool::get().spawn(async move { snapshot.ai.best_move(&snapshot, deadline) });
+    pending.search = Some((task, Instant::now()));
+}
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+// One full pulse of the thinking player's pieces, in seconds, and how brightly they glow at its peak
+const THINKING_PULSE_PERIOD: f32 = 1.2;
+const THINKING_PULSE_GLOW: f32 = 0.35;
+
+// While a search runs, keep the status line moving so the wait is visibly the AI thinking, with
+// how long it has taken and how fast the engine is going
+pub fn update_thinking_indicator(
+    pending: Res<PendingAiMove>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    mut status_text_query: Query<&mut Text, With<GameStatusText>>,
+) {
+    let Some((_, search_started)) = &pending.search else {
+        return;
+    };
+    let Ok(mut text) = status_text_query.get_single_mut() else {
+        return;
+    };
+
+    let elapsed = search_started.elapsed().as_secs_f32();
+    let spinner = SPINNER[(elapsed * 8.0) as usize % SPINNER.len()];
+    let name = match game_state.current_player {
+        Player::AI => "Smart AI",
+        player => game_state.player_name(player),
+    };
+    let unit = match settings.engine {
+        EngineKind::AlphaBeta => "positions",
+        EngineKind::Mcts | EngineKind::MctsStrict => "simulations",
+    };
+    let rate = game_state.ai.search_progress() as f32 / elapsed.max(0.001);
+    text.sections[0].value = format!("{} thinking {} {:.1}s, {:.0} {}/s", name, spinner, elapsed, rate, unit);
+}
+
+// The thinking player's pieces glow softly in and out while their search runs, and go back to
+// their theme's glow once it's done
+pub fn pulse_thinking_pieces(
+    pending: Res<PendingAiMove>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    cube_materials: Res<CubeMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut pulsing: Local<Option<Handle<StandardMaterial>>>,
+) {
+    let thinking = pending
+        .search
+        .as_ref()
+        .filter(|_| !settings.reduced_motion)
+        .and_then(|(_, search_started)| Some((cube_materials.piece(game_state.current_player.cell())?, search_started.elapsed())));
+    let glow = ColorTheme::in_use(&settings).palette().glow;
+
+    if let Some(handle) = pulsing.take() {
+        if thinking.as_ref().is_none_or(|(current, _)| *current != handle) {
+            if let Some(material) = materials.get_mut(&handle) {
+                material.emissive = LinearRgba::from(material.base_color.with_alpha(1.0)) * glow;
+            }
+        }
     }
+    let Some((handle, elapsed)) = thinking else {
+        return;
+    };
 
-    if let Some((x, y, z)) = game_state.ai.get_best_move(&game_state) {
-        game_state.make_move(x, y, z);
+    let phase = elapsed.as_secs_f32() / THINKING_PULSE_PERIOD * std::f32::consts::TAU;
+    let pulse = THINKING_PULSE_GLOW * (0.5 - 0.5 * phase.cos());
+    if let Some(material) = materials.get_mut(&handle) {
+        material.emissive = LinearRgba::from(material.base_color.with_alpha(1.0)) * (glow + pulse);
     }
+    *pulsing = Some(handle);
 }
 
+// Let the engine make the human's current move, either from the button or the E key
+pub fn play_for_me(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<PlayForMeButton>)>,
+    mut game_state: ResMut<GameState>,
+    clock: Res<TurnClock>,
+    mut diagnostics: Diagnostics,
+) {
+    let pressed = keyboard.just_pressed(KeyCode::KeyE)
+        || button_query.iter().any(|interaction| *interaction == Interaction::Pressed);
+    if !pressed || game_state.game_over || !game_state.is_human_turn() || game_state.phase == GamePhase::Editing {
+        return;
+    }
+
+    // Delegating a reply declines the pie rule swap, just like playing a move by hand
+    game_state.decline_swap();
+
+    let deadline = clock.limit.map(|_| Instant::now() + Duration::from_secs_f32(clock.remaining * 0.5));
+    let search_started = Instant::now();
+    let best_move = game_state.ai.best_move(&game_state, deadline);
+    diagnostics.add_measurement(&AI_SEARCH_TIME, || search_started.elapsed().as_secs_f64() * 1000.0);
+
+    if let Some((x, y, z)) = best_move {
+        game_state.make_delegated_move(x, y, z);
+    }
+}
+
+// Backspace on a human's turn takes back their latest move, and any replies to it, from the
+// game's recent snapshots: for a misclick, not a way back through the whole game. Puzzles and
+// the quiz judge the move as it was played, so they don't allow it
+pub fn rewind_turn(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut game_state: ResMut<GameState>,
+) {
+    if !keyboard.just_pressed(KeyCode::Backspace) || !game_state.is_human_turn() || puzzle.is_solving() || quiz.is_asking() {
+        return;
+    }
+
+    if !game_state.rewind_to_human_turn() {
+        info!("Nothing recent enough to take back");
+    }
+}
+
+// Ring the pieces the engine played on a human's behalf
+pub fn mark_delegated_moves(game_state: Res<GameState>, mut gizmos: Gizmos) {
+    for &index in &game_state.delegated_moves {
+        if let Some(&(x, y, z)) = game_state.move_history.get(index) {
+            gizmos.circle(cell_position(x, y, z), Dir3::Y, 0.6, Color::srgb(0.9, 0.8, 0.3));
+        }
+    }
+}
+
+// A ring around the latest piece played, by whoever played it, facing the camera so it reads
+// from any angle. Drawn in a lighter shade of the piece's own color
+pub fn mark_last_move(
+    game_state: Res<GameState>,
+    slice: Res<LayerSlice>,
+    cube_materials: Res<CubeMaterials>,
+    materials: Res<Assets<StandardMaterial>>,
+    camera_query: Query<&GlobalTransform, With<CameraController>>,
+    mut gizmos: Gizmos,
+) {
+    let Some((x, y, z)) = game_state.last_move.filter(|_| game_state.phase != GamePhase::Editing) else {
+        return;
+    };
+    let Some(color) = cube_materials
+        .piece(game_state.board[x][y][z])
+        .and_then(|handle| materials.get(&handle))
+        .map(|material| material.base_color.mix(&Color::WHITE, LAST_MOVE_LIGHTEN))
+    else {
+        return;
+    };
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    if slice.hides((x, y, z)) {
+        return;
+    }
+
+    let center = cell_position(x, y, z);
+    let Ok(facing) = Dir3::new(camera.translation() - center) else {
+        return;
+    };
+    for radius in LAST_MOVE_RADII {
+        gizmos.circle(center, facing, radius, color);
+    }
+}
+
+// With reduced motion on, a piece appears at its full size straight away
 pub fn animate_moves(
     mut commands: Commands,
     time: Res<Time>,
-    mut cube_query: Query<(Entity, &mut Transform, &mut MoveAnimation, &CubeMarker)>,
+    settings: Res<Settings>,
+    mut piece_query: Query<(Entity, &mut Transform, &mut MoveAnimation), With<Piece>>,
 ) {
-    for (entity, mut transform, mut animation, _cube_marker) in cube_query.iter_mut() {
+    for (entity, mut transform, mut animation) in piece_query.iter_mut() {
         animation.timer += time.delta_seconds();
         
-        if animation.timer >= animation.duration {
+        if animation.timer >= animation.duration || settings.reduced_motion {
             // Animation complete - set final scale and rotation, then remove animation component
             transform.scale = Vec3::splat(animation.target_scale);
             transform.rotation = Quat::IDENTITY; // Return to original orientation
@@ -617,81 +1693,161 @@ pub fn animate_moves(
     }
 }
 
-pub fn clear_animations_on_reset(
+pub fn animate_drops(
     mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mut cube_query: Query<(Entity, &mut Transform, &CubeMarker), With<MoveAnimation>>,
+    time: Res<Time>,
+    mut piece_query: Query<(Entity, &mut Transform, &mut DropAnimation, &Piece)>,
 ) {
-    if keyboard.just_pressed(KeyCode::KeyR) {
-        // Clear all animations and reset transforms
-        for (entity, mut transform, _) in cube_query.iter_mut() {
-            transform.scale = Vec3::ONE;
-            transform.rotation = Quat::IDENTITY;
-            commands.entity(entity).remove::<MoveAnimation>();
+    for (entity, mut transform, mut drop, piece) in piece_query.iter_mut() {
+        drop.velocity += DROP_GRAVITY * time.delta_seconds();
+        drop.offset += drop.velocity * time.delta_seconds();
+
+        // The resting position is the floor: bounce off it until the bounces die down
+        if drop.offset <= 0.0 {
+            drop.offset = 0.0;
+            drop.velocity = -drop.velocity * DROP_RESTITUTION;
+            if drop.velocity < DROP_SETTLE_SPEED {
+                commands.entity(entity).remove::<DropAnimation>();
+            }
         }
+
+        transform.translation = cell_position(piece.x, piece.y, piece.z) + Vec3::Y * drop.offset;
     }
 }
 
-pub fn play_sound_effects(
+// With reduced motion on, a piece goes at once
+pub fn animate_removals(
     mut commands: Commands,
-    mut sound_events: EventReader<SoundEvent>,
-    sounds: Res<GameSounds>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut piece_query: Query<(Entity, &mut Transform, &mut RemovalAnimation)>,
 ) {
-    if !sounds.enabled {
-        return;
+    for (entity, mut transform, mut animation) in piece_query.iter_mut() {
+        animation.timer += time.delta_seconds();
+
+        if animation.timer >= animation.duration || settings.reduced_motion {
+            // Gone, leaving the empty cell behind
+            commands.entity(entity).despawn();
+        } else {
+            // Ease-in shrink with a quarter turn, the reverse of the placement pop
+            let progress = animation.timer / animation.duration;
+            transform.scale = Vec3::splat(1.0 - progress * progress);
+            transform.rotation = Quat::from_rotation_y(progress * std::f32::consts::FRAC_PI_2);
+        }
     }
-    
-    for event in sound_events.read() {
-        match event {
-            SoundEvent::MovePlace => {
-                // Play the actual placement sound
-                info!("🔊 Move placed - playing satisfying placement sound");
-                commands.spawn(AudioBundle {
-                    source: sounds.move_place.clone(),
-                    settings: PlaybackSettings::DESPAWN,
-                });
-            }
-            SoundEvent::Hover => {
-                // Play a subtle hover sound (high frequency, quiet, brief)
-                info!("🔊 Cube hovered - playing subtle highlight sound");
-                // Future: Play soft "tick.ogg" or generate 1200Hz brief tone
-            }
-            SoundEvent::Win => {
-                // Play a victory sound (ascending notes, celebratory)
-                info!("🎉 Victory! - playing win fanfare");
-                // Future: Play "victory.ogg" or generate ascending note sequence
-            }
-            SoundEvent::Lose => {
-                // Play a defeat sound (descending notes, sympathetic)
-                info!("😞 Defeat - playing lose sound");
-                // Future: Play "defeat.ogg" or generate descending note sequence
-            }
-            SoundEvent::Reset => {
-                // Play a reset sound (neutral beep, fresh start)
-                info!("🔄 Game reset - playing refresh sound");
-                // Future: Play "reset.ogg" or generate neutral 600Hz tone
+}
+
+#[allow(clippy::type_complexity)]
+pub fn clear_animations_on_reset(
+    mut commands: Commands,
+    mut resets: EventReader<ResetRequested>,
+    mut piece_query: Query<(Entity, &mut Transform, &Piece, Has<RemovalAnimation>), Or<(With<MoveAnimation>, With<RemovalAnimation>, With<DropAnimation>)>>,
+) {
+    if resets.read().last().is_some() {
+        // Clear all animations: pieces on their way out go at once, the rest come to rest
+        for (entity, mut transform, piece, removing) in piece_query.iter_mut() {
+            if removing {
+                commands.entity(entity).despawn();
+                continue;
             }
+            transform.translation = cell_position(piece.x, piece.y, piece.z);
+            transform.scale = Vec3::ONE;
+            transform.rotation = Quat::IDENTITY;
+            commands.entity(entity).remove::<(MoveAnimation, DropAnimation)>();
         }
     }
 }
 
-pub fn randomize_light_on_reset(
-    _game_state: Res<GameState>,
-    mut light_query: Query<(&mut Transform, &mut DirectionalLight), With<GameLight>>,
+const HOVER_LINE_COLOR: Color = Color::srgba(0.8, 0.85, 1.0, 0.25);
+
+// While L is held, outline every line through the hovered cube, one hue per line. Otherwise,
+// with the setting on, an empty cube under the cursor gets its lines drawn faintly, to show
+// new players which cells a move there lines up with
+pub fn show_lines_through_hover(
     keyboard: Res<ButtonInput<KeyCode>>,
+    hovered_cubes: Query<&CubeMarker, With<HoveredCube>>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    mut gizmos: Gizmos,
 ) {
-    // Check if the game was just reset
-    if keyboard.just_pressed(KeyCode::KeyR) {
-        // Randomize light position and color
-        let new_position = generate_random_light_position();
-        let new_color = generate_random_light_color();
-        
-        for (mut light_transform, mut directional_light) in light_query.iter_mut() {
-            light_transform.translation = new_position;
-            light_transform.look_at(Vec3::ZERO, Vec3::Y);
-            directional_light.color = new_color;
+    let Some(hovered) = hovered_cubes.iter().next() else {
+        return;
+    };
+
+    if !keyboard.pressed(KeyCode::KeyL) {
+        if settings.hover_lines && game_state.board[hovered.x][hovered.y][hovered.z] == CellState::Empty {
+            for line in line_index().lines_through(hovered.x, hovered.y, hovered.z) {
+                gizmos.line(cell_position(line[0].0, line[0].1, line[0].2), cell_position(line[2].0, line[2].1, line[2].2), HOVER_LINE_COLOR);
+            }
         }
-        
-        info!("Light randomized - Position: {:?}, Color: {:?}", new_position, new_color);
+        return;
+    }
+
+    let lines: Vec<_> = line_index().lines_through(hovered.x, hovered.y, hovered.z).collect();
+    for (i, line) in lines.iter().enumerate() {
+        let hue = 360.0 * i as f32 / lines.len() as f32;
+        let line_color = Color::hsla(hue, 0.8, 0.6, 0.6);
+
+        let start = cell_position(line[0].0, line[0].1, line[0].2);
+        let end = cell_position(line[2].0, line[2].1, line[2].2);
+        gizmos.line(start, end, line_color);
+
+        // Outline the cells on the line, tinting occupied ones by their owner
+        for &(x, y, z) in line.iter() {
+            let outline_color = match game_state.board[x][y][z] {
+                CellState::Empty => line_color,
+                CellState::Human => Color::srgba(0.3, 0.9, 0.3, 0.8),
+                CellState::AI => Color::srgba(0.9, 0.3, 0.3, 0.8),
+                CellState::Third => Color::srgba(0.3, 0.5, 0.9, 0.8),
+                CellState::Neutral => Color::srgba(0.9, 0.9, 0.9, 0.8),
+                CellState::Blocked => Color::srgba(0.1, 0.1, 0.1, 0.8),
+            };
+            gizmos.cuboid(
+                Transform::from_translation(cell_position(x, y, z)).with_scale(Vec3::splat(0.9)),
+                outline_color,
+            );
+        }
+    }
+}
+
+pub fn configure_outline_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<OutlineGizmos>();
+    config.line_width = 4.0;
+}
+
+// The lattice between the cells: planes halfway between neighbouring cells and round the
+// outside, so the cube's structure reads while most cells are empty and see-through
+const FRAME_BOUNDS: [f32; 4] = [-3.0, -1.0, 1.0, 3.0];
+const FRAME_COLOR: Color = Color::srgba(0.55, 0.6, 0.7, 0.3);
+
+pub fn draw_board_frame(settings: Res<Settings>, mut gizmos: Gizmos) {
+    if !settings.board_frame {
+        return;
+    }
+
+    for a in FRAME_BOUNDS {
+        for b in FRAME_BOUNDS {
+            gizmos.line(Vec3::new(-3.0, a, b), Vec3::new(3.0, a, b), FRAME_COLOR);
+            gizmos.line(Vec3::new(a, -3.0, b), Vec3::new(a, 3.0, b), FRAME_COLOR);
+            gizmos.line(Vec3::new(a, b, -3.0), Vec3::new(a, b, 3.0), FRAME_COLOR);
+        }
+    }
+}
+
+// High-contrast mode draws bold outlines around every cell so the grid reads at a glance
+pub fn draw_high_contrast_outlines(
+    settings: Res<Settings>,
+    cubes_query: Query<&GlobalTransform, With<CubeMarker>>,
+    mut gizmos: Gizmos<OutlineGizmos>,
+) {
+    if !settings.high_contrast {
+        return;
     }
-} 
\ No newline at end of file
+
+    for cube_transform in cubes_query.iter() {
+        gizmos.cuboid(
+            Transform::from_translation(cube_transform.translation()).with_scale(Vec3::splat(0.8)),
+            Color::WHITE,
+        );
+    }
+}
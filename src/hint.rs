@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use std::time::{Duration, Instant};
+use ttt3d_core::game::{GamePhase, GameState};
+use crate::clock::TurnClock;
+use crate::graphics::{cell_position, CubeMarker};
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+use crate::settings::Settings;
+
+// How long a suggested cube stays highlighted
+const HINT_SECONDS: f32 = 4.0;
+
+const HINT_ARROW_COLOR: Color = Color::srgb(0.2, 0.9, 1.0);
+
+// Asks the engine for a move on the human's behalf, to show rather than play
+#[derive(Event)]
+pub struct HintRequest;
+
+// On the cube the engine suggests; removed once it times out or the position changes
+#[derive(Component)]
+pub struct HintHighlight {
+    pub remaining: f32,
+    // The game and move the hint was for
+    generation: u64,
+    ply: usize,
+}
+
+// H asks for a hint on the human's turn. Puzzles and the difficulty quiz are tests of
+// the player, so they get none
+pub fn request_hint(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut hint_requests: EventWriter<HintRequest>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyH)
+        || game_state.game_over
+        || !game_state.is_human_turn()
+        || game_state.phase == GamePhase::Editing
+        || puzzle.is_solving()
+        || quiz.is_asking()
+    {
+        return;
+    }
+    hint_requests.send(HintRequest);
+}
+
+// Run the game's engine from the human's side and highlight the cube it would play
+pub fn show_hint(
+    mut commands: Commands,
+    mut hint_requests: EventReader<HintRequest>,
+    game_state: Res<GameState>,
+    clock: Res<TurnClock>,
+    cube_query: Query<(Entity, &CubeMarker)>,
+    hint_query: Query<Entity, With<HintHighlight>>,
+) {
+    // Several presses in one frame still only need one search
+    if hint_requests.read().count() == 0 {
+        return;
+    }
+
+    // Leave the player most of their remaining time to act on the hint
+    let deadline = clock.limit.map(|_| Instant::now() + Duration::from_secs_f32(clock.remaining * 0.25));
+    let Some(suggestion) = game_state.ai.best_move(&game_state, deadline) else {
+        return;
+    };
+
+    for entity in hint_query.iter() {
+        commands.entity(entity).remove::<HintHighlight>();
+    }
+    if let Some((entity, _)) = cube_query.iter().find(|(_, cube)| (cube.x, cube.y, cube.z) == suggestion) {
+        commands.entity(entity).insert(HintHighlight {
+            remaining: HINT_SECONDS,
+            generation: game_state.generation,
+            ply: game_state.turns_taken(),
+        });
+    }
+}
+
+pub fn expire_hints(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut hint_query: Query<(Entity, &mut HintHighlight)>,
+) {
+    for (entity, mut hint) in hint_query.iter_mut() {
+        hint.remaining -= time.delta_seconds();
+        let stale = hint.generation != game_state.generation || hint.ply != game_state.turns_taken();
+        if hint.remaining <= 0.0 || stale {
+            commands.entity(entity).remove::<HintHighlight>();
+        }
+    }
+}
+
+// A bobbing arrow pointing down at the suggested cube, so it stands out from any angle. It
+// holds still with reduced motion on
+pub fn draw_hint_arrow(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    hint_query: Query<(&CubeMarker, &HintHighlight)>,
+    mut gizmos: Gizmos,
+) {
+    let bob = if settings.reduced_motion { 0.0 } else { (time.elapsed_seconds() * 4.0).sin() * 0.1 };
+    for (cube, _) in hint_query.iter() {
+        let target = cell_position(cube.x, cube.y, cube.z) + Vec3::Y * (0.5 + bob);
+        gizmos.arrow(target + Vec3::Y * 0.8, target, HINT_ARROW_COLOR);
+    }
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use ttt3d_core::game::{CellState, GameState};
+use crate::graphics::{cell_position, CubeMarker, HoveredCube};
+
+// Just outside a cell, so the outline wraps it without fighting its faces
+const RIM_SIZE: f32 = 0.84;
+
+// The hovered cell is outlined by a shell drawn around it, rather than by swapping its own
+// material, so whatever the cell shows (see-through, a hint, a selection) stays visible inside
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct HoverRimMaterial {
+    #[uniform(0)]
+    pub color: LinearRgba,
+}
+
+impl Material for HoverRimMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/hover_rim.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
+
+#[derive(Component)]
+pub struct HoverRim;
+
+// The shell's material, recolored with the board's color theme
+#[derive(Resource)]
+pub struct HoverRimHandle(pub Handle<HoverRimMaterial>);
+
+pub fn setup_hover_rim(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<HoverRimMaterial>>,
+) {
+    let material = materials.add(HoverRimMaterial {
+        color: LinearRgba::from(Color::srgba(0.6, 0.6, 0.6, 0.8)),
+    });
+    commands.spawn((
+        MaterialMeshBundle {
+            mesh: meshes.add(Cuboid::from_length(RIM_SIZE)),
+            material: material.clone(),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        HoverRim,
+    ));
+    commands.insert_resource(HoverRimHandle(material));
+}
+
+// Follow the hovered cube while it's one the human could play
+pub fn update_hover_rim(
+    hovered_query: Query<&CubeMarker, With<HoveredCube>>,
+    game_state: Res<GameState>,
+    mut rim_query: Query<(&mut Transform, &mut Visibility), With<HoverRim>>,
+) {
+    let Ok((mut transform, mut visibility)) = rim_query.get_single_mut() else {
+        return;
+    };
+
+    let target = hovered_query.iter().next().filter(|cube| {
+        game_state.board[cube.x][cube.y][cube.z] == CellState::Empty && game_state.is_human_turn() && !game_state.game_over
+    });
+    let shown = match target {
+        Some(cube) => {
+            let translation = cell_position(cube.x, cube.y, cube.z);
+            if transform.translation != translation {
+                transform.translation = translation;
+            }
+            Visibility::Inherited
+        }
+        None => Visibility::Hidden,
+    };
+    if *visibility != shown {
+        *visibility = shown;
+    }
+}
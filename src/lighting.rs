@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use chrono::Timelike;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use std::ops::Range;
+use ttt3d_core::game::GameState;
+use crate::graphics::GameLight;
+use crate::settings::Settings;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LightTheme {
+    // Follow the local time of day
+    #[default]
+    Auto,
+    Dawn,
+    Noon,
+    Dusk,
+    Night,
+}
+
+impl LightTheme {
+    pub fn name(self) -> &'static str {
+        match self {
+            LightTheme::Auto => "Auto",
+            LightTheme::Dawn => "Dawn",
+            LightTheme::Noon => "Noon",
+            LightTheme::Dusk => "Dusk",
+            LightTheme::Night => "Night",
+        }
+    }
+
+    pub fn next(self) -> LightTheme {
+        match self {
+            LightTheme::Auto => LightTheme::Dawn,
+            LightTheme::Dawn => LightTheme::Noon,
+            LightTheme::Noon => LightTheme::Dusk,
+            LightTheme::Dusk => LightTheme::Night,
+            LightTheme::Night => LightTheme::Auto,
+        }
+    }
+
+    // Pick the concrete theme for Auto from the local hour
+    pub fn resolve(self) -> LightTheme {
+        if self != LightTheme::Auto {
+            return self;
+        }
+
+        match chrono::Local::now().hour() {
+            5..=8 => LightTheme::Dawn,
+            9..=16 => LightTheme::Noon,
+            17..=20 => LightTheme::Dusk,
+            _ => LightTheme::Night,
+        }
+    }
+}
+
+// How far each game's look may stray from its theme: the sun's distance from the board, how
+// far it swings round the board, and how far its color shifts per channel
+pub const LIGHT_DISTANCE: Range<f32> = 8.0..12.0;
+const AZIMUTH_JITTER: f32 = 0.4;
+const COLOR_JITTER: f32 = 0.03;
+
+// A designed mood: sun color and strength, where it sits in the sky, and the fill light
+struct ThemePalette {
+    sun_color: [f32; 3],
+    illuminance: f32,
+    // Sun direction around the board (radians) and height above the horizon
+    azimuth: f32,
+    elevation: (f32, f32),
+    ambient_color: [f32; 3],
+    ambient_brightness: f32,
+}
+
+fn palette(theme: LightTheme) -> ThemePalette {
+    match theme {
+        LightTheme::Dawn => ThemePalette {
+            sun_color: [1.0, 0.78, 0.6],
+            illuminance: 2200.0,
+            azimuth: 0.5,
+            elevation: (0.25, 0.45),
+            ambient_color: [0.85, 0.75, 0.9],
+            ambient_brightness: 250.0,
+        },
+        LightTheme::Dusk => ThemePalette {
+            sun_color: [1.0, 0.6, 0.4],
+            illuminance: 2000.0,
+            azimuth: 3.6,
+            elevation: (0.2, 0.4),
+            ambient_color: [0.9, 0.7, 0.7],
+            ambient_brightness: 220.0,
+        },
+        LightTheme::Night => ThemePalette {
+            sun_color: [0.6, 0.7, 1.0],
+            illuminance: 900.0,
+            azimuth: 2.2,
+            elevation: (0.7, 1.0),
+            ambient_color: [0.5, 0.55, 0.8],
+            ambient_brightness: 150.0,
+        },
+        LightTheme::Noon | LightTheme::Auto => ThemePalette {
+            sun_color: [1.0, 0.98, 0.95],
+            illuminance: 3000.0,
+            azimuth: 1.2,
+            elevation: (0.9, 1.2),
+            ambient_color: [1.0, 1.0, 1.0],
+            ambient_brightness: 300.0,
+        },
+    }
+}
+
+// Where the sun goes for one game, and its color
+#[derive(Clone, Copy, Debug)]
+pub struct LightPlacement {
+    pub position: Vec3,
+    pub sun_color: [f32; 3],
+}
+
+// Each game varies the theme slightly; the variation is seeded from the profile's salt and
+// the game number, so the same sequence of looks comes back in every session
+pub fn light_placement(theme: LightTheme, salt: u64, generation: u64) -> LightPlacement {
+    let palette = palette(theme);
+    let mut rng = StdRng::seed_from_u64(salt ^ generation.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+    let distance: f32 = rng.gen_range(LIGHT_DISTANCE);
+    let azimuth = palette.azimuth + rng.gen_range(-AZIMUTH_JITTER..AZIMUTH_JITTER);
+    let elevation = rng.gen_range(palette.elevation.0..palette.elevation.1);
+    let position = Vec3::new(
+        distance * elevation.cos() * azimuth.cos(),
+        distance * elevation.sin(),
+        distance * elevation.cos() * azimuth.sin(),
+    );
+
+    let sun_color = palette.sun_color.map(|channel| (channel + rng.gen_range(-COLOR_JITTER..COLOR_JITTER)).clamp(0.0, 1.0));
+    LightPlacement { position, sun_color }
+}
+
+// Why a placement falls outside what its theme allows, if it does. Works back from the
+// placement itself, so a change to how placements are drawn can't hide behind it
+pub fn check_light_placement(theme: LightTheme, placement: &LightPlacement) -> Result<(), String> {
+    let palette = palette(theme);
+    let position = placement.position;
+    let distance = position.length();
+    if !(LIGHT_DISTANCE.start..=LIGHT_DISTANCE.end).contains(&distance) {
+        return Err(format!("the sun is {:.2} from the board, outside {:?}", distance, LIGHT_DISTANCE));
+    }
+
+    let elevation = (position.y / distance).asin();
+    if elevation < palette.elevation.0 - 1e-3 || elevation > palette.elevation.1 + 1e-3 {
+        return Err(format!("the sun is {:.2} rad above the horizon, outside {:?}", elevation, palette.elevation));
+    }
+
+    // Angles wrap, so compare the swing the short way round
+    let swing = (position.z.atan2(position.x) - palette.azimuth).rem_euclid(TAU);
+    let swing = swing.min(TAU - swing);
+    if swing > AZIMUTH_JITTER + 1e-3 {
+        return Err(format!("the sun has swung {:.2} rad round the board, more than {}", swing, AZIMUTH_JITTER));
+    }
+
+    for (channel, base) in placement.sun_color.into_iter().zip(palette.sun_color) {
+        if !(0.0..=1.0).contains(&channel) || (channel - base).abs() > COLOR_JITTER + 1e-4 {
+            return Err(format!("the sun color {:?} strays too far from {:?}", placement.sun_color, palette.sun_color));
+        }
+    }
+    Ok(())
+}
+
+// Last theme/game the light was set up for, so it only changes on a new game or setting
+#[derive(Default)]
+pub struct AppliedLighting {
+    theme: Option<LightTheme>,
+    generation: u64,
+}
+
+pub fn apply_light_theme(
+    settings: Res<Settings>,
+    game_state: Res<GameState>,
+    mut applied: Local<AppliedLighting>,
+    mut ambient: ResMut<AmbientLight>,
+    mut light_query: Query<(&mut Transform, &mut DirectionalLight), With<GameLight>>,
+) {
+    let theme = settings.light_theme.resolve();
+    // Reduced motion holds the light where the first game's was, rather than moving it each game
+    let generation = if settings.reduced_motion { 0 } else { game_state.generation };
+    if applied.theme == Some(theme) && applied.generation == generation {
+        return;
+    }
+    applied.theme = Some(theme);
+    applied.generation = generation;
+
+    let palette = palette(theme);
+    let LightPlacement { position, sun_color: [r, g, b] } = light_placement(theme, settings.light_salt, generation);
+    for (mut light_transform, mut directional_light) in light_query.iter_mut() {
+        light_transform.translation = position;
+        light_transform.look_at(Vec3::ZERO, Vec3::Y);
+        directional_light.color = Color::srgb(r, g, b);
+        directional_light.illuminance = palette.illuminance;
+    }
+
+    let [r, g, b] = palette.ambient_color;
+    ambient.color = Color::srgb(r, g, b);
+    ambient.brightness = palette.ambient_brightness;
+
+    info!("Lighting theme {} - Position: {:?}", theme.name(), position);
+}
@@ -1,37 +1,311 @@
+use bevy::asset::io::AssetSourceBuilder;
+use bevy::input::InputSystem;
 use bevy::prelude::*;
+use bevy::ui::UiSystem;
+use bevy::window::WindowMode;
+use ttt3d_core::game::*;
 
-mod game;
-mod ai;
+mod adaptive;
+mod analysis;
+#[cfg(feature = "audio")]
+mod audio;
+mod banner;
+mod capture;
+mod cell_info;
+mod cli;
+mod clock;
+mod colors;
+mod coordinates;
+mod credits;
+mod cursor;
+mod deliberation;
+mod editor;
+mod evaluation;
+mod game_over;
 mod graphics;
+mod hint;
+mod hover_rim;
+mod lighting;
+mod menu;
+mod minimap;
+mod modal;
+mod mods;
+mod network;
+mod observer;
+mod parallax;
+mod ponder;
+mod profiler;
+mod puzzle;
+mod quiz;
+mod recovery;
+mod reminder;
+mod replay;
+mod resign;
+mod search_overlay;
+mod session;
+mod settings;
+mod share;
+mod slice;
+mod snapping;
+mod stats;
+mod telemetry;
+mod threat_overlay;
+mod tips;
+mod tournament;
+mod train;
+mod tune;
+mod vault;
+mod visual;
+mod xray;
 
-use game::*;
+use adaptive::*;
+use analysis::*;
+#[cfg(feature = "audio")]
+use audio::SoundPlugin;
+use banner::*;
+use capture::CapturePlugin;
+use cell_info::*;
+use cli::LaunchOptions;
+use clock::*;
+use colors::cycle_piece_colors;
+use coordinates::*;
+use credits::*;
+use cursor::*;
+use deliberation::*;
+use editor::*;
+use evaluation::*;
+use game_over::*;
 use graphics::*;
+use hint::*;
+use hover_rim::*;
+use lighting::*;
+use menu::*;
+use minimap::*;
+use mods::*;
+use observer::*;
+use parallax::*;
+use ponder::*;
+use profiler::{ProfiledSet, ProfilerPlugin};
+use puzzle::*;
+use quiz::*;
+use recovery::*;
+use reminder::*;
+use resign::*;
+use search_overlay::*;
+use session::*;
+use settings::*;
+use share::*;
+use slice::*;
+use snapping::*;
+use stats::*;
+use telemetry::*;
+use threat_overlay::*;
+use tips::*;
+use tournament::*;
+use vault::*;
+use xray::*;
 
 fn main() {
-    App::new()
+    let launch = LaunchOptions::from_env();
+    let profile = Profile::default();
+    let mut settings = Settings::load(&profile);
+    let mut vault = ProfileVault::open(&profile);
+    let stats = PlayerStats::load(&profile, &mut vault);
+    let analysis = AnalysisJob::resume(&profile, &vault);
+    let telemetry = Telemetry::load(&settings, &profile);
+    launch.apply(&mut settings);
+
+    if let Some(games) = launch.tune {
+        tune::run(games, settings.rules);
+        return;
+    }
+    if let Some(games) = launch.train_eval {
+        train::run(games, settings.rules);
+        return;
+    }
+    #[cfg(feature = "solver")]
+    if launch.check_puzzles {
+        let passed = puzzle::check_puzzles(&launch.puzzle_dir.clone().unwrap_or_else(puzzle::puzzle_dir));
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+    if let Some(bless) = launch.visual_check {
+        let exit = visual::run(bless);
+        std::process::exit(if exit.is_success() { 0 } else { 1 });
+    }
+
+    let game_state = GameState {
+        ai: settings.build_engine(),
+        ..default()
+    };
+
+    let mut app = App::new();
+    app
+        // Piece packs load from `mods://`, which must be registered before the asset plugin
+        .register_asset_source(MODS_DIR, AssetSourceBuilder::platform_default(MODS_DIR, None))
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "3D Tic-Tac-Toe".into(),
                 resolution: (1024., 768.).into(),
+                mode: if launch.fullscreen { WindowMode::BorderlessFullscreen } else { WindowMode::Windowed },
                 ..default()
             }),
             ..default()
         }))
-        .init_resource::<GameState>()
+        .add_plugins((ProfilerPlugin, CapturePlugin, MaterialPlugin::<HoverRimMaterial>::default()))
+        .insert_resource(UiScale(settings.ui_scale))
+        .insert_resource(settings)
+        .insert_resource(profile)
+        .insert_resource(stats)
+        .insert_resource(analysis)
+        .init_resource::<ThinkingTimes>()
+        .init_resource::<SettingsPanel>()
+        .init_resource::<SnapDebug>()
+        .insert_resource(PiecePacks::discover())
+        .init_resource::<AppliedPiecePack>()
+        .insert_resource(game_state)
+        .init_resource::<TurnClock>()
+        .init_resource::<PendingAiMove>()
+        .init_resource::<Pondering>()
+        .init_resource::<PuzzleMode>()
+        .init_resource::<DifficultyQuiz>()
+        .init_resource::<BoardEditor>()
+        .init_resource::<Tournament>()
+        .init_resource::<LineTip>()
+        .init_resource::<PlayReminder>()
+        .init_resource::<SearchOverlay>()
+        .init_resource::<AiOutlook>()
+        .init_resource::<DifficultyController>()
+        .init_resource::<PendingShareCard>()
+        .init_resource::<LayerSlice>()
+        .init_resource::<StatsRecovery>()
+        .init_resource::<CoordinateOverlay>()
+        .init_resource::<ThreatOverlay>()
+        .init_resource::<XRayHover>()
+        .init_resource::<BoardTilt>()
+        .insert_resource(telemetry)
+        .init_resource::<CreditsScreen>()
+        .init_resource::<GameOverOverlay>()
+        .init_resource::<SessionScore>()
+        .insert_resource(PassphrasePrompt::for_vault(&vault))
+        .insert_resource(vault)
+        .init_resource::<Evaluation>()
+        .init_state::<AppState>()
         .add_event::<SoundEvent>()
-        .add_systems(Startup, setup_scene)
+        .add_event::<ResetRequested>()
+        .add_event::<HintRequest>()
+        .init_gizmo_group::<OutlineGizmos>()
+        .add_systems(Startup, (setup_scene, setup_settings_panel, setup_turn_clock_ui, setup_board_editor_ui, setup_tournament_ui, setup_thinking_graph, setup_analysis_ui, setup_observer_camera, setup_line_tip, setup_evaluation_bar, setup_play_reminder, setup_search_overlay, setup_passphrase_prompt, setup_stats_recovery, setup_draw_offer_text, setup_cell_info, setup_coordinate_overlay, (setup_telemetry_screen, setup_credits_screen, setup_menus, setup_game_over_overlay, setup_session_score, setup_hover_rim, setup_minimap), configure_outline_gizmos))
         .add_systems(Update, (
-            handle_hover,
-            handle_input,
-            rotate_camera,
-            trigger_move_animations,
-            animate_moves,
-            clear_animations_on_reset,
-            update_cube_materials,
+            handle_hover.run_if(in_state(AppState::Playing)).in_set(ProfiledSet::Picking),
+            (request_reset, reset_game, handle_input.run_if(in_state(AppState::Playing)).in_set(ProfiledSet::Picking)).chain(),
+            (toggle_camera_mode, fly_to_winning_line.after(check_game_over), choose_camera_preset, rotate_camera, fly_camera, tilt_board).chain(),
+            (trigger_move_animations, sync_pieces).chain(),
+            (animate_moves, animate_drops),
+            animate_removals,
+            clear_animations_on_reset.after(request_reset),
+            update_cube_materials.in_set(ProfiledSet::Materials),
+            update_hover_rim.after(handle_hover).in_set(ProfiledSet::Materials),
             check_game_over,
-            ai_move_system,
-            randomize_light_on_reset,
-            play_sound_effects,
+            update_score_text,
+            (ponder_on_human_turn, ai_move_system, update_thinking_indicator.after(check_game_over), pulse_thinking_pieces, flash_considered_cells).chain().run_if(in_state(AppState::Playing)).in_set(ProfiledSet::Ai),
+            apply_light_theme.in_set(ProfiledSet::Materials),
+            show_lines_through_hover.in_set(ProfiledSet::Overlays),
+            (play_for_me, rewind_turn).run_if(in_state(AppState::Playing)),
+            (mark_delegated_moves, mark_last_move).in_set(ProfiledSet::Overlays),
+            draw_high_contrast_outlines.in_set(ProfiledSet::Overlays),
+            draw_board_frame.in_set(ProfiledSet::Overlays),
         ))
-        .run();
+        .add_systems(Update, (
+            toggle_settings_panel,
+            handle_ui_scale_slider,
+            cycle_turn_time_limit,
+            cycle_piece_pack,
+            // Panel options are independent of each other
+            (
+                toggle_pie_rule,
+                cycle_third_player,
+                cycle_piece_colors,
+                toggle_notakto,
+                cycle_blocked_cells,
+                toggle_fading,
+                toggle_line_scoring,
+                cycle_passes,
+                cycle_light_theme,
+                cycle_engine,
+                cycle_think_time,
+                toggle_pondering,
+                toggle_piece_drop,
+                cycle_capture_size,
+                toggle_high_contrast,
+                cycle_hit_margin,
+                cycle_snap_angle,
+                toggle_reduced_motion,
+                cycle_play_reminder,
+                toggle_profile_lock,
+            ),
+            toggle_learned_eval,
+            cycle_color_theme,
+            toggle_colorblind,
+            toggle_board_frame,
+            toggle_hover_lines,
+            cycle_camera_damping,
+            cycle_board_tilt,
+            sync_rules,
+            update_variant_labels,
+            apply_ui_scale,
+            apply_piece_pack,
+            fallback_on_failed_pack_assets,
+        ).chain())
+        .add_systems(Update, (spawn_win_banner, animate_win_banner).chain())
+        .add_systems(Update, (request_share_card, compose_share_card).chain())
+        .add_systems(Update, (update_layer_slice.after(rotate_camera).before(handle_hover), hide_peeled_pieces, draw_peeled_layers.in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, scroll_pick_layer.after(update_layer_slice).before(handle_hover).run_if(in_state(AppState::Playing)).in_set(ProfiledSet::Picking))
+        .add_systems(Update, draw_xray_occluders.after(handle_hover).in_set(ProfiledSet::Overlays))
+        .add_systems(Update, (toggle_snap_debug, draw_snap_cone.after(handle_hover).in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, (track_play_time, dismiss_play_reminder, update_play_reminder).chain())
+        .add_systems(Update, (toggle_search_overlay, collect_search_stats, update_visit_labels.in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, (toggle_threat_overlay, update_threat_overlay.before(update_cube_materials).in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, (toggle_minimap, click_minimap.run_if(in_state(AppState::Playing)).in_set(ProfiledSet::Picking), update_minimap.in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, (toggle_coordinate_overlay, (update_coordinate_labels.after(rotate_camera), draw_coordinate_axes).in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, (offer_draw.run_if(in_state(AppState::Playing)), update_draw_offer_text).chain())
+        .add_systems(Update, (track_adaptive_game, toggle_adaptive_difficulty, sync_adaptive_level, update_adaptive_label).chain())
+        .add_systems(PreUpdate, (type_passphrase, answer_stats_recovery, answer_telemetry_screen, answer_credits_screen).chain().after(InputSystem))
+        .add_systems(PreUpdate, hold_input_in_menus.after(UiSystem::Focus))
+        .add_systems(Update, (press_menu_buttons, pause_game.run_if(in_state(AppState::Playing)), follow_game_over.after(check_game_over), show_menus).chain())
+        .add_systems(OnExit(AppState::Playing), clear_hover)
+        .add_systems(OnEnter(AppState::MainMenu), clear_session_score)
+        .add_systems(Update, (record_session_result.after(check_game_over), update_session_score).chain())
+        .add_systems(Update, (time_game, press_game_over_buttons, update_game_over_overlay.after(follow_game_over)).chain())
+        .add_systems(Update, (update_passphrase_prompt, update_stats_recovery))
+        .add_systems(Update, (open_telemetry_screen, record_telemetry, update_telemetry_screen).chain())
+        .add_systems(Update, (open_credits_screen, update_credits_screen).chain())
+        .add_systems(Update, update_cell_info.after(handle_hover))
+        .add_systems(Update, (move_cell_cursor.after(tilt_board).after(handle_input).run_if(in_state(AppState::Playing)), draw_cell_cursor.in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, (request_hint.run_if(in_state(AppState::Playing)), show_hint, expire_hints, draw_hint_arrow.in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, (
+            toggle_board_editor,
+            edit_cells,
+            handle_editor_buttons,
+            update_board_editor_ui,
+            draw_editor_gizmos,
+        ).chain())
+        .add_systems(Update, (record_thinking_times, update_thinking_graph).chain())
+        .add_systems(Update, (run_evaluation, update_evaluation_bar).chain())
+        .add_systems(Update, (offer_line_tip.after(record_thinking_times), update_line_tip_text, draw_line_tip.in_set(ProfiledSet::Overlays)).chain())
+        .add_systems(Update, (toggle_observer_view, sync_observer_camera.after(rotate_camera)).chain())
+        .add_systems(Update, (toggle_analysis, run_analysis, update_analysis_panel, mark_key_moment).chain())
+        .add_systems(Update, (toggle_tournament_screen, run_tournament, update_tournament_text).chain())
+        .add_systems(Update, (
+            sync_turn_clock_settings,
+            tick_turn_clock.run_if(in_state(AppState::Playing)),
+            update_turn_clock_text,
+        ).chain().before(check_game_over));
+
+    // Subsystems a slimmed-down build can leave out; see "Build Features" in the README
+    #[cfg(feature = "audio")]
+    app.add_plugins(SoundPlugin);
+    #[cfg(feature = "solver")]
+    app.add_plugins((PuzzlePlugin, QuizPlugin));
+
+    app.run();
 } 
\ No newline at end of file
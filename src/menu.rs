@@ -0,0 +1,239 @@
+use bevy::app::AppExit;
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use ttt3d_core::game::{GameState, Player};
+use crate::cli::Variant;
+use crate::credits::CreditsScreen;
+use crate::graphics::{HoveredCube, ResetRequested};
+use crate::settings::{Profile, Settings, SettingsPanel, SettingsPanelRoot};
+
+// Where the app is: the main menu it opens on, a game being played or paused, or a game that
+// has just ended. Systems that take moves, search or run the clock only run while Playing
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum MenuButton {
+    NewGame,
+    Mode,
+    Settings,
+    Credits,
+    Quit,
+    Resume,
+    MainMenu,
+}
+
+#[derive(Component)]
+pub struct MainMenuRoot;
+
+#[derive(Component)]
+pub struct PauseMenuRoot;
+
+#[derive(Component)]
+pub struct ModeLabel;
+
+fn mode_label(settings: &Settings) -> String {
+    format!("Mode: {}", Variant::of(&settings.rules).name())
+}
+
+fn spawn_menu<'a>(commands: &'a mut Commands, root: impl Bundle, title: &str) -> EntityCommands<'a> {
+    let mut menu = commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+            background_color: Color::srgba(0.05, 0.05, 0.08, 0.75).into(),
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(8),
+            ..default()
+        },
+        root,
+    ));
+    menu.with_children(|menu| {
+        menu.spawn(TextBundle::from_section(
+            title,
+            TextStyle {
+                font_size: 40.0,
+                color: Color::srgb(0.9, 0.8, 0.4),
+                ..default()
+            },
+        ));
+    });
+    menu
+}
+
+fn spawn_button(parent: &mut ChildBuilder, button: MenuButton, label: String) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(260.0),
+                    padding: UiRect::axes(Val::Px(10.0), Val::Px(6.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.2, 0.2, 0.3, 0.85).into(),
+                ..default()
+            },
+            button,
+        ))
+        .with_children(|parent| {
+            let mut text = parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            if button == MenuButton::Mode {
+                text.insert(ModeLabel);
+            }
+        });
+}
+
+pub fn setup_menus(mut commands: Commands, settings: Res<Settings>) {
+    spawn_menu(&mut commands, MainMenuRoot, "3D Tic-Tac-Toe").with_children(|menu| {
+        spawn_button(menu, MenuButton::NewGame, "New Game".to_string());
+        spawn_button(menu, MenuButton::Mode, mode_label(&settings));
+        spawn_button(menu, MenuButton::Settings, "Settings".to_string());
+        spawn_button(menu, MenuButton::Credits, "Credits".to_string());
+        spawn_button(menu, MenuButton::Quit, "Quit".to_string());
+    });
+    spawn_menu(&mut commands, PauseMenuRoot, "Paused").with_children(|menu| {
+        spawn_button(menu, MenuButton::Resume, "Resume [P]".to_string());
+        spawn_button(menu, MenuButton::MainMenu, "Main Menu".to_string());
+    });
+}
+
+// The game under a menu doesn't see its keys, which otherwise start puzzles, reset the board
+// and the like. P still unpauses, and with the settings panel open from the main menu, its keys
+// go to the panel
+pub fn hold_input_in_menus(
+    state: Res<State<AppState>>,
+    panel: Res<SettingsPanel>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    match state.get() {
+        AppState::Paused if keyboard.just_pressed(KeyCode::KeyP) => next_state.set(AppState::Playing),
+        AppState::MainMenu if panel.open => return,
+        AppState::MainMenu | AppState::Paused => {}
+        AppState::Playing | AppState::GameOver => return,
+    }
+    keyboard.reset_all();
+}
+
+// P pauses a game in progress, while the settings panel, which has its own use for it, is closed
+pub fn pause_game(keyboard: Res<ButtonInput<KeyCode>>, panel: Res<SettingsPanel>, mut next_state: ResMut<NextState<AppState>>) {
+    if !panel.open && keyboard.just_pressed(KeyCode::KeyP) {
+        next_state.set(AppState::Paused);
+    }
+}
+
+// A game that ends moves on to GameOver, and a new one back to Playing
+pub fn follow_game_over(state: Res<State<AppState>>, game_state: Res<GameState>, mut next_state: ResMut<NextState<AppState>>) {
+    match state.get() {
+        AppState::Playing if game_state.game_over => next_state.set(AppState::GameOver),
+        AppState::GameOver if !game_state.game_over => next_state.set(AppState::Playing),
+        _ => {}
+    }
+}
+
+// Hovering stops outside play, so the cube last hovered shouldn't stay lit
+pub fn clear_hover(mut commands: Commands, hovered_query: Query<Entity, With<HoveredCube>>) {
+    for entity in hovered_query.iter() {
+        commands.entity(entity).remove::<HoveredCube>();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn press_menu_buttons(
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    profile: Res<Profile>,
+    mut settings: ResMut<Settings>,
+    mut panel: ResMut<SettingsPanel>,
+    mut panel_query: Query<&mut Visibility, With<SettingsPanelRoot>>,
+    mut credits: ResMut<CreditsScreen>,
+    mut game_state: ResMut<GameState>,
+    mut resets: EventWriter<ResetRequested>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            // A new game undoes any rematch's change of who starts
+            MenuButton::NewGame => {
+                game_state.first_player = Player::Human;
+                resets.send(ResetRequested);
+                next_state.set(AppState::Playing);
+            }
+            // The next new game is played as the chosen variant, as if set in the panel
+            MenuButton::Mode => {
+                let variant = Variant::of(&settings.rules);
+                let index = Variant::ALL.iter().position(|&v| v == variant).unwrap_or(0);
+                let rules = Variant::ALL[(index + 1) % Variant::ALL.len()].apply(settings.rules);
+                settings.set_rules(rules);
+                settings.save(&profile);
+            }
+            MenuButton::Settings => {
+                panel.open = !panel.open;
+                for mut visibility in panel_query.iter_mut() {
+                    *visibility = if panel.open { Visibility::Visible } else { Visibility::Hidden };
+                }
+            }
+            MenuButton::Credits => credits.show(),
+            MenuButton::Quit => {
+                exit.send(AppExit::Success);
+            }
+            MenuButton::Resume => next_state.set(AppState::Playing),
+            MenuButton::MainMenu => next_state.set(AppState::MainMenu),
+        }
+    }
+}
+
+pub fn show_menus(
+    state: Res<State<AppState>>,
+    settings: Res<Settings>,
+    mut main_query: Query<&mut Visibility, (With<MainMenuRoot>, Without<PauseMenuRoot>)>,
+    mut pause_query: Query<&mut Visibility, With<PauseMenuRoot>>,
+    mut label_query: Query<&mut Text, With<ModeLabel>>,
+) {
+    // The panel's variant options change the mode too
+    if settings.is_changed() {
+        let label = mode_label(&settings);
+        for mut text in label_query.iter_mut() {
+            if text.sections[0].value != label {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+    if !state.is_changed() {
+        return;
+    }
+
+    let shown = |visible: bool| if visible { Visibility::Visible } else { Visibility::Hidden };
+    for mut visibility in main_query.iter_mut() {
+        *visibility = shown(*state.get() == AppState::MainMenu);
+    }
+    for mut visibility in pause_query.iter_mut() {
+        *visibility = shown(*state.get() == AppState::Paused);
+    }
+}
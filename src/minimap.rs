@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{CellState, GamePhase, GameState, Position};
+use crate::graphics::{cell_position, CubeMaterials};
+use crate::settings::{Profile, Settings};
+
+const CELL_SIZE: f32 = 18.0;
+const CELL_GAP: f32 = 2.0;
+const EMPTY_COLOR: Color = Color::srgba(0.35, 0.35, 0.35, 0.7);
+const HOVERED_COLOR: Color = Color::srgba(0.7, 0.7, 0.7, 0.9);
+// Matches the blocked cell material in graphics.rs
+const BLOCKED_COLOR: Color = Color::srgb(0.05, 0.05, 0.05);
+
+// A flat map of the board beside it: one 3x3 grid per layer, laid out like the notation, layers
+// along z, rows along y with the top row highest, cells along x. Clicking an empty cell plays
+// there, for cells that are hard to reach in 3D. F8 hides it
+#[derive(Component)]
+pub struct MinimapRoot;
+
+#[derive(Component)]
+pub struct MinimapCell(pub Position);
+
+pub fn setup_minimap(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(44.0),
+                    right: Val::Px(10.0),
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            },
+            MinimapRoot,
+        ))
+        .with_children(|root| {
+            for z in 0..3 {
+                root.spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(CELL_GAP),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|layer| {
+                    layer.spawn(TextBundle::from_section(
+                        format!("z = {}", z),
+                        TextStyle {
+                            font_size: 13.0,
+                            color: Color::srgb(0.8, 0.8, 0.8),
+                            ..default()
+                        },
+                    ));
+                    for y in (0..3).rev() {
+                        layer
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    column_gap: Val::Px(CELL_GAP),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|row| {
+                                for x in 0..3 {
+                                    row.spawn((
+                                        ButtonBundle {
+                                            style: Style {
+                                                width: Val::Px(CELL_SIZE),
+                                                height: Val::Px(CELL_SIZE),
+                                                ..default()
+                                            },
+                                            background_color: EMPTY_COLOR.into(),
+                                            ..default()
+                                        },
+                                        MinimapCell((x, y, z)),
+                                    ));
+                                }
+                            });
+                    }
+                });
+            }
+        });
+}
+
+pub fn toggle_minimap(keyboard: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>, profile: Res<Profile>) {
+    if !keyboard.just_pressed(KeyCode::F8) {
+        return;
+    }
+
+    settings.minimap = !settings.minimap;
+    settings.save(&profile);
+}
+
+// The same moves a click on the cube would make; `handle_input` leaves clicks over the map alone
+pub fn click_minimap(cell_query: Query<(&Interaction, &MinimapCell), Changed<Interaction>>, mut game_state: ResMut<GameState>) {
+    if game_state.game_over || !game_state.is_human_turn() || game_state.phase == GamePhase::Editing {
+        return;
+    }
+
+    for (interaction, cell) in cell_query.iter() {
+        let (x, y, z) = cell.0;
+        if *interaction == Interaction::Pressed && game_state.board[x][y][z] == CellState::Empty {
+            game_state.make_move(x, y, z);
+        }
+    }
+}
+
+// Each cell in its piece's color, whatever the pack, theme or player's choice made it, and the
+// cell under the cursor outlined on the board
+pub fn update_minimap(
+    settings: Res<Settings>,
+    game_state: Res<GameState>,
+    cube_materials: Res<CubeMaterials>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut root_query: Query<&mut Visibility, With<MinimapRoot>>,
+    mut cell_query: Query<(&Interaction, &MinimapCell, &mut BackgroundColor)>,
+    mut gizmos: Gizmos,
+) {
+    let shown = if settings.minimap { Visibility::Inherited } else { Visibility::Hidden };
+    for mut visibility in root_query.iter_mut() {
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+    if !settings.minimap {
+        return;
+    }
+
+    for (interaction, cell, mut background) in cell_query.iter_mut() {
+        let (x, y, z) = cell.0;
+        let state = game_state.board[x][y][z];
+        let color = match state {
+            CellState::Empty if *interaction != Interaction::None => HOVERED_COLOR,
+            CellState::Empty => EMPTY_COLOR,
+            CellState::Blocked => BLOCKED_COLOR,
+            _ => cube_materials
+                .piece(state)
+                .and_then(|handle| materials.get(&handle))
+                .map_or(EMPTY_COLOR, |material| material.base_color),
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+        if *interaction != Interaction::None {
+            gizmos.cuboid(Transform::from_translation(cell_position(x, y, z)).with_scale(Vec3::splat(0.95)), Color::WHITE);
+        }
+    }
+}
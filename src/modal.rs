@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+// The screens that take over the window until they're answered: the passphrase prompt, stats
+// recovery, the usage summary and the credits. Each is a dark panel centered over everything
+// else, holding one text, and is hidden until its screen shows it
+const PANEL_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.95);
+pub const PANEL_PADDING: f32 = 16.0;
+// Taller text is clipped, for a screen to scroll
+const PANEL_MAX_HEIGHT: f32 = 85.0;
+const FONT_SIZE: f32 = 18.0;
+
+// Spawn a modal screen, with `root_marker` on the node covering the window and `text_marker` on
+// its text, which starts out empty. Returns the text, for a screen whose text never changes to
+// fill in. Clicks on the screen don't reach buttons behind it
+pub fn spawn_modal(commands: &mut Commands, root_marker: impl Component, text_marker: impl Component, max_width: f32) -> Entity {
+    let mut text = Entity::PLACEHOLDER;
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                focus_policy: FocusPolicy::Block,
+                ..default()
+            },
+            root_marker,
+        ))
+        .with_children(|root| {
+            root.spawn(NodeBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(PANEL_PADDING)),
+                    max_width: Val::Px(max_width),
+                    max_height: Val::Percent(PANEL_MAX_HEIGHT),
+                    flex_direction: FlexDirection::Column,
+                    overflow: Overflow::clip_y(),
+                    ..default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..default()
+            })
+            .with_children(|panel| {
+                text = panel
+                    .spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: FONT_SIZE,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        )
+                        .with_text_justify(JustifyText::Center),
+                        text_marker,
+                    ))
+                    .id();
+            });
+        });
+    text
+}
+
+// While a modal screen is up it takes every key and click, read first for its own answers, so
+// nothing behind it sees them
+pub fn swallow_input(keyboard: &mut ButtonInput<KeyCode>, mouse: &mut ButtonInput<MouseButton>) {
+    keyboard.reset_all();
+    mouse.reset_all();
+}
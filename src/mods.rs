@@ -0,0 +1,334 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Component, Path};
+use crate::colors::{resolve_piece_colors, ColorTheme, COLORBLIND_PIECE_COLORS};
+use crate::graphics::{CubeMarker, CubeMaterials, GameMeshes, THREAT_TINT_ALPHA};
+use crate::hover_rim::{HoverRimHandle, HoverRimMaterial};
+use crate::settings::{PiecePackLabel, Profile, Settings, SettingsPanel};
+use crate::xray::XRAY_ALPHA;
+
+pub const MODS_DIR: &str = "mods";
+pub const CLASSIC_PACK_ID: &str = "classic";
+
+const MANIFEST_FILE: &str = "pack.ron";
+const MESH_EXTENSIONS: [&str; 2] = ["gltf", "glb"];
+const SOUND_EXTENSIONS: [&str; 2] = ["ogg", "mp3"];
+
+// Contents of `mods/<pack>/pack.ron`; anything left out falls back to the classic look
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct PackManifest {
+    pub name: String,
+    pub author: Option<String>,
+    // glTF mesh for the cells, relative to the pack folder (e.g. "piece.glb#Mesh0/Primitive0")
+    pub mesh: Option<String>,
+    pub human_color: Option<[f32; 3]>,
+    pub ai_color: Option<[f32; 3]>,
+    pub third_color: Option<[f32; 3]>,
+    // Shared piece color for the Notakto variant
+    pub neutral_color: Option<[f32; 3]>,
+    pub empty_color: Option<[f32; 4]>,
+    pub metallic: Option<f32>,
+    pub roughness: Option<f32>,
+    // Placement sound, relative to the pack folder
+    pub place_sound: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PiecePack {
+    pub id: String,
+    pub manifest: PackManifest,
+}
+
+impl PiecePack {
+    fn classic() -> Self {
+        Self {
+            id: CLASSIC_PACK_ID.to_string(),
+            manifest: PackManifest {
+                name: "Classic".to_string(),
+                human_color: Some([0.2, 0.7, 0.2]),
+                ai_color: Some([0.7, 0.2, 0.2]),
+                third_color: Some([0.2, 0.4, 0.8]),
+                neutral_color: Some([0.85, 0.85, 0.85]),
+                empty_color: Some([0.3, 0.3, 0.3, 0.5]),
+                ..default()
+            },
+        }
+    }
+
+    // Human, AI and third player piece colors, falling back to the classic pack's
+    pub fn piece_colors(&self) -> [[f32; 3]; 3] {
+        let defaults = Self::classic().manifest;
+        let manifest = &self.manifest;
+        [
+            manifest.human_color.or(defaults.human_color),
+            manifest.ai_color.or(defaults.ai_color),
+            manifest.third_color.or(defaults.third_color),
+        ]
+        .map(Option::unwrap_or_default)
+    }
+
+    pub fn empty_color(&self) -> [f32; 4] {
+        self.manifest.empty_color.or(Self::classic().manifest.empty_color).unwrap_or_default()
+    }
+
+    // Asset path inside the `mods://` asset source
+    pub fn asset_path(&self, relative: &str) -> String {
+        format!("{}://{}/{}", MODS_DIR, self.id, relative)
+    }
+}
+
+#[derive(Resource)]
+pub struct PiecePacks {
+    pub packs: Vec<PiecePack>,
+}
+
+impl PiecePacks {
+    // Enumerate `mods/`, keeping the built-in classic pack first and skipping anything malformed
+    pub fn discover() -> Self {
+        let mut packs = vec![PiecePack::classic()];
+        let mods_dir = FileAssetReader::get_base_path().join(MODS_DIR);
+
+        let Ok(entries) = fs::read_dir(&mods_dir) else {
+            return Self { packs };
+        };
+
+        let mut dirs: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        dirs.sort();
+
+        for dir in dirs {
+            match load_pack(&dir) {
+                Ok(pack) => {
+                    info!("Loaded piece pack '{}' from {:?}", pack.manifest.name, dir);
+                    packs.push(pack);
+                }
+                Err(err) => warn!("Skipping piece pack {:?}: {}", dir, err),
+            }
+        }
+
+        Self { packs }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&PiecePack> {
+        self.packs.iter().find(|pack| pack.id == id)
+    }
+}
+
+fn load_pack(dir: &Path) -> Result<PiecePack, String> {
+    let id = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("folder name is not valid UTF-8")?
+        .to_string();
+    if id == CLASSIC_PACK_ID {
+        return Err(format!("'{}' is reserved for the built-in pack", CLASSIC_PACK_ID));
+    }
+
+    let contents = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|err| format!("cannot read {}: {}", MANIFEST_FILE, err))?;
+    let manifest: PackManifest = ron::from_str(&contents)
+        .map_err(|err| format!("invalid {}: {}", MANIFEST_FILE, err))?;
+
+    validate_manifest(dir, &manifest)?;
+    Ok(PiecePack { id, manifest })
+}
+
+fn validate_manifest(dir: &Path, manifest: &PackManifest) -> Result<(), String> {
+    if manifest.name.trim().is_empty() {
+        return Err("manifest has no name".to_string());
+    }
+
+    let colors = manifest.human_color.iter().flatten()
+        .chain(manifest.ai_color.iter().flatten())
+        .chain(manifest.third_color.iter().flatten())
+        .chain(manifest.neutral_color.iter().flatten())
+        .chain(manifest.empty_color.iter().flatten())
+        .chain(manifest.metallic.iter())
+        .chain(manifest.roughness.iter());
+    for &component in colors {
+        if !(0.0..=1.0).contains(&component) {
+            return Err(format!("material value {} is outside 0.0..=1.0", component));
+        }
+    }
+
+    if let Some(mesh) = &manifest.mesh {
+        validate_file(dir, mesh, &MESH_EXTENSIONS)?;
+    }
+    if let Some(sound) = &manifest.place_sound {
+        validate_file(dir, sound, &SOUND_EXTENSIONS)?;
+    }
+
+    Ok(())
+}
+
+fn validate_file(dir: &Path, relative: &str, extensions: &[&str]) -> Result<(), String> {
+    // Strip glTF sub-asset labels such as "#Mesh0/Primitive0"
+    let file = relative.split('#').next().unwrap_or(relative);
+    // Plain names only: `..`, a root or a drive would let `join` reach outside the folder
+    if !Path::new(file).components().all(|component| matches!(component, Component::Normal(_))) {
+        return Err(format!("'{}' must stay inside the pack folder", relative));
+    }
+
+    let path = dir.join(file);
+    if !path.is_file() {
+        return Err(format!("missing file '{}'", file));
+    }
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+    if !extensions.contains(&extension.as_str()) {
+        return Err(format!("'{}' must be one of: {}", file, extensions.join(", ")));
+    }
+
+    Ok(())
+}
+
+// Handles of the pack currently applied, kept so failed loads can be detected and reverted
+#[derive(Resource, Default)]
+pub struct AppliedPiecePack {
+    pub id: Option<String>,
+    pub mesh: Option<Handle<Mesh>>,
+    pub high_contrast: bool,
+    pub color_theme: ColorTheme,
+    pub colorblind: bool,
+    pub piece_colors: [Option<[f32; 3]>; 3],
+}
+
+fn to_color(rgb: [f32; 3]) -> Color {
+    Color::srgb(rgb[0], rgb[1], rgb[2])
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn apply_piece_pack(
+    settings: Res<Settings>,
+    packs: Res<PiecePacks>,
+    asset_server: Res<AssetServer>,
+    cube_materials: Res<CubeMaterials>,
+    game_meshes: Res<GameMeshes>,
+    mut clear_color: ResMut<ClearColor>,
+    mut applied: ResMut<AppliedPiecePack>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hover_rim: Res<HoverRimHandle>,
+    mut rim_materials: ResMut<Assets<HoverRimMaterial>>,
+    mut cube_query: Query<&mut Handle<Mesh>, With<CubeMarker>>,
+    mut label_query: Query<&mut Text, With<PiecePackLabel>>,
+) {
+    if applied.id.as_deref() == Some(settings.piece_pack.as_str())
+        && applied.high_contrast == settings.high_contrast
+        && applied.color_theme == settings.color_theme
+        && applied.colorblind == settings.colorblind
+        && applied.piece_colors == settings.piece_colors
+    {
+        return;
+    }
+
+    let classic = PiecePack::classic();
+    let pack = packs.get(&settings.piece_pack).unwrap_or_else(|| {
+        warn!("Piece pack '{}' not found, using the classic pack", settings.piece_pack);
+        &classic
+    });
+    let defaults = &classic.manifest;
+    let manifest = &pack.manifest;
+
+    // Recolor the shared materials in place so every cube picks up the pack immediately, with
+    // the theme's colors in place of the pack's and each player's own piece color in place of
+    // those where they picked one. High contrast overrides the theme and the players' choices,
+    // and the colorblind aid overrides every piece color
+    let theme_colors = ColorTheme::in_use(&settings).palette();
+    let empty = theme_colors.empty_color(pack);
+    let [human, ai, third] = match (settings.colorblind, settings.high_contrast) {
+        (true, _) => COLORBLIND_PIECE_COLORS,
+        (false, true) => theme_colors.piece_colors(pack),
+        (false, false) => resolve_piece_colors(theme_colors.piece_colors(pack), settings.piece_colors, &theme_colors.board(pack)),
+    };
+    let neutral = theme_colors.neutral.or(manifest.neutral_color).or(defaults.neutral_color).unwrap_or_default();
+    let hovered = theme_colors.hovered;
+    let palette = [
+        (&cube_materials.human, to_color(human), theme_colors.glow),
+        (&cube_materials.ai, to_color(ai), theme_colors.glow),
+        (&cube_materials.third, to_color(third), theme_colors.glow),
+        (&cube_materials.neutral, to_color(neutral), theme_colors.glow),
+        (&cube_materials.empty, Color::srgba(empty[0], empty[1], empty[2], empty[3]), 0.0),
+        (&cube_materials.xray, Color::srgba(empty[0], empty[1], empty[2], empty[3] * XRAY_ALPHA), 0.0),
+        (&cube_materials.human_threat, to_color(human).with_alpha(THREAT_TINT_ALPHA), 0.0),
+        (&cube_materials.ai_threat, to_color(ai).with_alpha(THREAT_TINT_ALPHA), 0.0),
+        (&cube_materials.third_threat, to_color(third).with_alpha(THREAT_TINT_ALPHA), 0.0),
+    ];
+
+    for (handle, color, glow) in palette {
+        if let Some(material) = materials.get_mut(handle) {
+            material.base_color = color;
+            material.emissive = LinearRgba::from(color.with_alpha(1.0)) * glow;
+            material.alpha_mode = if color.alpha() < 1.0 { AlphaMode::Blend } else { AlphaMode::Opaque };
+            material.metallic = manifest.metallic.unwrap_or(0.0);
+            material.perceptual_roughness = manifest.roughness.unwrap_or(0.5);
+        }
+    }
+    clear_color.0 = theme_colors.background;
+    if let Some(rim) = rim_materials.get_mut(&hover_rim.0) {
+        rim.color = LinearRgba::from(Color::srgba(hovered[0], hovered[1], hovered[2], hovered[3]));
+    }
+
+    let mesh = match &manifest.mesh {
+        Some(path) => asset_server.load(pack.asset_path(path)),
+        None => game_meshes.cube.clone(),
+    };
+    for mut cube_mesh in cube_query.iter_mut() {
+        *cube_mesh = mesh.clone();
+    }
+
+    let label = match &manifest.author {
+        Some(author) => format!("Piece pack: {} by {} [P]", manifest.name, author),
+        None => format!("Piece pack: {} [P]", manifest.name),
+    };
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = label.clone();
+    }
+
+    applied.id = Some(settings.piece_pack.clone());
+    applied.high_contrast = settings.high_contrast;
+    applied.color_theme = settings.color_theme;
+    applied.colorblind = settings.colorblind;
+    applied.piece_colors = settings.piece_colors;
+    applied.mesh = manifest.mesh.as_ref().map(|_| mesh);
+}
+
+// If a pack's mesh fails to load, quietly fall back to the built-in cube
+pub fn fallback_on_failed_pack_assets(
+    asset_server: Res<AssetServer>,
+    game_meshes: Res<GameMeshes>,
+    mut applied: ResMut<AppliedPiecePack>,
+    mut cube_query: Query<&mut Handle<Mesh>, With<CubeMarker>>,
+) {
+    if let Some(mesh) = &applied.mesh {
+        if matches!(asset_server.load_state(mesh), LoadState::Failed(_)) {
+            warn!("Piece pack mesh failed to load, falling back to the classic cube");
+            for mut cube_mesh in cube_query.iter_mut() {
+                *cube_mesh = game_meshes.cube.clone();
+            }
+            applied.mesh = None;
+        }
+    }
+}
+
+pub fn cycle_piece_pack(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    packs: Res<PiecePacks>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+
+    let current = packs.packs.iter().position(|pack| pack.id == settings.piece_pack).unwrap_or(0);
+    settings.piece_pack = packs.packs[(current + 1) % packs.packs.len()].id.clone();
+    settings.save(&profile);
+}
@@ -0,0 +1,63 @@
+use std::sync::{Arc, OnceLock};
+use ttt3d_core::learned::LinearEvaluator;
+use ttt3d_core::network::Evaluator;
+
+// Where a value/policy network for the AI is looked for; see `ttt3d_core::network::OnnxEvaluator`
+// for the shape it must have
+pub const MODEL_PATH: &str = "assets/models/evaluator.onnx";
+
+// Where `--train-eval` writes the learned evaluation's weights, and where they are read from
+pub const LEARNED_EVAL_PATH: &str = "assets/models/learned_eval.ron";
+
+// The network steering MCTS, loaded the first time an engine is built. With no model file, or
+// in a build without the `neural` feature, the AI plays on rollouts and heuristics alone
+pub fn network() -> Option<Arc<dyn Evaluator>> {
+    static NETWORK: OnceLock<Option<Arc<dyn Evaluator>>> = OnceLock::new();
+    NETWORK.get_or_init(load).clone()
+}
+
+#[cfg(feature = "neural")]
+fn load() -> Option<Arc<dyn Evaluator>> {
+    let path = std::path::Path::new(MODEL_PATH);
+    // No model is the usual case, not a problem worth reporting
+    if !path.exists() {
+        return None;
+    }
+    match ttt3d_core::network::OnnxEvaluator::load(path) {
+        Ok(evaluator) => Some(Arc::new(evaluator)),
+        Err(err) => {
+            eprintln!("warning: ignoring the AI network at {}: {}", MODEL_PATH, err);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "neural"))]
+fn load() -> Option<Arc<dyn Evaluator>> {
+    if std::path::Path::new(MODEL_PATH).exists() {
+        eprintln!("warning: ignoring the AI network at {}: this build has no `neural` feature", MODEL_PATH);
+    }
+    None
+}
+
+// The learned evaluation, loaded the first time it is asked for. It needs no feature, only
+// weights from `--train-eval`; without them the setting falls back to `network()`
+pub fn learned_evaluator() -> Option<Arc<dyn Evaluator>> {
+    static LEARNED: OnceLock<Option<Arc<dyn Evaluator>>> = OnceLock::new();
+    LEARNED.get_or_init(load_learned).clone()
+}
+
+pub fn learned_eval_available() -> bool {
+    std::path::Path::new(LEARNED_EVAL_PATH).exists()
+}
+
+fn load_learned() -> Option<Arc<dyn Evaluator>> {
+    let contents = std::fs::read_to_string(LEARNED_EVAL_PATH).ok()?;
+    match ron::from_str::<LinearEvaluator>(&contents) {
+        Ok(evaluator) => Some(Arc::new(evaluator)),
+        Err(err) => {
+            eprintln!("warning: ignoring the learned evaluation at {}: {}", LEARNED_EVAL_PATH, err);
+            None
+        }
+    }
+}
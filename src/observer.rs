@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use bevy::render::camera::Viewport;
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+use crate::graphics::CameraController;
+use crate::settings::{Profile, Settings};
+
+// Render layer for things only the main view should show, such as the win banner, which
+// faces the main camera and would read backwards from the other side
+pub const MAIN_VIEW_LAYER: usize = 1;
+
+// The inset's side as a fraction of the window's shorter side, and its gap from the edge
+const INSET_FRACTION: f32 = 0.28;
+const INSET_MARGIN: f32 = 10.0;
+
+// Picture-in-picture camera looking at the board from the diametrically opposite side,
+// so lines hidden behind the front pieces can be seen without rotating
+#[derive(Component)]
+pub struct ObserverCamera;
+
+pub fn setup_observer_camera(mut commands: Commands, settings: Res<Settings>) {
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                // Drawn after the main view, over its corner
+                order: 1,
+                is_active: settings.observer_view,
+                clear_color: ClearColorConfig::Custom(Color::srgb(0.08, 0.08, 0.12)),
+                ..default()
+            },
+            ..default()
+        },
+        // Only the board layer, not the main view's extras
+        RenderLayers::layer(0),
+        ObserverCamera,
+    ));
+}
+
+// F4 shows or hides the opposite view; the choice is saved with the profile
+pub fn toggle_observer_view(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+) {
+    if keyboard.just_pressed(KeyCode::F4) {
+        settings.observer_view = !settings.observer_view;
+        settings.save(&profile);
+    }
+}
+
+// Mirror the main camera through the board's center, and keep the inset in the window's
+// right-hand side at a size that follows the window
+#[allow(clippy::type_complexity)]
+pub fn sync_observer_camera(
+    settings: Res<Settings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    main_query: Query<(&Transform, &Projection), (With<CameraController>, Without<ObserverCamera>)>,
+    mut observer_query: Query<(&mut Camera, &mut Transform, &mut Projection), With<ObserverCamera>>,
+) {
+    let Ok((mut camera, mut transform, mut projection)) = observer_query.get_single_mut() else {
+        return;
+    };
+
+    let (Ok(window), Ok((main_transform, main_projection))) = (windows.get_single(), main_query.get_single()) else {
+        return;
+    };
+
+    let (width, height) = (window.physical_width(), window.physical_height());
+    let size = (width.min(height) as f32 * INSET_FRACTION) as u32;
+    let margin = (INSET_MARGIN * window.scale_factor()) as u32;
+    // A minimized or tiny window has no room for the inset
+    let active = settings.observer_view && size > 0 && width >= size + margin;
+    if camera.is_active != active {
+        camera.is_active = active;
+    }
+    if !active {
+        return;
+    }
+
+    *transform = Transform::from_translation(-main_transform.translation).looking_at(Vec3::ZERO, Vec3::Y);
+    *projection = main_projection.clone();
+
+    // Vertically centered on the right, clear of the panels in the corners
+    let position = UVec2::new(width - size - margin, (height - size) / 2);
+    let current = camera.viewport.as_ref().map(|viewport| (viewport.physical_position, viewport.physical_size));
+    if current != Some((position, UVec2::splat(size))) {
+        camera.viewport = Some(Viewport {
+            physical_position: position,
+            physical_size: UVec2::splat(size),
+            ..default()
+        });
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use crate::graphics::{orbit_camera, CameraController, CameraMode};
+use crate::settings::Settings;
+
+// How quickly the lean follows the cursor: the gap shrinks by a factor of e every 1/this seconds
+const TILT_RATE: f32 = 6.0;
+
+// The board leans a degree or two towards the cursor, so the cells nearest it turn their sides a
+// little to the view and the depth under the cursor reads more easily. It's done by swinging the
+// camera the other way round the centre, which looks the same on screen and keeps hovering,
+// overlays and outlines lined up with the cubes. The controller's own angles are left alone
+#[derive(Resource, Default)]
+pub struct BoardTilt {
+    // Yaw and pitch, in radians, the camera is swung by
+    offset: Vec2,
+}
+
+// Runs after the camera has been turned, and places it every frame from the controller's angles
+// so the lean never builds up
+pub fn tilt_board(
+    windows: Query<&Window>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut tilt: ResMut<BoardTilt>,
+    mut camera_query: Query<(&mut Transform, &CameraController)>,
+) {
+    let Ok((mut transform, controller)) = camera_query.get_single_mut() else {
+        return;
+    };
+    // Flying freely, the camera isn't on its orbit to be swung round
+    if controller.mode != CameraMode::Orbit {
+        if tilt.offset != Vec2::ZERO {
+            tilt.offset = Vec2::ZERO;
+        }
+        return;
+    }
+    let degrees = if settings.reduced_motion { 0.0 } else { settings.board_tilt };
+    if degrees <= 0.0 && tilt.offset == Vec2::ZERO {
+        return;
+    }
+
+    // From -1 to 1 across the window, up being positive; centred when the cursor is outside it
+    let cursor = windows.get_single().ok().and_then(|window| {
+        let position = window.cursor_position()?;
+        Some(Vec2::new(position.x / window.width() * 2.0 - 1.0, 1.0 - position.y / window.height() * 2.0))
+    });
+    // Swinging the camera towards the cursor's side of the window turns that side of the board to it
+    let target = cursor.unwrap_or(Vec2::ZERO) * Vec2::new(-1.0, 1.0) * degrees.to_radians();
+    tilt.offset = if degrees <= 0.0 {
+        Vec2::ZERO
+    } else {
+        tilt.offset.lerp(target, 1.0 - (-TILT_RATE * time.delta_seconds()).exp())
+    };
+
+    orbit_camera(&mut transform, controller.distance, Vec2::new(controller.yaw, controller.pitch) + tilt.offset);
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use ttt3d_core::game::{GamePhase, GameState};
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+use crate::settings::Settings;
+
+// The engine thinking ahead on the human's time, and the turn it is thinking about
+#[derive(Resource, Default)]
+pub struct Pondering {
+    // Game generation, turns taken and engine, so a new turn, game or engine starts afresh
+    turn: Option<(u64, usize, usize)>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Pondering {
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.turn = None;
+    }
+}
+
+// Don't leave a search running on the pool as the app shuts down
+impl Drop for Pondering {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// While the human is to move, let the AI search their position on the compute pool, so it
+// can reply straight away once they do. Stopped as soon as it is no longer their turn
+pub fn ponder_on_human_turn(
+    settings: Res<Settings>,
+    game_state: Res<GameState>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut pondering: ResMut<Pondering>,
+) {
+    let wanted = settings.pondering
+        && !game_state.game_over
+        && game_state.phase != GamePhase::Editing
+        && game_state.is_human_turn()
+        && !puzzle.is_solving()
+        && !quiz.is_asking();
+    let engine = Arc::as_ptr(&game_state.ai) as *const () as usize;
+    let turn = wanted.then_some((game_state.generation, game_state.turns_taken(), engine));
+    if pondering.turn == turn {
+        return;
+    }
+
+    pondering.stop();
+    let Some(turn) = turn else {
+        return;
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    pondering.stop = stop.clone();
+    pondering.turn = Some(turn);
+
+    let snapshot = game_state.clone();
+    AsyncComputeTaskPool::get()
+        .spawn(async move { snapshot.ai.ponder(&snapshot, &stop) })
+        .detach();
+}
@@ -0,0 +1,219 @@
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+    RegisterDiagnostic,
+};
+use bevy::prelude::*;
+use std::fs;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use crate::settings::{Profile, Settings};
+
+pub const UPDATE_TIME: DiagnosticPath = DiagnosticPath::const_new("game/update_ms");
+pub const AI_SEARCH_TIME: DiagnosticPath = DiagnosticPath::const_new("game/ai_search_ms");
+pub const PICKING_TIME: DiagnosticPath = DiagnosticPath::const_new("game/picking_ms");
+pub const AI_SYSTEMS_TIME: DiagnosticPath = DiagnosticPath::const_new("game/ai_systems_ms");
+pub const MATERIALS_TIME: DiagnosticPath = DiagnosticPath::const_new("game/materials_ms");
+pub const OVERLAYS_TIME: DiagnosticPath = DiagnosticPath::const_new("game/overlays_ms");
+
+// The heavier groups of Update systems, each timed as a whole: from just before its first system
+// starts to just after its last one finishes, so systems running alongside it on other threads
+// are counted in too
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProfiledSet {
+    // Hovering, picking and clicking cells
+    Picking,
+    // Starting, polling and showing the AI's search, but not the search itself
+    Ai,
+    // Cube, hover rim and light materials
+    Materials,
+    // Gizmo outlines, labels and maps drawn over the board
+    Overlays,
+}
+
+impl ProfiledSet {
+    const ALL: [ProfiledSet; 4] = [ProfiledSet::Picking, ProfiledSet::Ai, ProfiledSet::Materials, ProfiledSet::Overlays];
+
+    fn path(self) -> DiagnosticPath {
+        match self {
+            ProfiledSet::Picking => PICKING_TIME,
+            ProfiledSet::Ai => AI_SYSTEMS_TIME,
+            ProfiledSet::Materials => MATERIALS_TIME,
+            ProfiledSet::Overlays => OVERLAYS_TIME,
+        }
+    }
+}
+
+pub struct ProfilerPlugin;
+
+impl Plugin for ProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((FrameTimeDiagnosticsPlugin, EntityCountDiagnosticsPlugin))
+            .register_diagnostic(Diagnostic::new(UPDATE_TIME).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(AI_SEARCH_TIME).with_suffix("ms"))
+            .init_resource::<FrameProfiler>()
+            .init_resource::<SetTimers>()
+            .add_systems(Startup, setup_profiler_overlay)
+            .add_systems(PreUpdate, mark_update_start)
+            .add_systems(PostUpdate, record_update_time)
+            .add_systems(Update, (toggle_profiler, update_profiler_overlay).chain());
+        for set in ProfiledSet::ALL {
+            app.register_diagnostic(Diagnostic::new(set.path()).with_suffix("ms"))
+                .add_systems(Update, (start_set_timer(set).before(set), record_set_time(set).after(set)));
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct FrameProfiler {
+    pub visible: bool,
+    pub update_started: Option<Instant>,
+    // Most recent summary, kept so bug reports can include it even when the overlay is hidden
+    pub last_summary: String,
+}
+
+// When each profiled set's timing started this frame, indexed by the set
+#[derive(Resource, Default)]
+struct SetTimers {
+    started: [Option<Instant>; ProfiledSet::ALL.len()],
+}
+
+#[derive(Component)]
+pub struct ProfilerText;
+
+fn setup_profiler_overlay(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::srgb(0.9, 0.9, 0.5),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(150.0),
+            left: Val::Px(10.0),
+            ..default()
+        })
+        .with_background_color(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ProfilerText,
+    ));
+}
+
+fn mark_update_start(mut profiler: ResMut<FrameProfiler>) {
+    profiler.update_started = Some(Instant::now());
+}
+
+fn record_update_time(profiler: Res<FrameProfiler>, mut diagnostics: bevy::diagnostic::Diagnostics) {
+    if let Some(started) = profiler.update_started {
+        diagnostics.add_measurement(&UPDATE_TIME, || started.elapsed().as_secs_f64() * 1000.0);
+    }
+}
+
+fn start_set_timer(set: ProfiledSet) -> impl FnMut(ResMut<SetTimers>) {
+    move |mut timers: ResMut<SetTimers>| timers.started[set as usize] = Some(Instant::now())
+}
+
+fn record_set_time(set: ProfiledSet) -> impl FnMut(Res<SetTimers>, bevy::diagnostic::Diagnostics) {
+    move |timers: Res<SetTimers>, mut diagnostics: bevy::diagnostic::Diagnostics| {
+        if let Some(started) = timers.started[set as usize] {
+            diagnostics.add_measurement(&set.path(), || started.elapsed().as_secs_f64() * 1000.0);
+        }
+    }
+}
+
+fn format_diagnostic(store: &DiagnosticsStore, path: &DiagnosticPath, label: &str) -> String {
+    match store.get(path) {
+        Some(diagnostic) => match (diagnostic.average(), diagnostic.value()) {
+            (Some(average), Some(last)) => format!(
+                "{:<12} avg {:>8.2}{} last {:>8.2}{}",
+                label, average, diagnostic.suffix, last, diagnostic.suffix
+            ),
+            _ => format!("{:<12} no samples yet", label),
+        },
+        None => format!("{:<12} unavailable", label),
+    }
+}
+
+pub fn profiler_summary(store: &DiagnosticsStore) -> String {
+    [
+        format_diagnostic(store, &FrameTimeDiagnosticsPlugin::FPS, "FPS"),
+        format_diagnostic(store, &FrameTimeDiagnosticsPlugin::FRAME_TIME, "Frame time"),
+        format_diagnostic(store, &UPDATE_TIME, "Update"),
+        format_diagnostic(store, &PICKING_TIME, "  Picking"),
+        format_diagnostic(store, &AI_SYSTEMS_TIME, "  AI"),
+        format_diagnostic(store, &MATERIALS_TIME, "  Materials"),
+        format_diagnostic(store, &OVERLAYS_TIME, "  Overlays"),
+        format_diagnostic(store, &AI_SEARCH_TIME, "AI search"),
+        format_diagnostic(store, &EntityCountDiagnosticsPlugin::ENTITY_COUNT, "Entities"),
+    ]
+    .join("\n")
+}
+
+// F10 toggles the overlay; Shift+F10 writes a bug report bundle with the latest summary
+fn toggle_profiler(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    store: Res<DiagnosticsStore>,
+    settings: Res<Settings>,
+    profile: Res<Profile>,
+    mut profiler: ResMut<FrameProfiler>,
+    mut text_query: Query<&mut Text, With<ProfilerText>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F10) {
+        return;
+    }
+
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if shift {
+        profiler.last_summary = profiler_summary(&store);
+        write_diagnostic_bundle(&profile, &settings, &profiler.last_summary);
+        return;
+    }
+
+    profiler.visible = !profiler.visible;
+    if !profiler.visible {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value.clear();
+        }
+    }
+}
+
+fn update_profiler_overlay(
+    store: Res<DiagnosticsStore>,
+    mut profiler: ResMut<FrameProfiler>,
+    mut text_query: Query<&mut Text, With<ProfilerText>>,
+) {
+    if !profiler.visible {
+        return;
+    }
+
+    profiler.last_summary = profiler_summary(&store);
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!("Frame profiler (F10)\n{}", profiler.last_summary);
+    }
+}
+
+fn write_diagnostic_bundle(profile: &Profile, settings: &Settings, summary: &str) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let dir = profile.dir().join("diagnostics");
+    let path = dir.join(format!("bug-report-{}.txt", timestamp));
+
+    let settings_text = ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::default())
+        .unwrap_or_else(|err| format!("<failed to serialize settings: {}>", err));
+    let contents = format!(
+        "3D Tic-Tac-Toe {} bug report\nOS: {} ({})\n\n[Frame profiler]\n{}\n\n[Settings]\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        summary,
+        settings_text,
+    );
+
+    match fs::create_dir_all(&dir).and_then(|_| fs::write(&path, contents)) {
+        Ok(()) => info!("Wrote diagnostic bundle to {:?}", path),
+        Err(err) => warn!("Failed to write diagnostic bundle: {}", err),
+    }
+}
@@ -0,0 +1,350 @@
+// Puzzles are verified and refuted by the solver, so builds without it keep only the idle
+// `PuzzleMode` the rest of the game checks
+#![cfg_attr(not(feature = "solver"), allow(dead_code, unused_imports))]
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use chrono::Datelike;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ttt3d_core::game::{CellState, GameState, Player, Position, Rules};
+use ttt3d_core::notation::{has_line, parse_board, Board};
+#[cfg(feature = "solver")]
+use ttt3d_core::solver::{forced_win, refutation, winning_moves};
+use crate::graphics::cell_position;
+
+const PUZZLE_DIR: &str = "puzzles";
+
+// The daily puzzle, started with Q
+#[cfg(feature = "solver")]
+pub struct PuzzlePlugin;
+
+#[cfg(feature = "solver")]
+impl Plugin for PuzzlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_puzzle_ui)
+            .add_systems(Update, (toggle_puzzle_mode, drive_puzzle, update_puzzle_text, mark_puzzle_refutation).chain());
+    }
+}
+
+// Contents of `assets/puzzles/*.ron`: the human is to move and wins by force in `moves` moves
+#[derive(Deserialize, Clone, Debug)]
+pub struct PuzzleFile {
+    pub name: String,
+    pub moves: u32,
+    // Three layers (z), each three rows (y) of three cells (x): 'H' human, 'A' AI, '.' empty
+    pub layers: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    pub name: String,
+    pub moves: u32,
+    pub board: Board,
+}
+
+// Where the game's own puzzles are kept
+pub fn puzzle_dir() -> PathBuf {
+    FileAssetReader::get_base_path().join("assets").join(PUZZLE_DIR)
+}
+
+// The puzzle files in `dir`, in name order
+fn puzzle_paths(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(feature = "solver")]
+// Read every puzzle in the assets folder, skipping any that fail to parse or verify
+pub fn load_puzzles() -> Vec<Puzzle> {
+    let Ok(paths) = puzzle_paths(&puzzle_dir()) else {
+        return Vec::new();
+    };
+
+    paths
+        .iter()
+        .filter_map(|path| match load_puzzle(path) {
+            Ok(puzzle) => Some(puzzle),
+            Err(err) => {
+                warn!("Skipping puzzle {:?}: {}", path, err);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "solver")]
+fn load_puzzle(path: &Path) -> Result<Puzzle, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("cannot read file: {}", err))?;
+    let file: PuzzleFile = ron::from_str(&contents).map_err(|err| format!("invalid puzzle: {}", err))?;
+    let board = parse_board(&file.layers)?;
+
+    let count = |cell: CellState| board.iter().flatten().flatten().filter(|&&c| c == cell).count();
+    if count(CellState::Human) != count(CellState::AI) {
+        return Err("the human must be to move (equal piece counts)".to_string());
+    }
+    if has_line(&board, CellState::Human) || has_line(&board, CellState::AI) {
+        return Err("the position is already won".to_string());
+    }
+
+    // Verify the puzzle is sound: a forced win in exactly the stated number of moves
+    if file.moves == 0 || !forced_win(&mut board.clone(), file.moves) {
+        return Err(format!("no forced win in {}", file.moves));
+    }
+    if file.moves > 1 && forced_win(&mut board.clone(), file.moves - 1) {
+        return Err(format!("it can be won in fewer than {} moves", file.moves));
+    }
+
+    Ok(Puzzle {
+        name: file.name,
+        moves: file.moves,
+        board,
+    })
+}
+
+#[cfg(feature = "solver")]
+// --check-puzzles: verify every puzzle in `dir` as the game would, and also that its solution is
+// unique, with one first move winning and no other, and that no position is in the folder twice.
+// Prints a line per puzzle and returns whether they all passed
+pub fn check_puzzles(dir: &Path) -> bool {
+    let paths = match puzzle_paths(dir) {
+        Ok(paths) if !paths.is_empty() => paths,
+        Ok(_) => {
+            eprintln!("No puzzles found in {:?}", dir);
+            return false;
+        }
+        Err(err) => {
+            eprintln!("Cannot read {:?}: {}", dir, err);
+            return false;
+        }
+    };
+
+    let mut seen: Vec<(Board, PathBuf)> = Vec::new();
+    let mut failures = 0;
+    for path in &paths {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let checked = load_puzzle(path).and_then(|puzzle| {
+            if let Some((_, earlier)) = seen.iter().find(|(board, _)| *board == puzzle.board) {
+                return Err(format!("the same position as {:?}", earlier.file_name().unwrap_or_default()));
+            }
+            seen.push((puzzle.board, path.clone()));
+            match winning_moves(&mut puzzle.board.clone(), puzzle.moves).as_slice() {
+                [key] => Ok((puzzle, *key)),
+                keys => Err(format!("{} first moves win in {}: {:?}", keys.len(), puzzle.moves, keys)),
+            }
+        });
+        match checked {
+            Ok((puzzle, key)) => println!("ok      {}  '{}', win in {}, key move {:?}", file_name, puzzle.name, puzzle.moves, key),
+            Err(err) => {
+                failures += 1;
+                eprintln!("FAILED  {}  {}", file_name, err);
+            }
+        }
+    }
+
+    println!("{} of {} puzzles passed", paths.len() - failures, paths.len());
+    failures == 0
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PuzzleStatus {
+    Solving,
+    Solved,
+    Failed,
+}
+
+pub struct ActivePuzzle {
+    pub puzzle: Puzzle,
+    pub status: PuzzleStatus,
+    pub moves_left: u32,
+    // The AI reply that broke the player's attempt, shown after a failure
+    pub refutation: Option<Position>,
+    generation: u64,
+    handled_moves: usize,
+}
+
+impl ActivePuzzle {
+    fn start(&mut self, game_state: &mut GameState) {
+        game_state.rules = Rules::default();
+        game_state.reset();
+        game_state.load_position(self.puzzle.board, Player::Human);
+
+        self.status = PuzzleStatus::Solving;
+        self.moves_left = self.puzzle.moves;
+        self.refutation = None;
+        self.generation = game_state.generation;
+        self.handled_moves = 0;
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PuzzleMode {
+    pub active: Option<ActivePuzzle>,
+}
+
+impl PuzzleMode {
+    // While a puzzle is being solved, the puzzle drives the AI's replies
+    pub fn is_solving(&self) -> bool {
+        self.active.as_ref().is_some_and(|active| active.status == PuzzleStatus::Solving)
+    }
+}
+
+#[derive(Component)]
+pub struct PuzzleText;
+
+pub fn setup_puzzle_ui(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 22.0,
+                color: Color::srgb(0.9, 0.8, 0.4),
+                ..default()
+            },
+        )
+        .with_text_justify(JustifyText::Center)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            ..default()
+        }),
+        PuzzleText,
+    ));
+}
+
+#[cfg(feature = "solver")]
+// Q starts today's puzzle, or leaves puzzle mode for a normal game
+pub fn toggle_puzzle_mode(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut mode: ResMut<PuzzleMode>,
+    mut game_state: ResMut<GameState>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyQ) {
+        return;
+    }
+
+    if mode.active.take().is_some() {
+        game_state.reset();
+        return;
+    }
+
+    let puzzles = load_puzzles();
+    if puzzles.is_empty() {
+        warn!("No puzzles found in assets/{}", PUZZLE_DIR);
+        return;
+    }
+
+    // The same puzzle for everyone on a given day
+    let day = chrono::Local::now().date_naive().num_days_from_ce();
+    let puzzle = puzzles[day.rem_euclid(puzzles.len() as i32) as usize].clone();
+    info!("Daily puzzle: {}", puzzle.name);
+
+    let mut active = ActivePuzzle {
+        puzzle,
+        status: PuzzleStatus::Solving,
+        moves_left: 0,
+        refutation: None,
+        generation: 0,
+        handled_moves: 0,
+    };
+    active.start(&mut game_state);
+    mode.active = Some(active);
+}
+
+#[cfg(feature = "solver")]
+// Check each of the player's moves against the solution and answer it: a refutation
+// if the forced win is gone, otherwise the engine's best defence
+pub fn drive_puzzle(mut mode: ResMut<PuzzleMode>, mut game_state: ResMut<GameState>) {
+    let Some(active) = mode.active.as_mut() else {
+        return;
+    };
+
+    // R restarts the puzzle from its starting position
+    if game_state.generation != active.generation {
+        active.start(&mut game_state);
+        return;
+    }
+
+    if active.status != PuzzleStatus::Solving || game_state.move_history.len() == active.handled_moves {
+        return;
+    }
+    active.handled_moves = game_state.move_history.len();
+
+    if game_state.game_over {
+        active.status = if game_state.winner == Some(Player::Human) {
+            PuzzleStatus::Solved
+        } else {
+            PuzzleStatus::Failed
+        };
+        return;
+    }
+
+    if game_state.current_player != Player::AI {
+        return;
+    }
+
+    active.moves_left = active.moves_left.saturating_sub(1);
+    let mut board = game_state.board;
+    let refuting_reply = if active.moves_left > 0 { refutation(&mut board, active.moves_left) } else { None };
+
+    let reply = if active.moves_left == 0 || refuting_reply.is_some() {
+        active.status = PuzzleStatus::Failed;
+        active.refutation = refuting_reply.or_else(|| game_state.ai.best_move(&game_state, None));
+        active.refutation
+    } else {
+        game_state.ai.best_move(&game_state, None)
+    };
+
+    if let Some((x, y, z)) = reply {
+        game_state.make_move(x, y, z);
+    }
+    active.handled_moves = game_state.move_history.len();
+}
+
+pub fn update_puzzle_text(
+    mode: Res<PuzzleMode>,
+    game_state: Res<GameState>,
+    mut text_query: Query<&mut Text, With<PuzzleText>>,
+) {
+    if !mode.is_changed() && !game_state.is_changed() {
+        return;
+    }
+
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    text.sections[0].value = match &mode.active {
+        None => String::new(),
+        Some(active) => match active.status {
+            PuzzleStatus::Solving => format!(
+                "Daily puzzle: {} - win in {} ({} left). Q to exit",
+                active.puzzle.name, active.puzzle.moves, active.moves_left
+            ),
+            PuzzleStatus::Solved => "Puzzle solved! R to replay, Q to exit".to_string(),
+            PuzzleStatus::Failed => match active.refutation {
+                Some((x, y, z)) => format!("Refuted: the AI answers at ({}, {}, {}). R to retry", x, y, z),
+                None => "No forced win left. R to retry".to_string(),
+            },
+        },
+    };
+}
+
+// Outline the refuting reply so the player can see where their attempt went wrong
+pub fn mark_puzzle_refutation(mode: Res<PuzzleMode>, mut gizmos: Gizmos) {
+    let Some((x, y, z)) = mode.active.as_ref().and_then(|active| active.refutation) else {
+        return;
+    };
+
+    gizmos.cuboid(
+        Transform::from_translation(cell_position(x, y, z)),
+        Color::srgb(1.0, 0.3, 0.2),
+    );
+}
@@ -0,0 +1,321 @@
+// Quiz positions are checked by the solver, so builds without it keep only the idle
+// `DifficultyQuiz` the rest of the game checks
+#![cfg_attr(not(feature = "solver"), allow(dead_code, unused_imports))]
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use ttt3d_core::Difficulty;
+use ttt3d_core::game::{CellState, GameState, Player, Position, Rules};
+use ttt3d_core::notation::{has_line, parse_board, Board};
+#[cfg(feature = "solver")]
+use ttt3d_core::solver::{forced_win, refutation};
+use ttt3d_core::threats::threats;
+use crate::settings::{Profile, Settings};
+
+const QUIZ_DIR: &str = "quiz";
+
+// How long the verdict on an answer stays up before the next position
+const FEEDBACK_SECONDS: f32 = 2.0;
+
+// The first-launch quiz that picks a starting difficulty
+#[cfg(feature = "solver")]
+pub struct QuizPlugin;
+
+#[cfg(feature = "solver")]
+impl Plugin for QuizPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_difficulty_quiz)
+            .add_systems(Update, (drive_difficulty_quiz, update_quiz_text).chain());
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuestionKind {
+    // Complete a line this move
+    Win,
+    // Stop the AI's only threat
+    Block,
+    // Make two threats at once, so the AI can't stop both
+    Fork,
+}
+
+impl QuestionKind {
+    fn prompt(self) -> &'static str {
+        match self {
+            QuestionKind::Win => "Find the winning move",
+            QuestionKind::Block => "The AI threatens a line - find the block",
+            QuestionKind::Fork => "Find the move that makes two threats at once",
+        }
+    }
+
+    // Whether a position is a fair question of this kind
+    #[cfg(feature = "solver")]
+    fn check(self, board: &Board) -> Result<(), String> {
+        let can_win_now = forced_win(&mut board.clone(), 1);
+        match self {
+            QuestionKind::Win if !can_win_now => Err("there is no winning move".to_string()),
+            QuestionKind::Block if can_win_now => Err("you can win outright instead".to_string()),
+            QuestionKind::Block if !ai_threatens(board) => Err("the AI has no threat to block".to_string()),
+            QuestionKind::Fork if can_win_now => Err("you can win outright instead".to_string()),
+            QuestionKind::Fork if !forced_win(&mut board.clone(), 2) => Err("there is no fork".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "solver")]
+    fn is_correct(self, board: &Board, (x, y, z): Position) -> bool {
+        let mut after = *board;
+        after[x][y][z] = CellState::Human;
+        match self {
+            QuestionKind::Win => has_line(&after, CellState::Human),
+            QuestionKind::Block => !ai_threatens(&after),
+            // Every AI reply still leaves a win in one
+            QuestionKind::Fork => refutation(&mut after, 1).is_none(),
+        }
+    }
+}
+
+// Whether the AI could complete a line with its next piece
+fn ai_threatens(board: &Board) -> bool {
+    !threats(board, Player::AI).is_empty()
+}
+
+// Contents of `assets/quiz/*.ron`, in the puzzles' board notation; you are to move
+#[derive(Deserialize)]
+struct QuestionFile {
+    kind: QuestionKind,
+    layers: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Question {
+    pub kind: QuestionKind,
+    pub board: Board,
+}
+
+#[cfg(feature = "solver")]
+fn load_questions() -> Vec<Question> {
+    let dir = FileAssetReader::get_base_path().join("assets").join(QUIZ_DIR);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .filter_map(|path| match load_question(path) {
+            Ok(question) => Some(question),
+            Err(err) => {
+                warn!("Skipping quiz position {:?}: {}", path, err);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "solver")]
+fn load_question(path: &Path) -> Result<Question, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("cannot read file: {}", err))?;
+    let file: QuestionFile = ron::from_str(&contents).map_err(|err| format!("invalid quiz position: {}", err))?;
+    let board = parse_board(&file.layers)?;
+
+    let count = |cell: CellState| board.iter().flatten().flatten().filter(|&&c| c == cell).count();
+    if count(CellState::Human) != count(CellState::AI) {
+        return Err("you must be to move (equal piece counts)".to_string());
+    }
+    if has_line(&board, CellState::Human) || has_line(&board, CellState::AI) {
+        return Err("the position is already won".to_string());
+    }
+    file.kind.check(&board)?;
+
+    Ok(Question { kind: file.kind, board })
+}
+
+// Fewer than two right starts on Easy, two on Normal, and a clean sweep on Hard
+fn recommend(correct: usize) -> Difficulty {
+    match correct {
+        0 | 1 => Difficulty::Easy,
+        2 => Difficulty::Normal,
+        _ => Difficulty::Hard,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum QuizStage {
+    #[default]
+    Idle,
+    // Waiting for the player to take or skip the quiz
+    Offered,
+    Asking(usize),
+    // Showing whether the answer to a question was right
+    Answered { index: usize, correct: bool, timer: f32 },
+    Finished(Difficulty),
+}
+
+// Three positions on a new profile's first launch; the answers pick a starting difficulty
+#[derive(Resource, Default)]
+pub struct DifficultyQuiz {
+    pub stage: QuizStage,
+    questions: Vec<Question>,
+    correct: usize,
+    // The game the current question was set up in; anything else resetting it ends the quiz
+    generation: u64,
+}
+
+impl DifficultyQuiz {
+    // While a question is on the board the AI mustn't reply to the answer
+    pub fn is_asking(&self) -> bool {
+        matches!(self.stage, QuizStage::Asking(_) | QuizStage::Answered { .. })
+    }
+
+    fn ask(&mut self, index: usize, game_state: &mut GameState) {
+        game_state.rules = Rules::default();
+        game_state.reset();
+        game_state.load_position(self.questions[index].board, Player::Human);
+        self.generation = game_state.generation;
+        self.stage = QuizStage::Asking(index);
+    }
+}
+
+#[derive(Component)]
+pub struct QuizText;
+
+#[cfg(feature = "solver")]
+pub fn setup_difficulty_quiz(mut commands: Commands, settings: Res<Settings>, mut quiz: ResMut<DifficultyQuiz>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 22.0,
+                color: Color::srgb(0.6, 0.8, 1.0),
+                ..default()
+            },
+        )
+        .with_text_justify(JustifyText::Center)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            ..default()
+        }),
+        QuizText,
+    ));
+
+    if settings.new_profile {
+        quiz.questions = load_questions();
+        if !quiz.questions.is_empty() {
+            quiz.stage = QuizStage::Offered;
+        }
+    }
+}
+
+#[cfg(feature = "solver")]
+pub fn drive_difficulty_quiz(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    profile: Res<Profile>,
+    mut settings: ResMut<Settings>,
+    mut quiz: ResMut<DifficultyQuiz>,
+    mut game_state: ResMut<GameState>,
+) {
+    match quiz.stage {
+        QuizStage::Idle => {}
+        QuizStage::Offered => {
+            if keyboard.just_pressed(KeyCode::Enter) {
+                quiz.correct = 0;
+                quiz.ask(0, &mut game_state);
+            } else if keyboard.just_pressed(KeyCode::Escape) {
+                quiz.stage = QuizStage::Idle;
+            }
+        }
+        QuizStage::Asking(index) => {
+            // Restarting, or opening a puzzle or the editor, abandons the quiz
+            if game_state.generation != quiz.generation {
+                quiz.stage = QuizStage::Idle;
+                return;
+            }
+
+            // Running out of time on the turn clock counts as a wrong answer
+            let answer = game_state.move_history.last().copied();
+            if answer.is_none() && !game_state.game_over {
+                return;
+            }
+
+            let question = &quiz.questions[index];
+            let correct = answer.is_some_and(|position| question.kind.is_correct(&question.board, position));
+            if correct {
+                quiz.correct += 1;
+            }
+            quiz.stage = QuizStage::Answered { index, correct, timer: 0.0 };
+        }
+        QuizStage::Answered { index, correct, timer } => {
+            let timer = timer + time.delta_seconds();
+            if timer < FEEDBACK_SECONDS {
+                quiz.stage = QuizStage::Answered { index, correct, timer };
+                return;
+            }
+
+            if index + 1 < quiz.questions.len() {
+                quiz.ask(index + 1, &mut game_state);
+                return;
+            }
+
+            // The recommendation goes into the profile; a --difficulty flag still wins this session
+            let difficulty = recommend(quiz.correct);
+            match settings.overridden.difficulty.as_mut() {
+                Some(saved) => *saved = difficulty,
+                None => {
+                    settings.difficulty = difficulty;
+                    game_state.ai = settings.build_engine();
+                }
+            }
+            settings.save(&profile);
+            game_state.reset();
+            quiz.stage = QuizStage::Finished(difficulty);
+        }
+        QuizStage::Finished(_) => {
+            if keyboard.just_pressed(KeyCode::Enter) || !game_state.move_history.is_empty() {
+                quiz.stage = QuizStage::Idle;
+            }
+        }
+    }
+}
+
+pub fn update_quiz_text(quiz: Res<DifficultyQuiz>, mut text_query: Query<&mut Text, With<QuizText>>) {
+    if !quiz.is_changed() {
+        return;
+    }
+
+    let total = quiz.questions.len();
+    let message = match quiz.stage {
+        QuizStage::Idle => String::new(),
+        QuizStage::Offered => format!(
+            "New here? Press Enter for a {}-position quiz to pick your difficulty, or Esc to skip",
+            total
+        ),
+        QuizStage::Asking(index) => {
+            format!("Question {} of {}: {}", index + 1, total, quiz.questions[index].kind.prompt())
+        }
+        QuizStage::Answered { correct: true, .. } => "Correct!".to_string(),
+        QuizStage::Answered { correct: false, .. } => "Not quite".to_string(),
+        QuizStage::Finished(difficulty) => format!(
+            "{} of {} right - recommended difficulty: {} (saved to your profile). Press Enter to play",
+            quiz.correct,
+            total,
+            difficulty.name()
+        ),
+    };
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = message.clone();
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use crate::modal::{spawn_modal, swallow_input};
+use crate::settings::Profile;
+use crate::stats::{PlayerStats, STATS_FILE};
+use crate::vault::ProfileVault;
+
+// Asks what to do when the profile's stats file turns out to be damaged, rather than quietly
+// starting the player's records over
+#[derive(Resource, Default)]
+pub struct StatsRecovery {
+    // Put off until the next launch
+    dismissed: bool,
+}
+
+#[derive(Component)]
+pub struct StatsRecoveryRoot;
+
+#[derive(Component)]
+pub struct StatsRecoveryText;
+
+pub fn setup_stats_recovery(mut commands: Commands) {
+    spawn_modal(&mut commands, StatsRecoveryRoot, StatsRecoveryText, 640.0);
+}
+
+fn is_open(recovery: &StatsRecovery, vault: &ProfileVault) -> bool {
+    !recovery.dismissed && vault.damage(STATS_FILE).is_some()
+}
+
+// B restores the backup and N starts new stats, either way keeping the damaged file aside.
+// Esc puts the choice off until the next launch, and stats aren't saved in the meantime. Like
+// the passphrase prompt, it takes every key and click while it's up
+pub fn answer_stats_recovery(
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    profile: Res<Profile>,
+    mut vault: ResMut<ProfileVault>,
+    mut stats: ResMut<PlayerStats>,
+    mut recovery: ResMut<StatsRecovery>,
+) {
+    if !is_open(&recovery, &vault) {
+        return;
+    }
+    let (restore, start_over, dismiss) =
+        (keyboard.just_pressed(KeyCode::KeyB), keyboard.just_pressed(KeyCode::KeyN), keyboard.just_pressed(KeyCode::Escape));
+    swallow_input(&mut keyboard, &mut mouse);
+
+    if dismiss {
+        recovery.dismissed = true;
+        return;
+    }
+    let recovered = match (restore, start_over) {
+        (true, _) => match PlayerStats::load_backup(&profile, &vault) {
+            Some(backup) => backup,
+            None => return,
+        },
+        (_, true) => PlayerStats::default(),
+        _ => return,
+    };
+    if let Err(err) = vault.clear_damage(&profile, STATS_FILE) {
+        warn!("Failed to move the damaged stats for profile '{}' aside: {}", profile.name, err);
+        return;
+    }
+    *stats = recovered;
+    stats.save(&profile, &vault);
+}
+
+pub fn update_stats_recovery(
+    recovery: Res<StatsRecovery>,
+    vault: Res<ProfileVault>,
+    profile: Res<Profile>,
+    mut root_query: Query<&mut Visibility, With<StatsRecoveryRoot>>,
+    mut text_query: Query<&mut Text, With<StatsRecoveryText>>,
+) {
+    if !recovery.is_changed() && !vault.is_changed() {
+        return;
+    }
+
+    let open = is_open(&recovery, &vault);
+    for mut visibility in root_query.iter_mut() {
+        let shown = if open { Visibility::Visible } else { Visibility::Hidden };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+    let Some(damage) = vault.damage(STATS_FILE).filter(|_| open) else {
+        return;
+    };
+    let restore = match PlayerStats::load_backup(&profile, &vault) {
+        Some(backup) => format!("B - restore the copy from before the last save ({} games)", backup.games),
+        None => "There's no sound backup to restore".to_string(),
+    };
+    let message = format!(
+        "The stats for profile '{}' are damaged: {}\n\n{}\nN - start new stats\nEsc - decide next time, without saving stats until then\n\n\
+         The damaged file is kept beside the new one, with .damaged added to its name",
+        profile.name, damage, restore
+    );
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = message.clone();
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::settings::Settings;
+
+// Time away from the window that counts as a break and starts the play time over
+const BREAK_SECONDS: f32 = 5.0 * 60.0;
+
+// Continuous play time this session, and the nudge once it passes the chosen duration
+#[derive(Resource, Default)]
+pub struct PlayReminder {
+    // Seconds played since the session started, the last break, or the last dismissal
+    played: f32,
+    // How long the window has been out of focus
+    away: f32,
+    pub shown: bool,
+}
+
+#[derive(Component)]
+pub struct PlayReminderRoot;
+
+#[derive(Component)]
+pub struct PlayReminderText;
+
+pub fn setup_play_reminder(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(60.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            PlayReminderRoot,
+        ))
+        .with_children(|root| {
+            root.spawn(NodeBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.85).into(),
+                ..default()
+            })
+            .with_children(|panel| {
+                panel.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::srgb(0.9, 0.85, 0.6),
+                            ..default()
+                        },
+                    )
+                    .with_text_justify(JustifyText::Center),
+                    PlayReminderText,
+                ));
+            });
+        });
+}
+
+// Count play time while the window has focus. A long enough spell away counts as a break;
+// a shorter one just pauses the count
+pub fn track_play_time(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut reminder: ResMut<PlayReminder>,
+) {
+    let focused = window_query.get_single().map_or(true, |window| window.focused);
+    if !focused {
+        reminder.away += time.delta_seconds();
+        if reminder.away >= BREAK_SECONDS {
+            reminder.played = 0.0;
+            reminder.shown = false;
+        }
+        return;
+    }
+
+    reminder.away = 0.0;
+    reminder.played += time.delta_seconds();
+    if settings.play_reminder > 0 && reminder.played >= settings.play_reminder as f32 * 60.0 {
+        reminder.shown = true;
+    }
+}
+
+// Esc puts the reminder away, and the count starts over for the next one
+pub fn dismiss_play_reminder(keyboard: Res<ButtonInput<KeyCode>>, mut reminder: ResMut<PlayReminder>) {
+    if reminder.shown && keyboard.just_pressed(KeyCode::Escape) {
+        reminder.shown = false;
+        reminder.played = 0.0;
+    }
+}
+
+pub fn update_play_reminder(
+    reminder: Res<PlayReminder>,
+    settings: Res<Settings>,
+    mut root_query: Query<&mut Visibility, With<PlayReminderRoot>>,
+    mut text_query: Query<&mut Text, With<PlayReminderText>>,
+) {
+    if !reminder.is_changed() {
+        return;
+    }
+
+    for mut visibility in root_query.iter_mut() {
+        let wanted = if reminder.shown { Visibility::Visible } else { Visibility::Hidden };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+    if reminder.shown {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = format!(
+                "You've been playing for {} minutes - a good moment to stretch and rest your eyes.\nPress Esc to dismiss",
+                settings.play_reminder
+            );
+        }
+    }
+}
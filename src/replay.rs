@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use ttt3d_core::game::Rules;
+
+// Every board this build plays is 3x3x3
+pub const BOARD_SIZE: usize = 3;
+
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Stamped into every saved game record, so that one written by another build, or under
+// rules this build doesn't know, is refused on load rather than replayed wrongly
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Watermark {
+    pub app_version: String,
+    pub board_size: usize,
+    pub rules: Rules,
+    // The engine and settings behind any verdicts saved alongside the moves
+    pub engine: String,
+}
+
+impl Watermark {
+    pub fn new(rules: Rules, engine: String) -> Self {
+        Self {
+            app_version: APP_VERSION.to_string(),
+            board_size: BOARD_SIZE,
+            rules,
+            engine,
+        }
+    }
+
+    // Why a record stamped with this watermark can't be replayed by this build, if it can't
+    pub fn check(&self, rules: &Rules) -> Result<(), String> {
+        if self.board_size != BOARD_SIZE {
+            return Err(format!(
+                "it was recorded on a {size}x{size}x{size} board and this build only plays {BOARD_SIZE}x{BOARD_SIZE}x{BOARD_SIZE}, \
+                 so it can't be converted; open it with tictactoe-3d {} instead",
+                self.app_version,
+                size = self.board_size
+            ));
+        }
+        // A newer build may have rule variants this one would silently drop
+        if version_parts(&self.app_version) > version_parts(APP_VERSION) {
+            return Err(format!(
+                "it was written by tictactoe-3d {}, which may use rules this build ({}) doesn't know; \
+                 open it with {} or later",
+                self.app_version, APP_VERSION, self.app_version
+            ));
+        }
+        if self.rules != *rules {
+            return Err(format!(
+                "its moves are labelled with different rules than it was saved under ({:?} vs {:?}); \
+                 correct the record's rules to match the watermark's",
+                rules, self.rules
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn version_parts(version: &str) -> Vec<u32> {
+    version.split('.').map(|part| part.parse().unwrap_or(0)).collect()
+}
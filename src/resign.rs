@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{GamePhase, GameState};
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+
+// The AI gives up once this many of its searches in a row rate its move this badly
+const RESIGN_WIN_RATE: f64 = 0.05;
+const RESIGN_SEARCHES: u32 = 3;
+
+// A draw the human offers is taken in a dead-drawn position, or when the AI's latest search
+// didn't see it ahead
+const ACCEPT_DRAW_WIN_RATE: f64 = 0.5;
+
+// How the AI rates its game, for resigning and for making and answering draw offers. Only kept
+// in two-player games, where one side giving up or agreeing settles it
+#[derive(Resource, Default)]
+pub struct AiOutlook {
+    generation: u64,
+    // Searches in a row that found the AI all but lost
+    hopeless: u32,
+    // The latest search's rating of the AI's move
+    latest: Option<f64>,
+    // Turns taken when the AI offered a draw; the offer stands until the human moves
+    offer: Option<usize>,
+    offered: bool,
+    // Turns taken when the AI turned the human's offer down
+    declined: Option<usize>,
+}
+
+impl AiOutlook {
+    fn sync(&mut self, game_state: &GameState) {
+        if self.generation != game_state.generation {
+            *self = Self {
+                generation: game_state.generation,
+                ..default()
+            };
+        }
+    }
+
+    // Take in the rating of the search that just finished on `game_state`, before its move is
+    // played; returns whether the AI should resign instead of playing it
+    pub fn record_search(&mut self, game_state: &GameState) -> bool {
+        self.sync(game_state);
+        if game_state.rules.player_count() != 2 {
+            return false;
+        }
+        // Moves found without searching, such as a win or a block, leave an older search's
+        // statistics behind
+        let Some(win_rate) = game_state.ai.search_stats().filter(|stats| stats.board == game_state.board).and_then(|stats| stats.win_rate) else {
+            return false;
+        };
+
+        self.latest = Some(win_rate);
+        self.hopeless = if win_rate < RESIGN_WIN_RATE { self.hopeless + 1 } else { 0 };
+        self.hopeless >= RESIGN_SEARCHES
+    }
+
+    // After the AI's move: once the game can only be drawn, offer the draw, once a game
+    pub fn offer_if_dead_drawn(&mut self, game_state: &GameState) {
+        self.sync(game_state);
+        if !self.offered && game_state.rules.player_count() == 2 && game_state.is_dead_drawn() {
+            self.offer = Some(game_state.turns_taken());
+            self.offered = true;
+        }
+    }
+
+    fn offer_stands(&self, game_state: &GameState) -> bool {
+        self.generation == game_state.generation && self.offer == Some(game_state.turns_taken())
+    }
+}
+
+#[derive(Component)]
+pub struct DrawOfferText;
+
+pub fn setup_draw_offer_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            visibility: Visibility::Hidden,
+            ..TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::srgb(0.9, 0.85, 0.6),
+                    ..default()
+                },
+            )
+            .with_text_justify(JustifyText::Center)
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(110.0),
+                width: Val::Percent(100.0),
+                ..default()
+            })
+        },
+        DrawOfferText,
+    ));
+}
+
+// = on the human's turn accepts the AI's standing offer, or offers a draw of the human's own,
+// which the AI answers at once. Puzzles and the quiz are tests, not games to settle
+pub fn offer_draw(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut game_state: ResMut<GameState>,
+    mut outlook: ResMut<AiOutlook>,
+) {
+    if !keyboard.just_pressed(KeyCode::Equal)
+        || game_state.game_over
+        || !game_state.is_human_turn()
+        || game_state.phase == GamePhase::Editing
+        || game_state.rules.player_count() != 2
+        || puzzle.is_solving()
+        || quiz.is_asking()
+    {
+        return;
+    }
+
+    outlook.sync(&game_state);
+    let accepts = outlook.offer_stands(&game_state)
+        || game_state.is_dead_drawn()
+        || outlook.latest.is_some_and(|win_rate| win_rate <= ACCEPT_DRAW_WIN_RATE);
+    if accepts {
+        game_state.agree_draw();
+    } else {
+        outlook.declined = Some(game_state.turns_taken());
+    }
+}
+
+pub fn update_draw_offer_text(
+    game_state: Res<GameState>,
+    outlook: Res<AiOutlook>,
+    mut text_query: Query<(&mut Text, &mut Visibility), With<DrawOfferText>>,
+) {
+    let current = outlook.generation == game_state.generation && !game_state.game_over;
+    let message = if current && outlook.offer_stands(&game_state) {
+        Some("The AI offers a draw, as no line can be completed any more - press = to accept, or play on")
+    } else if current && outlook.declined == Some(game_state.turns_taken()) {
+        Some("The AI declines the draw")
+    } else {
+        None
+    };
+
+    for (mut text, mut visibility) in text_query.iter_mut() {
+        let shown = if message.is_some() { Visibility::Visible } else { Visibility::Hidden };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+        if let Some(message) = message.filter(|&message| text.sections[0].value != message) {
+            text.sections[0].value = message.to_string();
+        }
+    }
+}
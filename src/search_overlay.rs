@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use ttt3d_core::ai::MoveStats;
+use ttt3d_core::game::{CellState, GameState, Position};
+use crate::graphics::{cell_position, CameraController, PendingAiMove};
+
+const LABEL_WIDTH: f32 = 56.0;
+const LABEL_HEIGHT: f32 = 34.0;
+
+const TOP_MOVE_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+const MOVE_COLOR: Color = Color::srgb(0.8, 0.9, 1.0);
+
+// F6 labels each cell the AI's search looked at with its visits and win rate, live while it
+// thinks and until the human replies. Only MCTS keeps such statistics
+#[derive(Resource, Default)]
+#[allow(clippy::type_complexity)]
+pub struct SearchOverlay {
+    pub enabled: bool,
+    // Game generation, turns taken and board of the AI's latest search
+    searched: Option<(u64, usize, [[[CellState; 3]; 3]; 3])>,
+    moves: Vec<MoveStats>,
+}
+
+#[derive(Component)]
+pub struct VisitLabel(Position);
+
+pub fn setup_search_overlay(mut commands: Commands) {
+    for x in 0..3 {
+        for y in 0..3 {
+            for z in 0..3 {
+                commands.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 13.0,
+                            color: MOVE_COLOR,
+                            ..default()
+                        },
+                    )
+                    .with_text_justify(JustifyText::Center)
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(LABEL_WIDTH),
+                        ..default()
+                    }),
+                    VisitLabel((x, y, z)),
+                ));
+            }
+        }
+    }
+}
+
+pub fn toggle_search_overlay(keyboard: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<SearchOverlay>) {
+    if keyboard.just_pressed(KeyCode::F6) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+// Follow the AI's search while it runs, then keep its final statistics up until the human
+// moves. The engine's statistics are only taken for the board the search started from
+pub fn collect_search_stats(pending: Res<PendingAiMove>, game_state: Res<GameState>, mut overlay: ResMut<SearchOverlay>) {
+    if !overlay.enabled {
+        return;
+    }
+    if pending.search.is_some() {
+        overlay.searched = Some((game_state.generation, game_state.turns_taken(), game_state.board));
+    }
+
+    let Some((generation, turns, board)) = overlay.searched else {
+        return;
+    };
+    if game_state.generation != generation || game_state.turns_taken() > turns + 1 {
+        overlay.searched = None;
+        overlay.moves.clear();
+        return;
+    }
+    if let Some(stats) = game_state.ai.search_stats().filter(|stats| stats.board == board) {
+        if overlay.moves != stats.moves {
+            overlay.moves = stats.moves;
+        }
+    }
+}
+
+// Pin each label over its cell, brighter the more of the search went into that move
+pub fn update_visit_labels(
+    overlay: Res<SearchOverlay>,
+    ui_scale: Res<UiScale>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    mut label_query: Query<(&VisitLabel, &mut Text, &mut Style, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    let most_visits = overlay.moves.iter().map(|stats| stats.visits).max().unwrap_or(0);
+    for (label, mut text, mut style, mut visibility) in label_query.iter_mut() {
+        let (x, y, z) = label.0;
+        let stats = overlay.moves.iter().find(|stats| stats.position == label.0).filter(|_| overlay.enabled);
+        let screen = camera.world_to_viewport(camera_transform, cell_position(x, y, z));
+        let (Some(stats), Some(screen)) = (stats, screen) else {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        };
+
+        *visibility = Visibility::Visible;
+        style.left = Val::Px(screen.x / ui_scale.0 - LABEL_WIDTH / 2.0);
+        style.top = Val::Px(screen.y / ui_scale.0 - LABEL_HEIGHT / 2.0);
+
+        let share = stats.visits as f32 / most_visits.max(1) as f32;
+        let color = if stats.visits == most_visits { TOP_MOVE_COLOR } else { MOVE_COLOR };
+        text.sections[0].value = format!("{}\n{:.0}%", stats.visits, stats.win_rate * 100.0);
+        text.sections[0].style.color = color.with_alpha(0.35 + 0.65 * share);
+    }
+}
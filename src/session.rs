@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{GamePhase, GameState, Player};
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+
+// Results since the game was opened or last left for the main menu, kept across resets. Unlike
+// the profile's stats these are never saved
+#[derive(Resource, Default)]
+pub struct SessionScore {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    // The game last counted, so each is counted once
+    counted: Option<u64>,
+}
+
+#[derive(Component)]
+pub struct SessionScoreText;
+
+pub fn setup_session_score(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            visibility: Visibility::Hidden,
+            ..TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                ..default()
+            })
+        },
+        SessionScoreText,
+    ));
+}
+
+// Count each game as it ends, from the human's side: anyone else winning is a loss. Puzzles, the
+// quiz and positions from the editor aren't games of the session
+pub fn record_session_result(
+    game_state: Res<GameState>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut score: ResMut<SessionScore>,
+) {
+    if !game_state.game_over || score.counted == Some(game_state.generation) {
+        return;
+    }
+    score.counted = Some(game_state.generation);
+
+    let set_up = game_state.start_board.iter().flatten().flatten().any(|cell| cell.is_piece());
+    if puzzle.active.is_some() || quiz.is_asking() || set_up || game_state.phase == GamePhase::Editing {
+        return;
+    }
+    match game_state.winner {
+        Some(Player::Human) => score.wins += 1,
+        Some(_) => score.losses += 1,
+        None => score.draws += 1,
+    }
+}
+
+// Going back to the main menu ends the session's run; the game just left isn't counted again
+pub fn clear_session_score(mut score: ResMut<SessionScore>) {
+    *score = SessionScore {
+        counted: score.counted,
+        ..default()
+    };
+}
+
+pub fn update_session_score(score: Res<SessionScore>, mut text_query: Query<(&mut Text, &mut Visibility), With<SessionScoreText>>) {
+    if !score.is_changed() {
+        return;
+    }
+
+    let played = score.wins + score.losses + score.draws;
+    for (mut text, mut visibility) in text_query.iter_mut() {
+        *visibility = if played > 0 { Visibility::Visible } else { Visibility::Hidden };
+        text.sections[0].value = format!("Session: {} won, {} lost, {} drawn", score.wins, score.losses, score.draws);
+    }
+}
@@ -0,0 +1,1670 @@
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use ttt3d_core::ai::{Engine, EngineKind};
+use ttt3d_core::Difficulty;
+use ttt3d_core::game::{GamePhase, GameState, Player, Rules, ThirdPlayer};
+use crate::adaptive::{adaptive_budget, adaptive_label, AdaptiveLabel};
+use crate::colors::{piece_color_label, ColorTheme, PieceColorLabel};
+use crate::vault::{profile_lock_label, write_atomically, ProfileLockLabel, ProfileVault};
+use crate::lighting::LightTheme;
+use crate::mods::CLASSIC_PACK_ID;
+use crate::network::{learned_eval_available, learned_evaluator, network};
+use crate::puzzle::PuzzleMode;
+use crate::stats::PlayerStats;
+use crate::credits::CREDITS_LABEL;
+use crate::telemetry::{telemetry_label, TelemetryLabel};
+
+pub const MIN_UI_SCALE: f32 = 0.75;
+pub const MAX_UI_SCALE: f32 = 2.0;
+
+// Extra padding (world units) added to each cube's picking box for easier clicking
+pub const HIT_MARGINS: [f32; 4] = [0.0, 0.15, 0.3, 0.45];
+
+// Angles in degrees within which a click that misses every cube snaps to the one cell it
+// passes close to (0 = no snapping)
+pub const SNAP_ANGLES: [f32; 4] = [0.0, 1.5, 3.0, 5.0];
+
+// How quickly a camera that was let go stops turning: its speed falls by a factor of e every
+// 1/damping seconds (0 = no momentum, so it stops the moment it's let go)
+pub const CAMERA_DAMPINGS: [f32; 4] = [0.0, 3.0, 6.0, 12.0];
+
+// Degrees the board leans towards the cursor for depth (0 = off)
+pub const BOARD_TILTS: [f32; 3] = [0.0, 1.0, 2.0];
+
+// Handicap choices for how many cells start blocked
+pub const BLOCKED_CELL_COUNTS: [usize; 4] = [0, 2, 4, 6];
+
+// Passes each player may make per game under the pass variant (0 = off)
+pub const PASS_LIMITS: [u32; 4] = [0, 1, 2, 3];
+
+// Selectable per-move time limits in seconds (0 = no clock)
+pub const TURN_TIME_LIMITS: [u32; 4] = [0, 15, 30, 60];
+
+// Selectable AI think times in milliseconds (0 = a fixed amount of search set by the difficulty)
+pub const THINK_TIMES: [u32; 6] = [0, 200, 500, 1000, 2000, 5000];
+
+// Selectable continuous play times in minutes before a break reminder (0 = never remind)
+pub const PLAY_REMINDERS: [u32; 5] = [0, 30, 60, 90, 120];
+
+// Output sizes for board captures (F12), independent of the window size
+pub const CAPTURE_SIZES: [[u32; 2]; 4] = [[1920, 1080], [3840, 2160], [1024, 1024], [2048, 2048]];
+const MAX_CAPTURE_DIMENSION: u32 = 8192;
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+// The player profile that settings and stats are stored under
+#[derive(Resource, Clone, Debug)]
+pub struct Profile {
+    pub name: String,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn dir(&self) -> PathBuf {
+        PathBuf::from("profiles").join(&self.name)
+    }
+}
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub ui_scale: f32,
+    pub turn_time_limit: u32,
+    pub piece_pack: String,
+    pub rules: Rules,
+    pub high_contrast: bool,
+    pub color_theme: ColorTheme,
+    // Colorblind-safe piece colors, and a pattern shell around each player's pieces
+    pub colorblind: bool,
+    // Thin lines along the cell boundaries
+    pub board_frame: bool,
+    // Faint lines through the hovered empty cell, with their count beside it
+    pub hover_lines: bool,
+    // The flat per-layer map of the board in the top right, toggled with F8
+    pub minimap: bool,
+    pub hit_margin: f32,
+    // Near-miss clicks within this many degrees of exactly one empty cell pick it (0 = off)
+    pub snap_angle: f32,
+    // Skips decorative motion such as the falling 3D win announcement, piece spins and the
+    // light moving between games, for instant changes instead
+    pub reduced_motion: bool,
+    // Damping of the camera's momentum once it's let go; one of `CAMERA_DAMPINGS`
+    pub camera_damping: f32,
+    // How far the board leans towards the cursor, in degrees; one of `BOARD_TILTS`
+    pub board_tilt: f32,
+    // Minutes of continuous play before suggesting a break; 0 never does
+    pub play_reminder: u32,
+    pub light_theme: LightTheme,
+    // Pieces fall into their cell and bounce to rest instead of popping in
+    pub piece_drop: bool,
+    // Width and height of board captures in pixels
+    pub capture_size: [u32; 2],
+    pub engine: EngineKind,
+    // How long the AI searches each move, in milliseconds; 0 searches by the difficulty instead
+    pub think_time_ms: u32,
+    // Picture-in-picture view of the board from the opposite side
+    pub observer_view: bool,
+    // Let the AI think ahead during the human's turn
+    pub pondering: bool,
+    // Steer MCTS with the evaluation fitted by `--train-eval` instead of the network, if any
+    pub learned_eval: bool,
+    // AI search strength, recommended by the first-launch quiz
+    pub difficulty: Difficulty,
+    // Let the AI's strength follow how the player does, in place of the difficulty
+    pub adaptive_difficulty: bool,
+    // Per-profile seed for the lighting variation, so each profile gets its own reproducible looks
+    pub light_salt: u64,
+    // Each player's chosen piece color, indexed by `Player::index`; `None` keeps the pack's
+    pub piece_colors: [Option<[f32; 3]>; 3],
+    // Seed for the AI's searches, so a game can be replayed move for move when reporting a bug;
+    // `None` lets the AI vary its play
+    pub ai_seed: Option<u64>,
+    // The player agreed to an anonymous usage summary being kept on this computer
+    pub telemetry: bool,
+    #[serde(skip)]
+    pub overridden: LaunchOverrides,
+    // The adaptive level the AI is built at while adaptive difficulty is in charge, taken from
+    // the profile's stats
+    #[serde(skip)]
+    pub adaptive_level: Option<f64>,
+    // Set when no settings file existed yet, so first-launch offers can be shown
+    #[serde(skip)]
+    pub new_profile: bool,
+}
+
+// Saved values displaced by command-line flags; they are written back in place of the
+// session's values so flags never overwrite the profile
+#[derive(Clone, Debug, Default)]
+pub struct LaunchOverrides {
+    pub rules: Option<Rules>,
+    pub light_salt: Option<u64>,
+    pub difficulty: Option<Difficulty>,
+    pub engine: Option<EngineKind>,
+    pub ai_seed: Option<Option<u64>>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            turn_time_limit: 0,
+            piece_pack: CLASSIC_PACK_ID.to_string(),
+            rules: Rules::default(),
+            high_contrast: false,
+            color_theme: ColorTheme::Classic,
+            colorblind: false,
+            board_frame: true,
+            hover_lines: true,
+            minimap: true,
+            hit_margin: 0.0,
+            snap_angle: SNAP_ANGLES[1],
+            reduced_motion: false,
+            camera_damping: CAMERA_DAMPINGS[2],
+            board_tilt: BOARD_TILTS[1],
+            play_reminder: 0,
+            light_theme: LightTheme::Auto,
+            piece_drop: false,
+            capture_size: CAPTURE_SIZES[0],
+            engine: EngineKind::Mcts,
+            think_time_ms: 0,
+            observer_view: false,
+            pondering: true,
+            learned_eval: false,
+            difficulty: Difficulty::Normal,
+            adaptive_difficulty: false,
+            light_salt: 0,
+            piece_colors: [None; 3],
+            ai_seed: None,
+            telemetry: false,
+            overridden: LaunchOverrides::default(),
+            adaptive_level: None,
+            new_profile: false,
+        }
+    }
+}
+
+impl Settings {
+    // Load settings for a profile, falling back to defaults if the file is missing or malformed
+    pub fn load(profile: &Profile) -> Self {
+        let path = profile.dir().join(SETTINGS_FILE);
+        let mut settings = match fs::read_to_string(&path) {
+            Ok(contents) => ron::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Ignoring malformed settings file {:?}: {}", path, err);
+                Settings::default()
+            }),
+            Err(_) => Settings {
+                new_profile: true,
+                ..Settings::default()
+            },
+        };
+        settings.ui_scale = settings.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        settings.hit_margin = settings.hit_margin.clamp(0.0, HIT_MARGINS[HIT_MARGINS.len() - 1]);
+        settings.snap_angle = settings.snap_angle.clamp(0.0, SNAP_ANGLES[SNAP_ANGLES.len() - 1]);
+        settings.camera_damping = settings.camera_damping.clamp(0.0, CAMERA_DAMPINGS[CAMERA_DAMPINGS.len() - 1]);
+        settings.board_tilt = settings.board_tilt.clamp(0.0, BOARD_TILTS[BOARD_TILTS.len() - 1]);
+        settings.capture_size = settings.capture_size.map(|dimension| dimension.clamp(1, MAX_CAPTURE_DIMENSION));
+        if settings.light_salt == 0 {
+            settings.light_salt = rand::random::<u64>().max(1);
+            settings.save(profile);
+        }
+        settings
+    }
+
+    // The AI as currently configured
+    pub fn build_engine(&self) -> Arc<dyn Engine> {
+        let think_time = (self.think_time_ms > 0).then(|| Duration::from_millis(self.think_time_ms as u64));
+        let network = if self.learned_eval { learned_evaluator().or_else(network) } else { network() };
+        match self.adaptive_level {
+            Some(level) => {
+                let (simulations, search_depth, blunder_rate) = adaptive_budget(level);
+                self.engine.build_with_budget(simulations, search_depth, blunder_rate, think_time, network, self.ai_seed)
+            }
+            None => self.engine.build(self.difficulty, think_time, network, self.ai_seed),
+        }
+    }
+
+    // The player's own choice of rules or engine, which is saved even if a launch flag had
+    // displaced the saved one this session
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+        self.overridden.rules = None;
+    }
+
+    pub fn set_engine(&mut self, engine: EngineKind) {
+        self.engine = engine;
+        self.overridden.engine = None;
+    }
+
+    pub fn save(&self, profile: &Profile) {
+        let mut persisted = self.clone();
+        if let Some(rules) = self.overridden.rules {
+            persisted.rules = rules;
+        }
+        if let Some(light_salt) = self.overridden.light_salt {
+            persisted.light_salt = light_salt;
+        }
+        if let Some(difficulty) = self.overridden.difficulty {
+            persisted.difficulty = difficulty;
+        }
+        if let Some(engine) = self.overridden.engine {
+            persisted.engine = engine;
+        }
+        if let Some(ai_seed) = self.overridden.ai_seed {
+            persisted.ai_seed = ai_seed;
+        }
+
+        let dir = profile.dir();
+        let result = fs::create_dir_all(&dir).and_then(|_| {
+            let contents = ron::ser::to_string_pretty(&persisted, ron::ser::PrettyConfig::default())
+                .map_err(std::io::Error::other)?;
+            write_atomically(&dir.join(SETTINGS_FILE), contents.as_bytes())
+        });
+
+        if let Err(err) = result {
+            warn!("Failed to save settings for profile '{}': {}", profile.name, err);
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SettingsPanel {
+    pub open: bool,
+    pub dragging_ui_scale: bool,
+}
+
+#[derive(Component)]
+pub struct SettingsPanelRoot;
+
+#[derive(Component)]
+pub struct UiScaleSlider;
+
+#[derive(Component)]
+pub struct UiScaleSliderFill;
+
+#[derive(Component)]
+pub struct UiScaleLabel;
+
+#[derive(Component)]
+pub struct TurnClockLabel;
+
+#[derive(Component)]
+pub struct PiecePackLabel;
+
+#[derive(Component)]
+pub struct PieRuleLabel;
+
+#[derive(Component)]
+pub struct ThirdPlayerLabel;
+
+#[derive(Component)]
+pub struct NotaktoLabel;
+
+#[derive(Component)]
+pub struct BlockedCellsLabel;
+
+#[derive(Component)]
+pub struct FadingLabel;
+
+#[derive(Component)]
+pub struct LineScoringLabel;
+
+#[derive(Component)]
+pub struct PassesLabel;
+
+#[derive(Component)]
+pub struct LightThemeLabel;
+
+#[derive(Component)]
+pub struct ColorThemeLabel;
+
+#[derive(Component)]
+pub struct ColorblindLabel;
+
+#[derive(Component)]
+pub struct BoardFrameLabel;
+
+#[derive(Component)]
+pub struct HoverLinesLabel;
+
+#[derive(Component)]
+pub struct PieceDropLabel;
+
+#[derive(Component)]
+pub struct EngineLabel;
+
+#[derive(Component)]
+pub struct ThinkTimeLabel;
+
+#[derive(Component)]
+pub struct PonderingLabel;
+
+#[derive(Component)]
+pub struct LearnedEvalLabel;
+
+#[derive(Component)]
+pub struct PlayReminderLabel;
+
+#[derive(Component)]
+pub struct CaptureSizeLabel;
+
+#[derive(Component)]
+pub struct HighContrastLabel;
+
+#[derive(Component)]
+pub struct HitMarginLabel;
+
+#[derive(Component)]
+pub struct SnapAngleLabel;
+
+#[derive(Component)]
+pub struct ReducedMotionLabel;
+
+#[derive(Component)]
+pub struct CameraDampingLabel;
+
+#[derive(Component)]
+pub struct BoardTiltLabel;
+
+fn ui_scale_fraction(scale: f32) -> f32 {
+    (scale - MIN_UI_SCALE) / (MAX_UI_SCALE - MIN_UI_SCALE)
+}
+
+fn pie_rule_label(enabled: bool) -> String {
+    format!("Pie rule: {} [U]", if enabled { "On" } else { "Off" })
+}
+
+fn third_player_label(third_player: ThirdPlayer) -> String {
+    let value = match third_player {
+        ThirdPlayer::Off => "Off",
+        ThirdPlayer::Human => "Human",
+        ThirdPlayer::AI => "AI",
+    };
+    format!("Third player: {} [C]", value)
+}
+
+fn notakto_label(enabled: bool) -> String {
+    format!("Notakto (misere): {} [N]", if enabled { "On" } else { "Off" })
+}
+
+fn blocked_cells_label(count: usize) -> String {
+    format!("Blocked cells: {} [B]", count)
+}
+
+fn light_theme_label(theme: LightTheme) -> String {
+    match theme {
+        LightTheme::Auto => format!("Lighting: Auto ({}) [I]", theme.resolve().name()),
+        _ => format!("Lighting: {} [I]", theme.name()),
+    }
+}
+
+fn engine_label(engine: EngineKind) -> String {
+    format!("AI engine: {} [F]", engine.name())
+}
+
+fn think_time_label(think_time_ms: u32) -> String {
+    match think_time_ms {
+        0 => "AI think time: By difficulty [Tab]".to_string(),
+        ms if ms < 1000 => format!("AI think time: {}ms per move [Tab]", ms),
+        ms => format!("AI think time: {}s per move [Tab]", ms as f32 / 1000.0),
+    }
+}
+
+fn pondering_label(enabled: bool) -> String {
+    format!("AI thinks on your time: {} [6]", if enabled { "On" } else { "Off" })
+}
+
+fn learned_eval_label(enabled: bool) -> String {
+    match (enabled, learned_eval_available()) {
+        (true, false) => "Learned evaluation: On, not trained yet [9]".to_string(),
+        (enabled, _) => format!("Learned evaluation: {} [9]", if enabled { "On" } else { "Off" }),
+    }
+}
+
+fn piece_drop_label(enabled: bool) -> String {
+    format!("Piece drop: {} [J]", if enabled { "On" } else { "Off" })
+}
+
+fn capture_size_label([width, height]: [u32; 2]) -> String {
+    format!("Capture size: {}x{} [Y]", width, height)
+}
+
+fn fading_label(enabled: bool) -> String {
+    format!("Fading pieces: {} [V]", if enabled { "On" } else { "Off" })
+}
+
+fn line_scoring_label(enabled: bool) -> String {
+    format!("Line scoring: {} [G]", if enabled { "On" } else { "Off" })
+}
+
+fn passes_label(passes: u32) -> String {
+    match passes {
+        0 => "Passing: Off [4]".to_string(),
+        1 => "Passing: 1 pass each [4]".to_string(),
+        passes => format!("Passing: {} passes each [4]", passes),
+    }
+}
+
+fn color_theme_label(theme: ColorTheme) -> String {
+    format!("Color theme: {} [']", theme.name())
+}
+
+fn colorblind_label(enabled: bool) -> String {
+    format!("Colorblind aid: {} [/]", if enabled { "On" } else { "Off" })
+}
+
+fn board_frame_label(enabled: bool) -> String {
+    format!("Board frame: {} [`]", if enabled { "On" } else { "Off" })
+}
+
+fn hover_lines_label(enabled: bool) -> String {
+    format!("Lines through hovered cell: {} [\\]", if enabled { "On" } else { "Off" })
+}
+
+fn high_contrast_label(enabled: bool) -> String {
+    format!("High contrast: {} [K]", if enabled { "On" } else { "Off" })
+}
+
+fn hit_margin_label(margin: f32) -> String {
+    let size = match HIT_MARGINS.iter().position(|&m| (m - margin).abs() < 0.01) {
+        Some(0) => "Normal",
+        Some(1) => "Large",
+        Some(2) => "Larger",
+        _ => "Largest",
+    };
+    format!("Hit targets: {} [M]", size)
+}
+
+fn snap_angle_label(angle: f32) -> String {
+    if angle <= 0.0 {
+        "Near-miss snapping: Off [5]".to_string()
+    } else {
+        format!("Near-miss snapping: within {} degrees [5]", angle)
+    }
+}
+
+fn reduced_motion_label(enabled: bool) -> String {
+    format!("Reduced motion: {} [O]", if enabled { "On" } else { "Off" })
+}
+
+fn camera_damping_label(damping: f32) -> String {
+    let feel = match CAMERA_DAMPINGS.iter().position(|&choice| (choice - damping).abs() < 0.01) {
+        Some(0) => "Off, stops when let go",
+        Some(1) => "Light, long glide",
+        Some(2) => "Medium",
+        Some(3) => "Heavy, short glide",
+        _ => "Custom",
+    };
+    format!("Camera damping: {} [-]", feel)
+}
+
+fn board_tilt_label(degrees: f32) -> String {
+    if degrees <= 0.0 {
+        "Board tilt: Off [;]".to_string()
+    } else {
+        format!("Board tilt: {} degrees towards the cursor [;]", degrees)
+    }
+}
+
+fn play_reminder_label(minutes: u32) -> String {
+    match minutes {
+        0 => "Break reminder: Off [7]".to_string(),
+        minutes => format!("Break reminder: after {} min [7]", minutes),
+    }
+}
+
+fn turn_clock_label(limit: u32) -> String {
+    if limit == 0 {
+        "Turn clock: Off [T]".to_string()
+    } else {
+        format!("Turn clock: {}s per move [T]", limit)
+    }
+}
+
+pub fn setup_settings_panel(mut commands: Commands, settings: Res<Settings>, stats: Res<PlayerStats>, vault: Res<ProfileVault>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    width: Val::Px(260.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.85).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            SettingsPanelRoot,
+        ))
+        .with_children(|panel| {
+            panel.spawn(TextBundle::from_section(
+                "Settings (F1 to close)",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    format!("UI scale: {:.0}%", settings.ui_scale * 100.0),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                UiScaleLabel,
+            ));
+
+            // Slider track with a fill bar showing the current value
+            panel
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(14.0),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.3, 0.3, 0.3).into(),
+                        ..default()
+                    },
+                    Interaction::default(),
+                    RelativeCursorPosition::default(),
+                    UiScaleSlider,
+                ))
+                .with_children(|track| {
+                    track.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(ui_scale_fraction(settings.ui_scale) * 100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.7, 0.2).into(),
+                            ..default()
+                        },
+                        UiScaleSliderFill,
+                    ));
+                });
+
+            panel.spawn((
+                TextBundle::from_section(
+                    turn_clock_label(settings.turn_time_limit),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                TurnClockLabel,
+            ));
+
+            // Filled in by the piece pack system once the active pack is applied
+            panel.spawn((
+                TextBundle::from_section(
+                    "Piece pack: [P]",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                PiecePackLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    pie_rule_label(settings.rules.pie_rule),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                PieRuleLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    third_player_label(settings.rules.third_player),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                ThirdPlayerLabel,
+            ));
+
+            for player in [Player::Human, Player::AI, Player::Third] {
+                panel.spawn((
+                    TextBundle::from_section(
+                        piece_color_label(player, settings.piece_colors[player.index()]),
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::srgb(0.8, 0.8, 0.8),
+                            ..default()
+                        },
+                    ),
+                    PieceColorLabel(player),
+                ));
+            }
+
+            panel.spawn((
+                TextBundle::from_section(
+                    notakto_label(settings.rules.notakto),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                NotaktoLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    blocked_cells_label(settings.rules.blocked_cells),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                BlockedCellsLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    fading_label(settings.rules.fading),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                FadingLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    line_scoring_label(settings.rules.line_scoring),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                LineScoringLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    passes_label(settings.rules.passes),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                PassesLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    engine_label(settings.engine),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                EngineLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    think_time_label(settings.think_time_ms),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                ThinkTimeLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    pondering_label(settings.pondering),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                PonderingLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    learned_eval_label(settings.learned_eval),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                LearnedEvalLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    adaptive_label(&settings, &stats),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                AdaptiveLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    light_theme_label(settings.light_theme),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                LightThemeLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    piece_drop_label(settings.piece_drop),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                PieceDropLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    capture_size_label(settings.capture_size),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                CaptureSizeLabel,
+            ));
+
+            panel.spawn(TextBundle::from_section(
+                "Accessibility",
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    high_contrast_label(settings.high_contrast),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                HighContrastLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    color_theme_label(settings.color_theme),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                ColorThemeLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    colorblind_label(settings.colorblind),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                ColorblindLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    board_frame_label(settings.board_frame),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                BoardFrameLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    hover_lines_label(settings.hover_lines),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                HoverLinesLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    hit_margin_label(settings.hit_margin),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                HitMarginLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    snap_angle_label(settings.snap_angle),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                SnapAngleLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    reduced_motion_label(settings.reduced_motion),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                ReducedMotionLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    camera_damping_label(settings.camera_damping),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                CameraDampingLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    board_tilt_label(settings.board_tilt),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                BoardTiltLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    play_reminder_label(settings.play_reminder),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                PlayReminderLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    profile_lock_label(&vault),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                ProfileLockLabel,
+            ));
+
+            panel.spawn((
+                TextBundle::from_section(
+                    telemetry_label(&settings),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                ),
+                TelemetryLabel,
+            ));
+
+            panel.spawn(TextBundle::from_section(
+                CREDITS_LABEL,
+                TextStyle {
+                    font_size: 16.0,
+                    color: Color::srgb(0.8, 0.8, 0.8),
+                    ..default()
+                },
+            ));
+        });
+}
+
+pub fn toggle_settings_panel(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut panel: ResMut<SettingsPanel>,
+    mut panel_query: Query<&mut Visibility, With<SettingsPanelRoot>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F1) {
+        return;
+    }
+
+    panel.open = !panel.open;
+    for mut visibility in panel_query.iter_mut() {
+        *visibility = if panel.open { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
+
+pub fn handle_ui_scale_slider(
+    buttons: Res<ButtonInput<MouseButton>>,
+    slider_query: Query<&RelativeCursorPosition, With<UiScaleSlider>>,
+    mut panel: ResMut<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+) {
+    if !panel.open {
+        return;
+    }
+
+    let Ok(cursor) = slider_query.get_single() else {
+        return;
+    };
+
+    if buttons.just_pressed(MouseButton::Left) && cursor.mouse_over() {
+        panel.dragging_ui_scale = true;
+    }
+
+    if !panel.dragging_ui_scale {
+        return;
+    }
+
+    if let Some(position) = cursor.normalized {
+        let fraction = position.x.clamp(0.0, 1.0);
+        // Snap to 5% steps so the value is easy to reproduce
+        let scale = MIN_UI_SCALE + fraction * (MAX_UI_SCALE - MIN_UI_SCALE);
+        let scale = (scale * 20.0).round() / 20.0;
+        if (scale - settings.ui_scale).abs() > f32::EPSILON {
+            settings.ui_scale = scale;
+        }
+    }
+
+    // Only persist once the drag is finished to avoid writing the file every frame
+    if buttons.just_released(MouseButton::Left) {
+        panel.dragging_ui_scale = false;
+        settings.save(&profile);
+    }
+}
+
+pub fn cycle_turn_time_limit(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<TurnClockLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+
+    let current = TURN_TIME_LIMITS.iter().position(|&limit| limit == settings.turn_time_limit).unwrap_or(0);
+    settings.turn_time_limit = TURN_TIME_LIMITS[(current + 1) % TURN_TIME_LIMITS.len()];
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = turn_clock_label(settings.turn_time_limit);
+    }
+}
+
+pub fn toggle_pie_rule(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<PieRuleLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyU) {
+        return;
+    }
+
+    let rules = Rules { pie_rule: !settings.rules.pie_rule, ..settings.rules };
+    settings.set_rules(rules);
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = pie_rule_label(settings.rules.pie_rule);
+    }
+}
+
+pub fn cycle_third_player(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<ThirdPlayerLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+
+    let third_player = match settings.rules.third_player {
+        ThirdPlayer::Off => ThirdPlayer::Human,
+        ThirdPlayer::Human => ThirdPlayer::AI,
+        ThirdPlayer::AI => ThirdPlayer::Off,
+    };
+    let rules = Rules { third_player, ..settings.rules };
+    settings.set_rules(rules);
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = third_player_label(settings.rules.third_player);
+    }
+}
+
+pub fn toggle_notakto(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<NotaktoLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyN) {
+        return;
+    }
+
+    let rules = Rules { notakto: !settings.rules.notakto, ..settings.rules };
+    settings.set_rules(rules);
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = notakto_label(settings.rules.notakto);
+    }
+}
+
+pub fn cycle_blocked_cells(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<BlockedCellsLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyB) {
+        return;
+    }
+
+    let current = BLOCKED_CELL_COUNTS
+        .iter()
+        .position(|&count| count == settings.rules.blocked_cells)
+        .unwrap_or(0);
+    let rules = Rules { blocked_cells: BLOCKED_CELL_COUNTS[(current + 1) % BLOCKED_CELL_COUNTS.len()], ..settings.rules };
+    settings.set_rules(rules);
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = blocked_cells_label(settings.rules.blocked_cells);
+    }
+}
+
+pub fn toggle_fading(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<FadingLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyV) {
+        return;
+    }
+
+    let rules = Rules { fading: !settings.rules.fading, ..settings.rules };
+    settings.set_rules(rules);
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = fading_label(settings.rules.fading);
+    }
+}
+
+pub fn toggle_line_scoring(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<LineScoringLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyG) {
+        return;
+    }
+
+    let rules = Rules { line_scoring: !settings.rules.line_scoring, ..settings.rules };
+    settings.set_rules(rules);
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = line_scoring_label(settings.rules.line_scoring);
+    }
+}
+
+pub fn cycle_passes(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<PassesLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Digit4) {
+        return;
+    }
+
+    let current = PASS_LIMITS.iter().position(|&passes| passes == settings.rules.passes).unwrap_or(0);
+    let rules = Rules { passes: PASS_LIMITS[(current + 1) % PASS_LIMITS.len()], ..settings.rules };
+    settings.set_rules(rules);
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = passes_label(settings.rules.passes);
+    }
+}
+
+// Switching engines takes effect from the AI's next move, even mid-game
+pub fn cycle_engine(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut game_state: ResMut<GameState>,
+    mut label_query: Query<&mut Text, With<EngineLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+
+    let engine = settings.engine.next();
+    settings.set_engine(engine);
+    settings.save(&profile);
+    game_state.ai = settings.build_engine();
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = engine_label(settings.engine);
+    }
+}
+
+// Like switching engines, a new think time applies from the AI's next move
+pub fn cycle_think_time(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut game_state: ResMut<GameState>,
+    mut label_query: Query<&mut Text, With<ThinkTimeLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    let index = THINK_TIMES.iter().position(|&ms| ms == settings.think_time_ms).unwrap_or(0);
+    settings.think_time_ms = THINK_TIMES[(index + 1) % THINK_TIMES.len()];
+    settings.save(&profile);
+    game_state.ai = settings.build_engine();
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = think_time_label(settings.think_time_ms);
+    }
+}
+
+// Only MCTS is steered by an evaluation; the other engines ignore it
+pub fn toggle_learned_eval(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut game_state: ResMut<GameState>,
+    mut label_query: Query<&mut Text, With<LearnedEvalLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Digit9) {
+        return;
+    }
+
+    settings.learned_eval = !settings.learned_eval;
+    settings.save(&profile);
+    game_state.ai = settings.build_engine();
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = learned_eval_label(settings.learned_eval);
+    }
+}
+
+pub fn toggle_piece_drop(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<PieceDropLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyJ) {
+        return;
+    }
+
+    settings.piece_drop = !settings.piece_drop;
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = piece_drop_label(settings.piece_drop);
+    }
+}
+
+pub fn cycle_capture_size(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<CaptureSizeLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyY) {
+        return;
+    }
+
+    // A hand-edited size that isn't in the list starts the cycle over
+    let current = CAPTURE_SIZES.iter().position(|&size| size == settings.capture_size);
+    settings.capture_size = CAPTURE_SIZES[current.map_or(0, |i| (i + 1) % CAPTURE_SIZES.len())];
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = capture_size_label(settings.capture_size);
+    }
+}
+
+pub fn cycle_light_theme(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<LightThemeLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyI) {
+        return;
+    }
+
+    settings.light_theme = settings.light_theme.next();
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = light_theme_label(settings.light_theme);
+    }
+}
+
+pub fn toggle_high_contrast(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<HighContrastLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyK) {
+        return;
+    }
+
+    settings.high_contrast = !settings.high_contrast;
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = high_contrast_label(settings.high_contrast);
+    }
+}
+
+// ' steps through the color themes; `apply_piece_pack` recolors the board to match
+pub fn cycle_color_theme(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<ColorThemeLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Quote) {
+        return;
+    }
+
+    settings.color_theme = settings.color_theme.next();
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = color_theme_label(settings.color_theme);
+    }
+}
+
+pub fn toggle_colorblind(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<ColorblindLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Slash) {
+        return;
+    }
+
+    settings.colorblind = !settings.colorblind;
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = colorblind_label(settings.colorblind);
+    }
+}
+
+pub fn toggle_board_frame(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<BoardFrameLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Backquote) {
+        return;
+    }
+
+    settings.board_frame = !settings.board_frame;
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = board_frame_label(settings.board_frame);
+    }
+}
+
+pub fn toggle_hover_lines(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<HoverLinesLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Backslash) {
+        return;
+    }
+
+    settings.hover_lines = !settings.hover_lines;
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = hover_lines_label(settings.hover_lines);
+    }
+}
+
+pub fn cycle_hit_margin(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<HitMarginLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyM) {
+        return;
+    }
+
+    let current = HIT_MARGINS.iter().position(|&m| (m - settings.hit_margin).abs() < 0.01).unwrap_or(0);
+    settings.hit_margin = HIT_MARGINS[(current + 1) % HIT_MARGINS.len()];
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = hit_margin_label(settings.hit_margin);
+    }
+}
+
+pub fn cycle_snap_angle(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<SnapAngleLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Digit5) {
+        return;
+    }
+
+    let current = SNAP_ANGLES.iter().position(|&angle| (angle - settings.snap_angle).abs() < 0.01);
+    settings.snap_angle = SNAP_ANGLES[current.map_or(0, |i| (i + 1) % SNAP_ANGLES.len())];
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = snap_angle_label(settings.snap_angle);
+    }
+}
+
+pub fn toggle_pondering(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<PonderingLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Digit6) {
+        return;
+    }
+
+    settings.pondering = !settings.pondering;
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = pondering_label(settings.pondering);
+    }
+}
+
+pub fn toggle_reduced_motion(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<ReducedMotionLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::KeyO) {
+        return;
+    }
+
+    settings.reduced_motion = !settings.reduced_motion;
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = reduced_motion_label(settings.reduced_motion);
+    }
+}
+
+// Reduced motion turns the camera's momentum off whatever this is set to
+pub fn cycle_camera_damping(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<CameraDampingLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Minus) {
+        return;
+    }
+
+    let current = CAMERA_DAMPINGS.iter().position(|&damping| (damping - settings.camera_damping).abs() < 0.01);
+    settings.camera_damping = CAMERA_DAMPINGS[current.map_or(0, |i| (i + 1) % CAMERA_DAMPINGS.len())];
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = camera_damping_label(settings.camera_damping);
+    }
+}
+
+// Reduced motion keeps the board still whatever this is set to
+pub fn cycle_board_tilt(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<BoardTiltLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Semicolon) {
+        return;
+    }
+
+    let current = BOARD_TILTS.iter().position(|&degrees| (degrees - settings.board_tilt).abs() < 0.01);
+    settings.board_tilt = BOARD_TILTS[current.map_or(0, |i| (i + 1) % BOARD_TILTS.len())];
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = board_tilt_label(settings.board_tilt);
+    }
+}
+
+// A new duration applies to the time already played this session
+pub fn cycle_play_reminder(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+    mut label_query: Query<&mut Text, With<PlayReminderLabel>>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Digit7) {
+        return;
+    }
+
+    let current = PLAY_REMINDERS.iter().position(|&minutes| minutes == settings.play_reminder).unwrap_or(0);
+    settings.play_reminder = PLAY_REMINDERS[(current + 1) % PLAY_REMINDERS.len()];
+    settings.save(&profile);
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = play_reminder_label(settings.play_reminder);
+    }
+}
+
+// Rule changes take effect immediately on an untouched board, otherwise from the next reset
+// (puzzles, the board editor and positions set up in it always use the standard rules)
+pub fn sync_rules(settings: Res<Settings>, puzzle: Res<PuzzleMode>, mut game_state: ResMut<GameState>) {
+    let set_up = game_state.phase == GamePhase::Editing
+        || game_state.board.iter().flatten().flatten().any(|cell| cell.is_piece());
+    if puzzle.active.is_some() || set_up {
+        return;
+    }
+
+    if game_state.turns_taken() == 0 && game_state.rules != settings.rules {
+        let reblock = game_state.rules.blocked_cells != settings.rules.blocked_cells;
+        game_state.rules = settings.rules;
+        // Re-deal the blocked cells for the new handicap
+        if reblock {
+            game_state.reset();
+        }
+    }
+}
+
+// The variant labels, for when the rules change from outside the panel, as the main menu's mode
+// choice does
+#[allow(clippy::type_complexity)]
+pub fn update_variant_labels(
+    settings: Res<Settings>,
+    mut labels: ParamSet<(
+        Query<&mut Text, With<NotaktoLabel>>,
+        Query<&mut Text, With<FadingLabel>>,
+        Query<&mut Text, With<LineScoringLabel>>,
+        Query<&mut Text, With<PassesLabel>>,
+    )>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let rules = settings.rules;
+    let set = |text: &mut Text, label: String| {
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    };
+    for mut text in labels.p0().iter_mut() {
+        set(&mut text, notakto_label(rules.notakto));
+    }
+    for mut text in labels.p1().iter_mut() {
+        set(&mut text, fading_label(rules.fading));
+    }
+    for mut text in labels.p2().iter_mut() {
+        set(&mut text, line_scoring_label(rules.line_scoring));
+    }
+    for mut text in labels.p3().iter_mut() {
+        set(&mut text, passes_label(rules.passes));
+    }
+}
+
+pub fn apply_ui_scale(
+    settings: Res<Settings>,
+    panel: Res<SettingsPanel>,
+    mut ui_scale: ResMut<UiScale>,
+    mut label_query: Query<&mut Text, With<UiScaleLabel>>,
+    mut fill_query: Query<&mut Style, With<UiScaleSliderFill>>,
+) {
+    if !settings.is_changed() && !panel.is_changed() {
+        return;
+    }
+
+    // Rescaling while dragging would move the slider under the cursor, so wait for the release
+    if !panel.dragging_ui_scale && (ui_scale.0 - settings.ui_scale).abs() > f32::EPSILON {
+        ui_scale.0 = settings.ui_scale;
+    }
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = format!("UI scale: {:.0}%", settings.ui_scale * 100.0);
+    }
+
+    for mut style in fill_query.iter_mut() {
+        style.width = Val::Percent(ui_scale_fraction(settings.ui_scale) * 100.0);
+    }
+}
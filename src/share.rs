@@ -0,0 +1,210 @@
+use ab_glyph::{point, Font as _, FontArc, PxScale, ScaleFont};
+use bevy::prelude::*;
+use image::{imageops, Rgba, RgbaImage};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use ttt3d_core::game::{GameState, Player};
+use crate::adaptive::DifficultyController;
+use crate::capture::{CaptureDone, CaptureRequest};
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+use crate::settings::{Profile, Settings};
+
+pub const CAPTURE_LABEL: &str = "share-card";
+
+// The board render fills the top of the card, with the text in a strip below it
+const BOARD_SIZE: UVec2 = UVec2::new(800, 600);
+const TEXT_HEIGHT: u32 = 170;
+const BACKGROUND: Rgba<u8> = Rgba([22, 24, 34, 255]);
+
+// What the card says, taken when it is asked for so a new game can't change it mid-capture
+struct CardText {
+    result: String,
+    result_color: [u8; 3],
+    details: String,
+}
+
+#[derive(Resource, Default)]
+pub struct PendingShareCard(Option<CardText>);
+
+fn card_text(game_state: &GameState, settings: &Settings, controller: &DifficultyController) -> CardText {
+    let moves = game_state.move_history.len();
+    let (result, result_color) = match game_state.winner {
+        Some(Player::Human) => (format!("I beat the AI in {} moves", moves), [60, 200, 60]),
+        Some(Player::AI) => (format!("The AI beat me in {} moves", moves), [210, 60, 60]),
+        Some(Player::Third) => (format!("{} won in {} moves", game_state.player_name(Player::Third), moves), [60, 110, 210]),
+        None => (format!("Drawn in {} moves", moves), [210, 210, 60]),
+    };
+
+    let accuracy = match controller.accuracy() {
+        Some((found, critical)) => format!("Accuracy {:.0}% ({} of {} key moves)", 100.0 * found as f64 / critical as f64, found, critical),
+        None => "No key moves to find".to_string(),
+    };
+    let difficulty = match settings.adaptive_level {
+        Some(_) => "Adaptive".to_string(),
+        None => settings.difficulty.name().to_string(),
+    };
+    CardText {
+        result,
+        result_color,
+        details: format!("{} - {} - {}", accuracy, difficulty, chrono::Local::now().format("%Y-%m-%d")),
+    }
+}
+
+// F9 once a game is over renders the final board for a share card. Puzzles and the quiz are
+// tests rather than games, so they get none
+#[allow(clippy::too_many_arguments)]
+pub fn request_share_card(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    controller: Res<DifficultyController>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut pending: ResMut<PendingShareCard>,
+    mut requests: EventWriter<CaptureRequest>,
+) {
+    if !keyboard.just_pressed(KeyCode::F9) || !game_state.game_over || puzzle.active.is_some() || quiz.is_asking() {
+        return;
+    }
+
+    pending.0 = Some(card_text(&game_state, &settings, &controller));
+    requests.send(CaptureRequest {
+        size: BOARD_SIZE,
+        label: Some(CAPTURE_LABEL.to_string()),
+    });
+}
+
+// Lay the captured board and the text out on the card, save it under the profile's `share/`
+// folder, and copy it to the clipboard where the platform has a way to
+pub fn compose_share_card(
+    mut done: EventReader<CaptureDone>,
+    mut pending: ResMut<PendingShareCard>,
+    fonts: Res<Assets<Font>>,
+    profile: Res<Profile>,
+) {
+    for capture in done.read().filter(|capture| capture.label.as_deref() == Some(CAPTURE_LABEL)) {
+        let Some(text) = pending.0.take() else {
+            continue;
+        };
+        let board = match capture.image.clone().try_into_dynamic() {
+            Ok(dynamic) => dynamic.to_rgba8(),
+            Err(err) => {
+                warn!("Failed to convert the share card capture: {:?}", err);
+                continue;
+            }
+        };
+
+        let mut card = RgbaImage::from_pixel(BOARD_SIZE.x, BOARD_SIZE.y + TEXT_HEIGHT, BACKGROUND);
+        imageops::overlay(&mut card, &board, 0, 0);
+        // The default UI font, built into the binary
+        match fonts.get(&Handle::<Font>::default()) {
+            Some(font) => {
+                let [r, g, b] = text.result_color;
+                draw_centered(&mut card, &font.font, &text.result, 44.0, BOARD_SIZE.y + 20, Rgba([r, g, b, 255]));
+                draw_centered(&mut card, &font.font, &text.details, 24.0, BOARD_SIZE.y + 82, Rgba([220, 220, 220, 255]));
+                draw_centered(&mut card, &font.font, "3D Tic-Tac-Toe", 18.0, BOARD_SIZE.y + 128, Rgba([140, 140, 160, 255]));
+            }
+            None => warn!("No font for the share card's text"),
+        }
+
+        match save_card(&profile, &card) {
+            Ok(path) => {
+                info!("Saved share card to {:?}", path);
+                // Clipboard tools can take a moment to start, so the frame doesn't wait for them
+                thread::spawn(move || {
+                    if copy_to_clipboard(&path) {
+                        info!("Copied share card to the clipboard");
+                    } else {
+                        info!("No clipboard tool found; the share card is only saved to disk");
+                    }
+                });
+            }
+            Err(err) => warn!("Failed to save share card: {}", err),
+        }
+    }
+}
+
+// Write one line of `text` with its top at `top`, centred across the card
+fn draw_centered(card: &mut RgbaImage, font: &FontArc, text: &str, size: f32, top: u32, color: Rgba<u8>) {
+    let scaled = font.as_scaled(PxScale::from(size));
+    let width: f32 = text.chars().map(|c| scaled.h_advance(scaled.glyph_id(c))).sum();
+    let mut x = (card.width() as f32 - width) / 2.0;
+    let baseline = top as f32 + scaled.ascent();
+
+    for c in text.chars() {
+        let glyph = scaled.glyph_id(c).with_scale_and_position(scaled.scale(), point(x, baseline));
+        x += scaled.h_advance(glyph.id);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let (px, py) = (bounds.min.x as i32 + gx as i32, bounds.min.y as i32 + gy as i32);
+            if px < 0 || py < 0 || px >= card.width() as i32 || py >= card.height() as i32 {
+                return;
+            }
+            let pixel = card.get_pixel_mut(px as u32, py as u32);
+            for channel in 0..3 {
+                let blended = pixel[channel] as f32 + (color[channel] as f32 - pixel[channel] as f32) * coverage.min(1.0);
+                pixel[channel] = blended.round() as u8;
+            }
+        });
+    }
+}
+
+fn save_card(profile: &Profile, card: &RgbaImage) -> Result<PathBuf, String> {
+    let dir = profile.dir().join("share");
+    let path = dir.join(format!("card-{}.png", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    card.save(&path).map_err(|err| err.to_string())?;
+    Ok(path)
+}
+
+// There is no portable image clipboard, so this asks the platform's own tool: PowerShell on
+// Windows, AppleScript on macOS, and wl-copy or xclip on Linux. False if none of them worked
+fn copy_to_clipboard(path: &Path) -> bool {
+    let path_text = path.to_string_lossy();
+    let mut attempts: Vec<Command> = Vec::new();
+    if cfg!(target_os = "windows") {
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-STA",
+            "-Command",
+            &format!(
+                "Add-Type -AssemblyName System.Windows.Forms; [System.Windows.Forms.Clipboard]::SetImage([System.Drawing.Image]::FromFile('{}'))",
+                path_text.replace('\'', "''")
+            ),
+        ]);
+        attempts.push(command);
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.args(["-e", &format!("set the clipboard to (read (POSIX file \"{}\") as «class PNGf»)", path_text.replace('"', "\\\""))]);
+        attempts.push(command);
+    } else {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            match fs::File::open(path) {
+                Ok(file) => {
+                    let mut command = Command::new("wl-copy");
+                    command.args(["--type", "image/png"]).stdin(file);
+                    attempts.push(command);
+                }
+                Err(_) => return false,
+            }
+        }
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard", "-t", "image/png", "-i", &path_text]);
+        attempts.push(command);
+    }
+
+    attempts.into_iter().any(|mut command| {
+        command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
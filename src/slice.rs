@@ -0,0 +1,167 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use ttt3d_core::game::{GameState, Position};
+use crate::graphics::{cell_position, CameraController, CubeMaterials, Piece};
+use crate::settings::SettingsPanel;
+use crate::xray::XRayHover;
+
+// Outlines left where peeled cells were, so the hidden layers still show their pieces
+const PEELED_EMPTY_COLOR: Color = Color::srgba(0.6, 0.6, 0.6, 0.25);
+const PEELED_PIECE_ALPHA: f32 = 0.5;
+// Just inside a cube, so an outline never hides a visible cube's edges
+const PEELED_OUTLINE_SIZE: f32 = 0.7;
+// Trackpads scroll by the pixel; this far moves the picked layer by one
+const SCROLL_PIXELS_PER_LAYER: f32 = 40.0;
+
+// Peel view: ] takes away the layer nearest the camera, and again the next one, and [ puts them
+// back, so the middle and far layers can be seen and clicked. Layers run across whichever board
+// axis the camera looks along most, so they follow the view as it turns.
+//
+// Layer picking: scrolling the wheel away picks the nearest layer left, and again the next one,
+// and only that layer's cells can be hovered or clicked. Scrolling back past the nearest picks
+// from the whole board again
+#[derive(Resource, Default)]
+pub struct LayerSlice {
+    // Layers taken away, 0 to 2; the far layer always stays
+    pub peeled: usize,
+    // The only layer picked from, counting from the nearest, if one was scrolled to
+    pub active: Option<usize>,
+    // Scrolling not yet enough to move a layer, in layers
+    scroll: f32,
+    // The axis (0 for x, 1 for y, 2 for z) the layers are stacked along, and whether the camera
+    // is on its high side
+    axis: usize,
+    from_high: bool,
+}
+
+impl LayerSlice {
+    // Whether the cell is in a peeled layer: not drawn, and not picked by the cursor
+    pub fn hides(&self, position: Position) -> bool {
+        self.depth(position) < self.peeled
+    }
+
+    // Whether the cell is in the layer scrolled to, tinted by `update_cube_materials`
+    pub fn in_active_layer(&self, position: Position) -> bool {
+        self.active == Some(self.depth(position))
+    }
+
+    // Whether the cursor can pick the cell: it isn't peeled, and is in the layer scrolled to if any
+    pub fn pickable(&self, position: Position) -> bool {
+        !self.hides(position) && (self.active.is_none() || self.in_active_layer(position))
+    }
+
+    // 0 for the layer nearest the camera, 2 for the farthest
+    fn depth(&self, position: Position) -> usize {
+        let (x, y, z) = position;
+        let coordinate = [x, y, z][self.axis];
+        if self.from_high { 2 - coordinate } else { coordinate }
+    }
+}
+
+pub fn update_layer_slice(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    camera_query: Query<&Transform, With<CameraController>>,
+    mut slice: ResMut<LayerSlice>,
+) {
+    if keyboard.just_pressed(KeyCode::BracketRight) && slice.peeled < 2 {
+        slice.peeled += 1;
+    }
+    if keyboard.just_pressed(KeyCode::BracketLeft) && slice.peeled > 0 {
+        slice.peeled -= 1;
+    }
+    // A peeled layer can't be picked from, so the picked layer moves back to the nearest left
+    if slice.active.is_some_and(|active| active < slice.peeled) {
+        slice.active = Some(slice.peeled);
+    }
+
+    let Ok(transform) = camera_query.get_single() else {
+        return;
+    };
+    let view = transform.translation.abs();
+    let axis = if view.x >= view.y && view.x >= view.z {
+        0
+    } else if view.y >= view.z {
+        1
+    } else {
+        2
+    };
+    let from_high = transform.translation[axis] > 0.0;
+    if slice.axis != axis || slice.from_high != from_high {
+        slice.axis = axis;
+        slice.from_high = from_high;
+    }
+}
+
+// Only while playing, and not while the settings panel is open, as its own keys have focus
+pub fn scroll_pick_layer(mut wheel: EventReader<MouseWheel>, panel: Res<SettingsPanel>, mut slice: ResMut<LayerSlice>) {
+    if panel.open {
+        wheel.clear();
+        return;
+    }
+
+    let mut scroll = slice.scroll;
+    for event in wheel.read() {
+        scroll += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / SCROLL_PIXELS_PER_LAYER,
+        };
+    }
+    let mut active = slice.active;
+    while scroll >= 1.0 {
+        scroll -= 1.0;
+        active = Some(active.map_or(slice.peeled, |layer| (layer + 1).min(2)));
+    }
+    while scroll <= -1.0 {
+        scroll += 1.0;
+        active = active.filter(|&layer| layer > slice.peeled).map(|layer| layer - 1);
+    }
+    if slice.scroll != scroll {
+        slice.scroll = scroll;
+    }
+    if slice.active != active {
+        slice.active = active;
+    }
+}
+
+// Cubes in peeled layers are hidden along with the rest of their look in `update_cube_materials`.
+// So are pieces in front of a cube X-ray hover looks through to
+pub fn hide_peeled_pieces(slice: Res<LayerSlice>, xray: Res<XRayHover>, mut piece_query: Query<(&Piece, &mut Visibility)>) {
+    for (piece, mut visibility) in piece_query.iter_mut() {
+        let hidden = slice.hides(piece.position()) || xray.occludes(piece.position());
+        let shown = if hidden { Visibility::Hidden } else { Visibility::Inherited };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+}
+
+pub fn draw_peeled_layers(
+    slice: Res<LayerSlice>,
+    game_state: Res<GameState>,
+    cube_materials: Res<CubeMaterials>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut gizmos: Gizmos,
+) {
+    if slice.peeled == 0 {
+        return;
+    }
+
+    for x in 0..3 {
+        for y in 0..3 {
+            for z in 0..3 {
+                if !slice.hides((x, y, z)) {
+                    continue;
+                }
+                // In the piece's own color, whatever the pack or the player's choice made it
+                let color = cube_materials
+                    .piece(game_state.board[x][y][z])
+                    .and_then(|handle| materials.get(&handle))
+                    .map_or(PEELED_EMPTY_COLOR, |material| material.base_color.with_alpha(PEELED_PIECE_ALPHA));
+                gizmos.cuboid(
+                    Transform::from_translation(cell_position(x, y, z)).with_scale(Vec3::splat(PEELED_OUTLINE_SIZE)),
+                    color,
+                );
+            }
+        }
+    }
+}
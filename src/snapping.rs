@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use crate::graphics::CameraController;
+use crate::settings::Settings;
+
+const CONE_COLOR: Color = Color::srgba(0.4, 0.8, 1.0, 0.6);
+const INSIDE_COLOR: Color = Color::srgb(0.3, 1.0, 0.4);
+const OUTSIDE_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+
+// How far past the board the debug cone is drawn, in world units from the camera
+const CONE_LENGTH: f32 = 20.0;
+
+// F5 shows the cone of near misses that snap to a cell, for tuning the snap angle
+#[derive(Resource, Default)]
+pub struct SnapDebug {
+    pub enabled: bool,
+    // The latest cursor ray and the centers of the cells it could snap to
+    ray: Option<Ray3d>,
+    cells: Vec<Vec3>,
+}
+
+// Angle in radians between the ray and the direction from its origin to `point`
+fn angle_to(ray: Ray3d, point: Vec3) -> f32 {
+    (point - ray.origin).angle_between(*ray.direction)
+}
+
+// A ray that hit no cube still picks a cell if it passes within `max_angle` degrees of that
+// cell's center and of no other. Near two or more cells it's too ambiguous, so nothing is picked
+pub fn snap_target<T: Copy>(ray: Ray3d, cells: impl IntoIterator<Item = (T, Vec3)>, max_angle: f32) -> Option<T> {
+    if max_angle <= 0.0 {
+        return None;
+    }
+
+    let max_angle = max_angle.to_radians();
+    let mut near = cells
+        .into_iter()
+        .filter(|&(_, center)| (center - ray.origin).dot(*ray.direction) > 0.0 && angle_to(ray, center) <= max_angle);
+    match (near.next(), near.next()) {
+        (Some((target, _)), None) => Some(target),
+        _ => None,
+    }
+}
+
+// Keep what the hover system saw, so the cone can be drawn for it
+pub fn record_snap_ray(debug: &mut SnapDebug, ray: Option<Ray3d>, cells: impl IntoIterator<Item = Vec3>) {
+    if !debug.enabled {
+        return;
+    }
+    debug.ray = ray;
+    debug.cells.clear();
+    debug.cells.extend(cells);
+}
+
+pub fn toggle_snap_debug(keyboard: Res<ButtonInput<KeyCode>>, mut debug: ResMut<SnapDebug>) {
+    if keyboard.just_pressed(KeyCode::F5) {
+        debug.enabled = !debug.enabled;
+        debug.ray = None;
+    }
+}
+
+// The cone around the cursor ray, with a ring at each pickable cell's depth: cells whose
+// center sits inside their ring are in reach of a snap. Seen down the ray from the main camera
+// the rings are concentric around the cursor; the observer view shows the cone side-on
+pub fn draw_snap_cone(
+    debug: Res<SnapDebug>,
+    settings: Res<Settings>,
+    camera_query: Query<&GlobalTransform, With<CameraController>>,
+    mut gizmos: Gizmos,
+) {
+    let (true, Some(ray)) = (debug.enabled, debug.ray) else {
+        return;
+    };
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+
+    let max_angle = settings.snap_angle.to_radians();
+    let direction = ray.direction;
+    let radius_at = |depth: f32| depth * max_angle.tan();
+
+    // Four edges of the cone, from its apex at the ray origin to a far cap
+    let far = ray.origin + *direction * CONE_LENGTH;
+    let side = direction.any_orthonormal_vector();
+    let up = direction.cross(side);
+    for edge in [side, -side, up, -up] {
+        gizmos.line(ray.origin, far + edge * radius_at(CONE_LENGTH), CONE_COLOR);
+    }
+    gizmos.circle(far, direction, radius_at(CONE_LENGTH), CONE_COLOR);
+
+    let inside = |center: Vec3| angle_to(ray, center) <= max_angle;
+    for &center in &debug.cells {
+        let depth = (center - ray.origin).dot(*direction);
+        if depth <= 0.0 {
+            continue;
+        }
+        gizmos.circle(ray.origin + *direction * depth, direction, radius_at(depth), CONE_COLOR);
+        let color = if inside(center) { INSIDE_COLOR } else { OUTSIDE_COLOR };
+        // A small marker facing the camera at the cell's center
+        let facing = Dir3::new(camera.translation() - center).unwrap_or(Dir3::Y);
+        gizmos.circle(center, facing, 0.08, color);
+    }
+}
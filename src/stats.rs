@@ -0,0 +1,282 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use ttt3d_core::game::{line_kind, GameState, LineKind, Player};
+use crate::adaptive::AdaptiveRecord;
+use crate::graphics::THIRD_PLAYER_COLOR;
+use crate::settings::Profile;
+use crate::vault::ProfileVault;
+
+pub const STATS_FILE: &str = "stats.ron";
+
+// Tallest bar in the thinking-time graph, in pixels; the slowest move fills it
+const GRAPH_HEIGHT: f32 = 80.0;
+const BAR_WIDTH: f32 = 10.0;
+
+// Totals across every finished game on this profile (indexed by `Player::index`)
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStats {
+    pub games: u32,
+    pub moves: [u32; 3],
+    pub thinking_seconds: [f64; 3],
+    // The kind of line the human has lost the last few games to, and how many in a row
+    pub loss_streak: Option<(LineKind, u32)>,
+    // Where adaptive difficulty has settled for this player
+    pub adaptive: AdaptiveRecord,
+}
+
+impl PlayerStats {
+    // Load stats for a profile, starting fresh if there are none yet or the profile is locked. A
+    // damaged file starts fresh too, but is marked in the vault so it isn't saved over before
+    // the player has chosen what to do about it
+    pub fn load(profile: &Profile, vault: &mut ProfileVault) -> Self {
+        let loaded = vault.read_checked(profile, STATS_FILE).and_then(|contents| {
+            contents
+                .map(|contents| ron::from_str(&contents).map_err(|err| format!("it can't be read as stats: {}", err)))
+                .transpose()
+        });
+        match loaded {
+            Ok(stats) => stats.unwrap_or_default(),
+            Err(damage) => {
+                warn!("Stats for profile '{}' are damaged: {}", profile.name, damage);
+                vault.mark_damaged(STATS_FILE, damage);
+                PlayerStats::default()
+            }
+        }
+    }
+
+    // The stats from before the latest save, if their copy is sound
+    pub fn load_backup(profile: &Profile, vault: &ProfileVault) -> Option<Self> {
+        let contents = vault.read_backup(profile, STATS_FILE).ok()??;
+        ron::from_str(&contents).ok()
+    }
+
+    // Games played while the profile is still locked, or while damaged stats wait on the
+    // player, aren't recorded
+    pub fn save(&self, profile: &Profile, vault: &ProfileVault) {
+        if vault.is_locked() || vault.damage(STATS_FILE).is_some() {
+            return;
+        }
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)
+            .and_then(|contents| vault.write(profile, STATS_FILE, &contents));
+
+        if let Err(err) = result {
+            warn!("Failed to save stats for profile '{}': {}", profile.name, err);
+        }
+    }
+
+    // A loss to a different kind of line starts a new run, and anything but a loss ends it.
+    // Under Notakto the completed line is the loser's own, so it doesn't count
+    fn record_result(&mut self, game_state: &GameState) {
+        let lost_to = match (game_state.winner, game_state.winning_line) {
+            (Some(winner), Some(line)) if winner != Player::Human && !game_state.rules.notakto => Some(line_kind(&line)),
+            _ => None,
+        };
+        self.loss_streak = lost_to.map(|kind| match self.loss_streak {
+            Some((streak_kind, losses)) if streak_kind == kind => (kind, losses + 1),
+            _ => (kind, 1),
+        });
+    }
+
+    pub fn average_seconds(&self, player: Player) -> Option<f64> {
+        let moves = self.moves[player.index()];
+        (moves > 0).then(|| self.thinking_seconds[player.index()] / moves as f64)
+    }
+}
+
+// Wall-clock time each move of the current game took, from the start of the turn to the move
+#[derive(Resource, Default)]
+pub struct ThinkingTimes {
+    pub moves: Vec<(Player, f32)>,
+    generation: u64,
+    turn_started: f32,
+    // Who was to move last frame, so a new move can be credited to them
+    to_move: Option<Player>,
+    recorded: bool,
+}
+
+impl ThinkingTimes {
+    pub fn average_seconds(&self, player: Player) -> Option<f32> {
+        let times: Vec<f32> = self.moves.iter().filter(|(mover, _)| *mover == player).map(|&(_, secs)| secs).collect();
+        (!times.is_empty()).then(|| times.iter().sum::<f32>() / times.len() as f32)
+    }
+}
+
+pub fn record_thinking_times(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    profile: Res<Profile>,
+    vault: Res<ProfileVault>,
+    mut times: ResMut<ThinkingTimes>,
+    mut stats: ResMut<PlayerStats>,
+) {
+    let now = time.elapsed_seconds();
+    if times.generation != game_state.generation {
+        *times = ThinkingTimes {
+            generation: game_state.generation,
+            turn_started: now,
+            ..default()
+        };
+    }
+
+    // A reply made in the same frame (such as a puzzle's) took no time of its own
+    let mut mover = times.to_move.unwrap_or(game_state.current_player);
+    let mut elapsed = now - times.turn_started;
+    while times.moves.len() < game_state.move_history.len() {
+        times.moves.push((mover, elapsed));
+        times.turn_started = now;
+        mover = mover.next(game_state.rules.player_count());
+        elapsed = 0.0;
+    }
+
+    // Only touch the resource when something changed, so the graph isn't rebuilt every frame
+    if times.to_move != Some(game_state.current_player) {
+        times.to_move = Some(game_state.current_player);
+    }
+
+    if game_state.game_over && !times.recorded {
+        times.recorded = true;
+        stats.games += 1;
+        for &(player, seconds) in &times.moves {
+            stats.moves[player.index()] += 1;
+            stats.thinking_seconds[player.index()] += seconds as f64;
+        }
+        stats.record_result(&game_state);
+        stats.save(&profile, &vault);
+    }
+}
+
+#[derive(Component)]
+pub struct ThinkingGraph;
+
+#[derive(Component)]
+pub struct ThinkingGraphTitle;
+
+#[derive(Component)]
+pub struct ThinkingGraphBars;
+
+pub fn setup_thinking_graph(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(90.0),
+                    right: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.85).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            ThinkingGraph,
+        ))
+        .with_children(|graph| {
+            graph.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                ThinkingGraphTitle,
+            ));
+            graph.spawn((
+                NodeBundle {
+                    style: Style {
+                        height: Val::Px(GRAPH_HEIGHT),
+                        align_items: AlignItems::FlexEnd,
+                        column_gap: Val::Px(2.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ThinkingGraphBars,
+            ));
+        });
+}
+
+fn player_color(player: Player) -> Color {
+    match player {
+        Player::Human => Color::srgb(0.2, 0.7, 0.2),
+        Player::AI => Color::srgb(0.7, 0.2, 0.2),
+        Player::Third => THIRD_PLAYER_COLOR,
+    }
+}
+
+fn format_average(seconds: Option<f64>) -> String {
+    seconds.map_or_else(|| "-".to_string(), |seconds| format!("{:.1}s", seconds))
+}
+
+// Once a game ends, show one bar per move (colored by who played it) and the averages
+pub fn update_thinking_graph(
+    mut commands: Commands,
+    times: Res<ThinkingTimes>,
+    game_state: Res<GameState>,
+    stats: Res<PlayerStats>,
+    mut graph_query: Query<&mut Visibility, With<ThinkingGraph>>,
+    mut title_query: Query<&mut Text, With<ThinkingGraphTitle>>,
+    bars_query: Query<Entity, With<ThinkingGraphBars>>,
+) {
+    if !times.is_changed() {
+        return;
+    }
+
+    let show = game_state.game_over && !times.moves.is_empty();
+    for mut visibility in graph_query.iter_mut() {
+        *visibility = if show { Visibility::Visible } else { Visibility::Hidden };
+    }
+    let Ok(bars) = bars_query.get_single() else {
+        return;
+    };
+    commands.entity(bars).despawn_descendants();
+    if !show {
+        return;
+    }
+
+    let mut players = vec![Player::Human, Player::AI];
+    if game_state.rules.player_count() > 2 {
+        players.push(Player::Third);
+    }
+    let this_game: Vec<_> = players
+        .iter()
+        .map(|&player| {
+            let average = times.average_seconds(player).map(f64::from);
+            format!("{} {}", game_state.player_name(player), format_average(average))
+        })
+        .collect();
+    let all_games: Vec<_> = players
+        .iter()
+        .map(|&player| format!("{} {}", game_state.player_name(player), format_average(stats.average_seconds(player))))
+        .collect();
+    for mut text in title_query.iter_mut() {
+        text.sections[0].value = format!(
+            "Thinking time per move\nThis game: {}\nAll {} games: {}",
+            this_game.join(", "),
+            stats.games,
+            all_games.join(", ")
+        );
+    }
+
+    let slowest = times.moves.iter().map(|&(_, seconds)| seconds).fold(0.0, f32::max).max(0.001);
+    commands.entity(bars).with_children(|parent| {
+        for &(player, seconds) in &times.moves {
+            parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(BAR_WIDTH),
+                    // Keep instant moves visible as a sliver
+                    height: Val::Px((seconds / slowest * GRAPH_HEIGHT).max(2.0)),
+                    ..default()
+                },
+                background_color: player_color(player).into(),
+                ..default()
+            });
+        }
+    });
+}
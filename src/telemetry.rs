@@ -0,0 +1,320 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use ttt3d_core::game::{GameState, ThirdPlayer};
+use crate::capture::CaptureRequest;
+use crate::coordinates::CoordinateOverlay;
+use crate::hint::HintRequest;
+use crate::modal::{spawn_modal, swallow_input};
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+use crate::search_overlay::SearchOverlay;
+use crate::settings::{Profile, Settings, SettingsPanel};
+use crate::share;
+use crate::slice::LayerSlice;
+use crate::vault::write_atomically;
+
+pub const TELEMETRY_FILE: &str = "telemetry.ron";
+
+// Anonymous counts of how the game gets played, kept only on this computer and only once the
+// player has agreed to it. Nothing is ever sent: sharing it means sending the file by hand
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageSummary {
+    // The day collecting started
+    pub since: String,
+    // Finished games, by difficulty ("Adaptive" while the AI follows the player)
+    pub games_by_difficulty: BTreeMap<String, u32>,
+    // Moves over those games, for the average length
+    pub moves: u32,
+    // Finished games by rule variant; a game with several variants on counts for each
+    pub games_by_variant: BTreeMap<String, u32>,
+    // Times each feature was used
+    pub features: BTreeMap<String, u32>,
+}
+
+impl UsageSummary {
+    fn load(profile: &Profile) -> Self {
+        let path = profile.dir().join(TELEMETRY_FILE);
+        match fs::read_to_string(&path) {
+            Ok(contents) => ron::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Starting a new usage summary in place of the malformed {:?}: {}", path, err);
+                Self::started_today()
+            }),
+            Err(_) => Self::started_today(),
+        }
+    }
+
+    fn started_today() -> Self {
+        Self {
+            since: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            ..default()
+        }
+    }
+
+    fn save(&self, profile: &Profile) {
+        let dir = profile.dir();
+        let result = fs::create_dir_all(&dir).and_then(|_| {
+            let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(io::Error::other)?;
+            write_atomically(&dir.join(TELEMETRY_FILE), contents.as_bytes())
+        });
+        if let Err(err) = result {
+            warn!("Failed to save the usage summary for profile '{}': {}", profile.name, err);
+        }
+    }
+
+    fn count_feature(&mut self, feature: &str) {
+        *self.features.entry(feature.to_string()).or_default() += 1;
+    }
+
+    fn total_games(&self) -> u32 {
+        self.games_by_difficulty.values().sum()
+    }
+}
+
+// The summary being collected, and the screen that asks for consent and shows what's kept
+#[derive(Resource, Default)]
+pub struct Telemetry {
+    // Loaded while the player has agreed to collecting; `None` otherwise
+    summary: Option<UsageSummary>,
+    screen_open: bool,
+    // The game last counted, so each is only counted once it ends
+    generation: Option<u64>,
+    // The overlays' state last frame, so each time one is turned on counts as a use
+    shown: (bool, bool, bool),
+}
+
+impl Telemetry {
+    pub fn load(settings: &Settings, profile: &Profile) -> Self {
+        Self {
+            summary: settings.telemetry.then(|| UsageSummary::load(profile)),
+            ..default()
+        }
+    }
+}
+
+pub fn telemetry_label(settings: &Settings) -> String {
+    format!("Anonymous usage summary: {} [.]", if settings.telemetry { "On, kept on this computer" } else { "Off" })
+}
+
+#[derive(Component)]
+pub struct TelemetryLabel;
+
+#[derive(Component)]
+pub struct TelemetryScreenRoot;
+
+#[derive(Component)]
+pub struct TelemetryScreenText;
+
+pub fn setup_telemetry_screen(mut commands: Commands) {
+    spawn_modal(&mut commands, TelemetryScreenRoot, TelemetryScreenText, 640.0);
+}
+
+// . in the settings panel opens the screen
+pub fn open_telemetry_screen(keyboard: Res<ButtonInput<KeyCode>>, panel: Res<SettingsPanel>, mut telemetry: ResMut<Telemetry>) {
+    if panel.open && keyboard.just_pressed(KeyCode::Period) {
+        telemetry.screen_open = true;
+    }
+}
+
+// Enter turns collecting on, or off again, which deletes the summary; Esc closes the screen
+// as it is. Like the passphrase prompt, it takes every key and click while it's up
+pub fn answer_telemetry_screen(
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    profile: Res<Profile>,
+    mut settings: ResMut<Settings>,
+    mut telemetry: ResMut<Telemetry>,
+) {
+    if !telemetry.screen_open {
+        return;
+    }
+    let (confirm, close) = (keyboard.just_pressed(KeyCode::Enter), keyboard.just_pressed(KeyCode::Escape));
+    swallow_input(&mut keyboard, &mut mouse);
+
+    if close {
+        telemetry.screen_open = false;
+    }
+    if !confirm {
+        return;
+    }
+    settings.telemetry = !settings.telemetry;
+    settings.save(&profile);
+    if settings.telemetry {
+        let summary = UsageSummary::started_today();
+        summary.save(&profile);
+        telemetry.summary = Some(summary);
+    } else {
+        telemetry.summary = None;
+        let path = profile.dir().join(TELEMETRY_FILE);
+        if let Err(err) = fs::remove_file(&path) {
+            if err.kind() != io::ErrorKind::NotFound {
+                warn!("Failed to delete the usage summary {:?}: {}", path, err);
+            }
+        }
+    }
+}
+
+// Count each finished game and each time a feature is used, saving the summary as it changes.
+// Puzzles and the quiz are counted as features rather than games
+#[allow(clippy::too_many_arguments)]
+pub fn record_telemetry(
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    profile: Res<Profile>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    coordinates: Res<CoordinateOverlay>,
+    search: Res<SearchOverlay>,
+    slice: Res<LayerSlice>,
+    mut hints: EventReader<HintRequest>,
+    mut captures: EventReader<CaptureRequest>,
+    mut telemetry: ResMut<Telemetry>,
+) {
+    let shown = (coordinates.enabled, search.enabled, slice.peeled > 0);
+    let turned_on = [
+        ("coordinate overlay", shown.0 && !telemetry.shown.0),
+        ("search overlay", shown.1 && !telemetry.shown.1),
+        ("peel view", shown.2 && !telemetry.shown.2),
+    ];
+    let captures: Vec<&str> = captures
+        .read()
+        .map(|capture| match capture.label.as_deref() {
+            Some(share::CAPTURE_LABEL) => "share card",
+            _ => "screenshot",
+        })
+        .collect();
+    let hints = hints.read().count();
+    if telemetry.shown != shown {
+        telemetry.shown = shown;
+    }
+    let game_ended = game_state.game_over && telemetry.generation != Some(game_state.generation);
+    if game_ended {
+        telemetry.generation = Some(game_state.generation);
+    }
+
+    // Only borrowed for writing when there's something to count, so the screen isn't redrawn
+    // every frame
+    let used: Vec<&str> = turned_on
+        .into_iter()
+        .filter_map(|(feature, used)| used.then_some(feature))
+        .chain(captures)
+        .chain(std::iter::repeat_n("hint", hints))
+        .collect();
+    if (used.is_empty() && !game_ended) || telemetry.summary.is_none() {
+        return;
+    }
+    let Some(summary) = telemetry.summary.as_mut() else {
+        return;
+    };
+    for feature in used {
+        summary.count_feature(feature);
+    }
+
+    if game_ended {
+        if puzzle.active.is_some() {
+            summary.count_feature("puzzle");
+        } else if quiz.is_asking() {
+            summary.count_feature("difficulty quiz question");
+        } else {
+            let difficulty = match settings.adaptive_level {
+                Some(_) => "Adaptive".to_string(),
+                None => settings.difficulty.name().to_string(),
+            };
+            *summary.games_by_difficulty.entry(difficulty).or_default() += 1;
+            summary.moves += game_state.move_history.len() as u32;
+            for variant in variants(&game_state) {
+                *summary.games_by_variant.entry(variant.to_string()).or_default() += 1;
+            }
+            if !game_state.delegated_moves.is_empty() {
+                summary.count_feature("play for me");
+            }
+        }
+    }
+    summary.save(&profile);
+}
+
+fn variants(game_state: &GameState) -> Vec<&'static str> {
+    let rules = game_state.rules;
+    let variants: Vec<&'static str> = [
+        (rules.pie_rule, "pie rule"),
+        (rules.third_player != ThirdPlayer::Off, "third player"),
+        (rules.notakto, "notakto"),
+        (rules.blocked_cells > 0, "blocked cells"),
+        (rules.fading, "fading"),
+        (rules.line_scoring, "line scoring"),
+        (rules.passes > 0, "passes"),
+    ]
+    .into_iter()
+    .filter_map(|(on, name)| on.then_some(name))
+    .collect();
+    if variants.is_empty() {
+        vec!["standard"]
+    } else {
+        variants
+    }
+}
+
+pub fn update_telemetry_screen(
+    telemetry: Res<Telemetry>,
+    settings: Res<Settings>,
+    profile: Res<Profile>,
+    mut root_query: Query<&mut Visibility, With<TelemetryScreenRoot>>,
+    mut text_query: Query<&mut Text, (With<TelemetryScreenText>, Without<TelemetryLabel>)>,
+    mut label_query: Query<&mut Text, With<TelemetryLabel>>,
+) {
+    if settings.is_changed() {
+        let label = telemetry_label(&settings);
+        for mut text in label_query.iter_mut() {
+            if text.sections[0].value != label {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+    if !telemetry.is_changed() {
+        return;
+    }
+
+    for mut visibility in root_query.iter_mut() {
+        let shown = if telemetry.screen_open { Visibility::Visible } else { Visibility::Hidden };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+    if !telemetry.screen_open {
+        return;
+    }
+
+    let path = profile.dir().join(TELEMETRY_FILE);
+    let message = match &telemetry.summary {
+        None => format!(
+            "Anonymous usage summary\n\n\
+             With your agreement, the game keeps a summary of how it's played: games per difficulty and rule \
+             variant, the average game length, and how often features such as hints, the overlays and share \
+             cards are used. No names, boards, times of day or anything else about you are kept.\n\n\
+             The summary stays in {:?} and is never sent anywhere. If you'd like to help the developers, you \
+             can send them that file yourself.\n\n\
+             Enter - turn it on\nEsc - leave it off",
+            path
+        ),
+        Some(summary) => {
+            let contents = ron::ser::to_string_pretty(summary, ron::ser::PrettyConfig::default()).unwrap_or_default();
+            let games = summary.total_games();
+            let average = match games {
+                0 => "no games yet".to_string(),
+                games => format!("{:.1} moves a game on average", summary.moves as f64 / games as f64),
+            };
+            format!(
+                "Anonymous usage summary: On\n\n\
+                 This is everything kept, exactly as saved in {:?} ({}):\n\n{}\n\n\
+                 Enter - turn it off and delete the summary\nEsc - close",
+                path, average, contents
+            )
+        }
+    };
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = message.clone();
+    }
+}
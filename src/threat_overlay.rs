@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{GameState, Player};
+use ttt3d_core::threats::threat_lines;
+use crate::puzzle::PuzzleMode;
+use crate::quiz::DifficultyQuiz;
+
+// F7 tints every line where a player has two pieces and the third cell empty, in that player's
+// piece color, so threats to take or to block stand out. Puzzles and the quiz test spotting
+// them, so the tint stays off there
+#[derive(Resource, Default)]
+pub struct ThreatOverlay {
+    pub enabled: bool,
+    // Whose threat line each cell is on; a cell on several players' lines shows the first
+    // player's, the human's before the AI's
+    pub cells: [[[Option<Player>; 3]; 3]; 3],
+}
+
+pub fn toggle_threat_overlay(keyboard: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<ThreatOverlay>) {
+    if keyboard.just_pressed(KeyCode::F7) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+// Tinted by `update_cube_materials`
+pub fn update_threat_overlay(
+    game_state: Res<GameState>,
+    puzzle: Res<PuzzleMode>,
+    quiz: Res<DifficultyQuiz>,
+    mut overlay: ResMut<ThreatOverlay>,
+) {
+    let mut cells = [[[None; 3]; 3]; 3];
+    if overlay.enabled && !game_state.game_over && !puzzle.is_solving() && !quiz.is_asking() {
+        let players = [Player::Human, Player::AI, Player::Third];
+        for &player in players.iter().take(game_state.rules.player_count()).rev() {
+            for (line, _) in threat_lines(&game_state.board, player) {
+                for (x, y, z) in line {
+                    cells[x][y][z] = Some(player);
+                }
+            }
+        }
+    }
+    if overlay.cells != cells {
+        overlay.cells = cells;
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{line_index, line_kind, GameState, LineKind};
+use crate::graphics::cell_position;
+use crate::stats::PlayerStats;
+
+// Losses in a row to the same kind of line before the tip appears
+const LOSSES_BEFORE_TIP: u32 = 3;
+
+// How long each line of the family is traced in the illustration
+const SECONDS_PER_LINE: f32 = 0.6;
+
+const TIP_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+
+fn family_name(kind: LineKind) -> &'static str {
+    match kind {
+        LineKind::Axis => "straight lines",
+        LineKind::FaceDiagonal => "face diagonals",
+        LineKind::SpaceDiagonal => "space diagonals",
+    }
+}
+
+fn family_advice(kind: LineKind) -> &'static str {
+    match kind {
+        LineKind::Axis => {
+            "They run along a single row, column or pillar, including straight down through the layers - \
+             watch for two pieces lined up on any axis"
+        }
+        LineKind::FaceDiagonal => {
+            "They cross a layer corner to corner, and the upright slices too, not just the flat layers - \
+             check the diagonals of every slice, not only the one you're looking at"
+        }
+        LineKind::SpaceDiagonal => {
+            "They run corner to corner through the center, so every one needs the center cell - \
+             taking or blocking the center shuts them all"
+        }
+    }
+}
+
+// A post-game tip about the kind of line the player keeps losing to, with every line of that
+// kind traced on the board in turn. It lasts until the next game starts
+#[derive(Resource, Default)]
+pub struct LineTip {
+    pub kind: Option<LineKind>,
+    losses: u32,
+    // The game it was considered for, so each finished game is only looked at once
+    generation: Option<u64>,
+    // When the tip appeared, to time the illustration from
+    shown_at: f32,
+}
+
+#[derive(Component)]
+pub struct LineTipText;
+
+pub fn setup_line_tip(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 18.0,
+                color: TIP_COLOR,
+                ..default()
+            },
+        )
+        .with_text_justify(JustifyText::Center)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(140.0),
+            width: Val::Percent(100.0),
+            ..default()
+        }),
+        LineTipText,
+    ));
+}
+
+// Runs after the finished game has gone into the stats
+pub fn offer_line_tip(game_state: Res<GameState>, stats: Res<PlayerStats>, time: Res<Time>, mut tip: ResMut<LineTip>) {
+    if !game_state.game_over {
+        if tip.kind.is_some() {
+            tip.kind = None;
+        }
+        return;
+    }
+    if tip.generation == Some(game_state.generation) {
+        return;
+    }
+
+    tip.generation = Some(game_state.generation);
+    tip.shown_at = time.elapsed_seconds();
+    match stats.loss_streak {
+        Some((kind, losses)) if losses >= LOSSES_BEFORE_TIP => {
+            tip.kind = Some(kind);
+            tip.losses = losses;
+        }
+        _ => tip.kind = None,
+    }
+}
+
+pub fn update_line_tip_text(tip: Res<LineTip>, mut text_query: Query<&mut Text, With<LineTipText>>) {
+    if !tip.is_changed() {
+        return;
+    }
+
+    let message = tip.kind.map_or(String::new(), |kind| {
+        let count = line_index().lines.iter().filter(|line| line_kind(line) == kind).count();
+        format!(
+            "Tip: your last {} losses were all to {} ({} on the board, traced here).\n{}.",
+            tip.losses,
+            family_name(kind),
+            count,
+            family_advice(kind)
+        )
+    });
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = message.clone();
+    }
+}
+
+// Trace the family one line at a time, each growing from one end to the other
+pub fn draw_line_tip(tip: Res<LineTip>, time: Res<Time>, mut gizmos: Gizmos) {
+    let Some(kind) = tip.kind else {
+        return;
+    };
+
+    let family: Vec<_> = line_index().lines.iter().filter(|line| line_kind(line) == kind).collect();
+    let step = (time.elapsed_seconds() - tip.shown_at) / SECONDS_PER_LINE;
+    let line = family[step as usize % family.len()];
+    let progress = (step.fract() * 1.5).min(1.0);
+
+    let (start, end) = (cell_position(line[0].0, line[0].1, line[0].2), cell_position(line[2].0, line[2].1, line[2].2));
+    // Reach a little past the end cubes so the line reads through them
+    let overhang = (end - start).normalize() * 0.5;
+    let (start, end) = (start - overhang, end + overhang);
+    gizmos.line(start, start.lerp(end, progress), TIP_COLOR);
+    for &(x, y, z) in line {
+        gizmos.cuboid(Transform::from_translation(cell_position(x, y, z)).with_scale(Vec3::splat(0.9)), TIP_COLOR);
+    }
+}
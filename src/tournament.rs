@@ -0,0 +1,284 @@
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use ttt3d_core::ai::{Engine, MCTSAi};
+use ttt3d_core::game::{GameState, Player, Position};
+
+// Games in each pairing; the entrants alternate who moves first
+const GAMES_PER_MATCH: u32 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    // Uniformly random legal moves, as a baseline
+    Random,
+    // The game's AI; with no rollouts it plays on its win/block checks and position heuristics
+    // alone, and in strict mode it goes without the win/block checks
+    Mcts { simulations: u32, strict: bool },
+}
+
+pub struct Entrant {
+    pub name: &'static str,
+    pub strategy: Strategy,
+}
+
+// Seeded strongest first; the bracket pairs the top seed with the bottom one
+pub const ENTRANTS: [Entrant; 4] = [
+    Entrant { name: "MCTS 2000", strategy: Strategy::Mcts { simulations: 2000, strict: false } },
+    Entrant { name: "MCTS 2000 strict", strategy: Strategy::Mcts { simulations: 2000, strict: true } },
+    Entrant { name: "Heuristic", strategy: Strategy::Mcts { simulations: 0, strict: false } },
+    Entrant { name: "Random", strategy: Strategy::Random },
+];
+
+impl Strategy {
+    fn choose_move(self, game: &GameState) -> Option<Position> {
+        match self {
+            Strategy::Random => game.get_empty_positions().choose(&mut rand::thread_rng()).copied(),
+            Strategy::Mcts { simulations, strict } => {
+                let mut ai = MCTSAi::new();
+                ai.simulations = simulations;
+                ai.strict = strict;
+                ai.best_move(game, None)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Standing {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+// Two entrants (indices into `ENTRANTS`) and their results against each other
+#[derive(Clone, Debug)]
+pub struct Pairing {
+    pub a: usize,
+    pub b: usize,
+    pub a_wins: u32,
+    pub b_wins: u32,
+    pub draws: u32,
+    pub winner: Option<usize>,
+}
+
+impl Pairing {
+    fn new(a: usize, b: usize) -> Self {
+        Self { a, b, a_wins: 0, b_wins: 0, draws: 0, winner: None }
+    }
+
+    fn games_played(&self) -> u32 {
+        self.a_wins + self.b_wins + self.draws
+    }
+}
+
+// A single-elimination bracket between AI configurations, played one move per frame
+// on boards of its own while the tournament screen is open
+#[derive(Resource, Default)]
+pub struct Tournament {
+    pub screen_open: bool,
+    pub standings: Vec<Standing>,
+    pub rounds: Vec<Vec<Pairing>>,
+    pub champion: Option<usize>,
+    game: Option<GameState>,
+}
+
+impl Tournament {
+    fn start(&mut self) {
+        let seeds = ENTRANTS.len();
+        self.standings = vec![Standing::default(); seeds];
+        self.rounds = vec![(0..seeds / 2).map(|i| Pairing::new(i, seeds - 1 - i)).collect()];
+        self.champion = None;
+        self.game = None;
+    }
+
+    fn is_running(&self) -> bool {
+        !self.rounds.is_empty() && self.champion.is_none()
+    }
+
+    // Play one move of the current game, or move the bracket on once a game or round is done
+    fn step(&mut self) {
+        let Some(round) = self.rounds.last_mut() else {
+            return;
+        };
+
+        let Some(pairing) = round.iter_mut().find(|pairing| pairing.winner.is_none()) else {
+            let winners: Vec<usize> = round.iter().filter_map(|pairing| pairing.winner).collect();
+            if let [champion] = winners[..] {
+                self.champion = Some(champion);
+            } else {
+                self.rounds.push(winners.chunks(2).map(|pair| Pairing::new(pair[0], pair[1])).collect());
+            }
+            return;
+        };
+
+        // The entrants swap sides every game; the Human seat moves first
+        let a_first = pairing.games_played() % 2 == 0;
+        let seat = |player: Player| if (player == Player::Human) == a_first { pairing.a } else { pairing.b };
+
+        let game = self.game.get_or_insert_with(GameState::default);
+        if !game.game_over {
+            let mover = seat(game.current_player);
+            if let Some((x, y, z)) = ENTRANTS[mover].strategy.choose_move(game) {
+                game.make_move(x, y, z);
+            }
+            return;
+        }
+
+        match game.winner.map(seat) {
+            Some(winner) => {
+                let loser = if winner == pairing.a { pairing.b } else { pairing.a };
+                self.standings[winner].wins += 1;
+                self.standings[loser].losses += 1;
+                if winner == pairing.a {
+                    pairing.a_wins += 1;
+                } else {
+                    pairing.b_wins += 1;
+                }
+            }
+            None => {
+                self.standings[pairing.a].draws += 1;
+                self.standings[pairing.b].draws += 1;
+                pairing.draws += 1;
+            }
+        }
+        self.game = None;
+
+        // A tied pairing goes to the higher seed
+        if pairing.games_played() == GAMES_PER_MATCH {
+            pairing.winner = Some(if pairing.b_wins > pairing.a_wins { pairing.b } else { pairing.a });
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct TournamentScreen;
+
+#[derive(Component)]
+pub struct TournamentText;
+
+pub fn setup_tournament_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.05, 0.05, 0.08, 0.95).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            TournamentScreen,
+        ))
+        .with_children(|screen| {
+            screen.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                TournamentText,
+            ));
+        });
+}
+
+// F3 shows the tournament screen, starting a bracket the first time; Enter starts a new one
+pub fn toggle_tournament_screen(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut tournament: ResMut<Tournament>,
+    mut screen_query: Query<&mut Visibility, With<TournamentScreen>>,
+) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        tournament.screen_open = !tournament.screen_open;
+        if tournament.screen_open && tournament.rounds.is_empty() {
+            tournament.start();
+        }
+        for mut visibility in screen_query.iter_mut() {
+            *visibility = if tournament.screen_open { Visibility::Visible } else { Visibility::Hidden };
+        }
+    }
+
+    if tournament.screen_open && keyboard.just_pressed(KeyCode::Enter) {
+        tournament.start();
+    }
+}
+
+pub fn run_tournament(mut tournament: ResMut<Tournament>) {
+    if tournament.screen_open && tournament.is_running() {
+        tournament.step();
+    }
+}
+
+pub fn update_tournament_text(
+    tournament: Res<Tournament>,
+    mut text_query: Query<&mut Text, With<TournamentText>>,
+) {
+    if !tournament.is_changed() || !tournament.screen_open {
+        return;
+    }
+
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let mut lines = vec![
+        "AI Tournament (F3 to close, Enter to restart)".to_string(),
+        String::new(),
+        "Standings (wins / draws / losses)".to_string(),
+    ];
+
+    // Most wins first, then fewest losses
+    let mut order: Vec<usize> = (0..tournament.standings.len()).collect();
+    order.sort_by_key(|&i| (std::cmp::Reverse(tournament.standings[i].wins), tournament.standings[i].losses));
+    for (place, &i) in order.iter().enumerate() {
+        let standing = tournament.standings[i];
+        lines.push(format!(
+            "  {}. {}: {} / {} / {}",
+            place + 1,
+            ENTRANTS[i].name,
+            standing.wins,
+            standing.draws,
+            standing.losses
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("Bracket".to_string());
+    let round_count = tournament.rounds.len();
+    for (round, pairings) in tournament.rounds.iter().enumerate() {
+        let name = if pairings.len() == 1 && round + 1 == round_count { "Final" } else { "Semifinal" };
+        // Only the first undecided pairing of the latest round is being played
+        let current = pairings.iter().position(|pairing| pairing.winner.is_none());
+        for (index, pairing) in pairings.iter().enumerate() {
+            let result = match pairing.winner {
+                Some(winner) => format!(" -> {}", ENTRANTS[winner].name),
+                None if current == Some(index) => {
+                    format!(" (game {} of {})", pairing.games_played() + 1, GAMES_PER_MATCH)
+                }
+                None => String::new(),
+            };
+            lines.push(format!(
+                "  {}: {} {}-{}-{} {}{}",
+                name,
+                ENTRANTS[pairing.a].name,
+                pairing.a_wins,
+                pairing.draws,
+                pairing.b_wins,
+                ENTRANTS[pairing.b].name,
+                result
+            ));
+        }
+    }
+
+    if let Some(champion) = tournament.champion {
+        lines.push(String::new());
+        lines.push(format!("Champion: {}", ENTRANTS[champion].name));
+    }
+
+    text.sections[0].value = lines.join("\n");
+}
@@ -0,0 +1,110 @@
+use rand::Rng;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+use ttt3d_core::ai::{Engine, MCTSAi, SearchState};
+use ttt3d_core::game::{GameState, Rules};
+use ttt3d_core::learned::{LinearEvaluator, Sample};
+use crate::network::LEARNED_EVAL_PATH;
+
+// Self-play games recorded unless `--train-games` says otherwise
+pub const DEFAULT_GAMES: u32 = 200;
+
+// Search per self-play move: enough to play sensibly, little enough for hundreds of games
+const SELF_PLAY_SIMULATIONS: u32 = 1000;
+// Each game opens with this many random moves, so the games don't all play the same line
+const RANDOM_OPENING_MOVES: usize = 2;
+
+// One self-play game, recording each position before its move is made, scored afterwards by
+// how the game went for the side to move there
+fn play(rules: Rules, rng: &mut impl Rng) -> Vec<Sample> {
+    let mut ai = MCTSAi::new();
+    ai.simulations = SELF_PLAY_SIMULATIONS;
+    // The games already keep every core busy
+    ai.threads = 1;
+
+    let mut game = GameState { rules, ..GameState::default() };
+    game.reset();
+
+    let mut positions = Vec::new();
+    while !game.game_over {
+        let reply = if game.turns_taken() < RANDOM_OPENING_MOVES {
+            let moves = MCTSAi::get_possible_moves_for_state(&game.board);
+            (!moves.is_empty()).then(|| moves[rng.gen_range(0..moves.len())])
+        } else {
+            ai.best_move(&game, None)
+        };
+        let Some((x, y, z)) = reply else {
+            break;
+        };
+        positions.push(SearchState::from_game(&game));
+        game.make_move(x, y, z);
+    }
+
+    positions
+        .iter()
+        .map(|state| {
+            let outcome = match game.winner {
+                Some(winner) if winner == state.to_move => 1.0,
+                Some(_) => -1.0,
+                None => 0.0,
+            };
+            Sample::new(state, outcome)
+        })
+        .collect()
+}
+
+// `--train-eval`: play MCTS against itself, fit `LinearEvaluator` weights to how each position
+// turned out, and write them to `LEARNED_EVAL_PATH` for the learned-evaluation setting. Runs
+// without a window, spread over every core
+pub fn run(games: u32, rules: Rules) {
+    if rules.player_count() != 2 || rules.notakto || rules.fading || rules.line_scoring {
+        eprintln!("error: the learned evaluation only covers two-player games without Notakto, fading or line scoring");
+        std::process::exit(2);
+    }
+
+    let next = AtomicU32::new(0);
+    let samples = Mutex::new(Vec::new());
+    let workers = thread::available_parallelism().map_or(1, |count| count.get());
+    eprintln!("Training: {} self-play games at {} simulations a move, on {} threads", games, SELF_PLAY_SIMULATIONS, workers);
+
+    let started = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                let mut rng = rand::thread_rng();
+                while next.fetch_add(1, Ordering::Relaxed) < games {
+                    let played = play(rules, &mut rng);
+                    let mut samples = samples.lock().expect("training thread panicked");
+                    samples.extend(played);
+                }
+            });
+        }
+    });
+    let samples = samples.into_inner().expect("training thread panicked");
+    eprintln!("Played in {:.0?}; fitting {} positions", started.elapsed(), samples.len());
+
+    let evaluator = LinearEvaluator::fit(&samples);
+    println!("Mean squared error: {:.3} (all zero weights: {:.3})", evaluator.error(&samples), LinearEvaluator { weights: Default::default() }.error(&samples));
+    println!("Weights: {:?}", evaluator.weights);
+
+    let path = Path::new(LEARNED_EVAL_PATH);
+    let written = ron::ser::to_string_pretty(&evaluator, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())
+        .and_then(|contents| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+            }
+            fs::write(path, contents).map_err(|err| err.to_string())
+        });
+    match written {
+        Ok(()) => println!("Wrote {}", LEARNED_EVAL_PATH),
+        Err(err) => {
+            eprintln!("error: couldn't write {}: {}", LEARNED_EVAL_PATH, err);
+            std::process::exit(1);
+        }
+    }
+}
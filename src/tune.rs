@@ -0,0 +1,179 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use ttt3d_core::ai::{Engine, MCTSAi, RolloutWeights};
+use ttt3d_core::game::{GameState, Player, Rules};
+
+// Games each configuration plays against the defaults unless `--tune-games` says otherwise
+pub const DEFAULT_GAMES: u32 = 20;
+
+// The grid searched: every combination of these is one configuration
+const EXPLORATION_PARAMS: [f64; 4] = [0.7, 1.0, 1.414, 2.0];
+// RAVE equivalence parameters, 0 being plain UCT
+const RAVE_EQUIVALENCES: [f64; 2] = [0.0, 300.0];
+const SIMULATIONS: [u32; 3] = [500, 2000, 5000];
+const SMART_CHANCES: [f64; 3] = [0.5, 0.7, 0.9];
+// (center, corner) weights for rollout moves
+const POSITION_WEIGHTS: [(f64, f64); 3] = [(10.0, 5.0), (10.0, 0.0), (5.0, 10.0)];
+
+#[derive(Clone, Copy, Debug)]
+struct Config {
+    exploration_param: f64,
+    rave_equivalence: f64,
+    simulations: u32,
+    rollout: RolloutWeights,
+}
+
+impl Config {
+    fn defaults() -> Self {
+        let ai = MCTSAi::new();
+        Self {
+            exploration_param: ai.exploration_param,
+            rave_equivalence: ai.rave_equivalence,
+            simulations: ai.simulations,
+            rollout: ai.rollout,
+        }
+    }
+
+    fn build(self) -> MCTSAi {
+        let mut ai = MCTSAi::new();
+        ai.exploration_param = self.exploration_param;
+        ai.rave_equivalence = self.rave_equivalence;
+        ai.simulations = self.simulations;
+        ai.rollout = self.rollout;
+        // The grid already keeps every core busy
+        ai.threads = 1;
+        ai
+    }
+
+    fn grid() -> Vec<Self> {
+        let mut configs = Vec::new();
+        for exploration_param in EXPLORATION_PARAMS {
+            for rave_equivalence in RAVE_EQUIVALENCES {
+                for simulations in SIMULATIONS {
+                    for smart_chance in SMART_CHANCES {
+                        for (center, corner) in POSITION_WEIGHTS {
+                            configs.push(Config {
+                                exploration_param,
+                                rave_equivalence,
+                                simulations,
+                                rollout: RolloutWeights { smart_chance, center, corner },
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        configs
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Record {
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    // Time the configuration spent choosing its moves, for weighing strength against cost
+    thinking: Duration,
+    moves: u32,
+}
+
+impl Record {
+    // Draws count half
+    fn score(&self) -> f64 {
+        let games = self.wins + self.draws + self.losses;
+        (self.wins as f64 + self.draws as f64 / 2.0) / games.max(1) as f64
+    }
+}
+
+// One game between a configuration and the defaults, the configuration moving first if asked
+fn play(config: Config, config_first: bool, rules: Rules, record: &mut Record) {
+    let (candidate, baseline) = (config.build(), Config::defaults().build());
+    let mut game = GameState { rules, ..GameState::default() };
+    game.reset();
+
+    let candidate_seat = if config_first { Player::Human } else { Player::AI };
+    while !game.game_over {
+        let started = Instant::now();
+        let reply = if game.current_player == candidate_seat {
+            let reply = candidate.best_move(&game, None);
+            record.thinking += started.elapsed();
+            record.moves += 1;
+            reply
+        } else {
+            baseline.best_move(&game, None)
+        };
+        let Some((x, y, z)) = reply else {
+            break;
+        };
+        game.make_move(x, y, z);
+    }
+
+    match game.winner {
+        Some(winner) if winner == candidate_seat => record.wins += 1,
+        Some(_) => record.losses += 1,
+        None => record.draws += 1,
+    }
+}
+
+// `--tune`: play every configuration in the grid against the current `MCTSAi::new()` defaults,
+// alternating who moves first, and print the results strongest first. Runs without a window,
+// spread over every core
+pub fn run(games: u32, rules: Rules) {
+    let configs = Config::grid();
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    let workers = thread::available_parallelism().map_or(1, |count| count.get());
+    eprintln!(
+        "Tuning: {} configurations x {} games against the defaults, on {} threads",
+        configs.len(),
+        games,
+        workers
+    );
+
+    let started = Instant::now();
+    let mut results: Vec<(Config, Record)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    while let Some(&config) = configs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        let mut record = Record::default();
+                        for game in 0..games {
+                            play(config, game % 2 == 0, rules, &mut record);
+                        }
+                        done.push((config, record));
+                        let finished = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                        eprintln!("  {}/{} configurations", finished, configs.len());
+                    }
+                    done
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().expect("tuning thread panicked")).collect()
+    });
+    eprintln!("Done in {:.0?}", started.elapsed());
+
+    results.sort_by(|(_, a), (_, b)| b.score().total_cmp(&a.score()));
+    let defaults = Config::defaults();
+    println!(
+        "Score against the defaults (exploration {}, RAVE {}, {} simulations, smart chance {}, center {}, corner {}), draws counting half:\n",
+        defaults.exploration_param, defaults.rave_equivalence, defaults.simulations, defaults.rollout.smart_chance, defaults.rollout.center, defaults.rollout.corner
+    );
+    println!("{:>5}  {:>11}  {:>4}  {:>11}  {:>5}  {:>6}  {:>6}  {:>13}  {:>9}", "score", "exploration", "rave", "simulations", "smart", "center", "corner", "won/drew/lost", "ms/move");
+    for (config, record) in &results {
+        let ms_per_move = record.thinking.as_secs_f64() * 1000.0 / record.moves.max(1) as f64;
+        println!(
+            "{:>5.2}  {:>11}  {:>4}  {:>11}  {:>5}  {:>6}  {:>6}  {:>13}  {:>9.1}",
+            record.score(),
+            config.exploration_param,
+            config.rave_equivalence,
+            config.simulations,
+            config.rollout.smart_chance,
+            config.rollout.center,
+            config.rollout.corner,
+            format!("{}/{}/{}", record.wins, record.draws, record.losses),
+            ms_per_move
+        );
+    }
+}
@@ -0,0 +1,512 @@
+use argon2::Argon2;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key as CipherKey, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use crate::analysis::{AnalysisJob, CHECKPOINT_FILE};
+use crate::modal::{spawn_modal, swallow_input};
+use crate::settings::{Profile, SettingsPanel};
+use crate::stats::{PlayerStats, STATS_FILE};
+
+const LOCK_FILE: &str = "lock.ron";
+
+// A locked profile's records are kept under their usual name with this extension added
+const SEALED_EXTENSION: &str = "sealed";
+// The last good copy of a record, kept beside it and replaced each time the record is saved
+const BACKUP_EXTENSION: &str = "bak";
+// Where a damaged record is moved when the player starts over, in case it can be mended by hand
+const DAMAGED_EXTENSION: &str = "damaged";
+
+// Records start with a line holding a checksum of the rest, so a file cut short or scribbled on
+// is caught instead of read as something else. Files saved before checksums have no such line
+const CHECKSUM_PREFIX: &str = "// crc32 ";
+
+// Every file holding the player's records, sealed while the profile is locked. Settings stay
+// readable, since they're needed before the passphrase can be asked for
+const RECORD_FILES: [&str; 2] = [STATS_FILE, CHECKPOINT_FILE];
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const MIN_PASSPHRASE_LEN: usize = 4;
+
+// Sealed into the lock file, so a passphrase can be checked without any records to try it on
+const CHECK_TEXT: &[u8] = b"tictactoe-3d profile lock";
+
+// What a locked profile keeps in the clear: the key's salt, and the check text sealed with it
+#[derive(Clone, Serialize, Deserialize)]
+struct ProfileLock {
+    salt: Vec<u8>,
+    check: Vec<u8>,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Option<CipherKey> {
+    let mut key = CipherKey::default();
+    Argon2::default().hash_password_into(passphrase.as_bytes(), salt, &mut key).ok()?;
+    Some(key)
+}
+
+// A fresh nonce followed by the ciphertext, whose tag catches any edit to the file
+fn seal(key: &CipherKey, plaintext: &[u8]) -> Vec<u8> {
+    let mut nonce = [0; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = ChaCha20Poly1305::new(key)
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .expect("encrypting a record in memory cannot fail");
+    [&nonce[..], &ciphertext].concat()
+}
+
+fn unseal(key: &CipherKey, sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    ChaCha20Poly1305::new(key).decrypt(Nonce::from_slice(nonce), ciphertext).ok()
+}
+
+fn with_checksum(contents: &str) -> String {
+    format!("{}{:08x}\n{}", CHECKSUM_PREFIX, crc32fast::hash(contents.as_bytes()), contents)
+}
+
+// The record without its checksum line, or why it doesn't match
+fn verify_checksum(text: String) -> Result<String, String> {
+    let Some(rest) = text.strip_prefix(CHECKSUM_PREFIX) else {
+        return Ok(text);
+    };
+    let (checksum, contents) = rest.split_once('\n').ok_or("it was cut short")?;
+    match u32::from_str_radix(checksum, 16) {
+        Ok(checksum) if checksum == crc32fast::hash(contents.as_bytes()) => Ok(contents.to_string()),
+        _ => Err("its checksum doesn't match".to_string()),
+    }
+}
+
+// `path` with `extension` added after the one it has
+fn with_extension_added(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+// Write to a temporary file beside `path` and rename it into place, so a crash, or another copy
+// of the game saving to the same profile, leaves either the old file or the new one but never
+// part of each. The temporary file is named for this process so two copies don't share it
+pub fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = with_extension_added(path, &format!("{}.tmp", process::id()));
+    let result = fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+// Optional passphrase protection for a profile's records, for players sharing a computer.
+// Records are read and written through here so they are sealed whenever the profile is locked
+#[derive(Resource, Default)]
+pub struct ProfileVault {
+    lock: Option<ProfileLock>,
+    // Derived once the passphrase has been given this session
+    key: Option<CipherKey>,
+    // Records found damaged, with what was wrong. They aren't written over until the player
+    // has chosen what to do about them
+    damaged: Vec<(&'static str, String)>,
+}
+
+impl ProfileVault {
+    pub fn open(profile: &Profile) -> Self {
+        let path = profile.dir().join(LOCK_FILE);
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        // A damaged lock still keeps the records sealed; no passphrase will open them
+        let lock = ron::from_str(&contents).unwrap_or_else(|err| {
+            warn!("Profile lock {:?} is malformed, so the profile can't be unlocked: {}", path, err);
+            ProfileLock { salt: Vec::new(), check: Vec::new() }
+        });
+        Self { lock: Some(lock), ..default() }
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.lock.is_some()
+    }
+
+    // Protected, and the passphrase not given yet
+    pub fn is_locked(&self) -> bool {
+        self.lock.is_some() && self.key.is_none()
+    }
+
+    // A wrong passphrase leaves the key as it was, so checking one on an unlocked profile
+    // doesn't lock it again
+    fn unlock(&mut self, passphrase: &str) -> bool {
+        let Some(lock) = &self.lock else {
+            return true;
+        };
+        let key = derive_key(passphrase, &lock.salt).filter(|key| unseal(key, &lock.check).as_deref() == Some(CHECK_TEXT));
+        if key.is_some() {
+            self.key = key;
+        }
+        key.is_some()
+    }
+
+    fn record_path(&self, profile: &Profile, name: &str) -> PathBuf {
+        match self.lock {
+            Some(_) => profile.dir().join(format!("{}.{}", name, SEALED_EXTENSION)),
+            None => profile.dir().join(name),
+        }
+    }
+
+    // A record file's contents; none while the profile is locked, or if it's damaged
+    pub fn read(&self, profile: &Profile, name: &str) -> Option<String> {
+        let path = self.record_path(profile, name);
+        self.read_path(&path)
+            .map_err(|err| warn!("Ignoring {:?}: {}", path, err))
+            .ok()
+            .flatten()
+    }
+
+    // Like `read`, but a damaged record is an error saying what's wrong with it
+    pub fn read_checked(&self, profile: &Profile, name: &str) -> Result<Option<String>, String> {
+        self.read_path(&self.record_path(profile, name))
+    }
+
+    // The copy of a record from before its latest save
+    pub fn read_backup(&self, profile: &Profile, name: &str) -> Result<Option<String>, String> {
+        self.read_path(&with_extension_added(&self.record_path(profile, name), BACKUP_EXTENSION))
+    }
+
+    fn read_path(&self, path: &Path) -> Result<Option<String>, String> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.to_string()),
+        };
+        let plaintext = match (&self.lock, &self.key) {
+            (None, _) => bytes,
+            (Some(_), None) => return Ok(None),
+            (Some(_), Some(key)) => unseal(key, &bytes).ok_or("it doesn't open with this profile's passphrase, so it was damaged or edited")?,
+        };
+        let text = String::from_utf8(plaintext).map_err(|_| "it isn't text".to_string())?;
+        verify_checksum(text).map(Some)
+    }
+
+    // Saving a record first keeps the copy it replaces as the backup, as long as that copy is
+    // sound; a damaged one would only push out the last good backup
+    pub fn write(&self, profile: &Profile, name: &str, contents: &str) -> io::Result<()> {
+        if self.is_locked() {
+            return Err(io::Error::other("the profile is locked"));
+        }
+        if self.damage(name).is_some() {
+            return Err(io::Error::other("the saved copy is damaged and waiting on the player"));
+        }
+        fs::create_dir_all(profile.dir())?;
+        let path = self.record_path(profile, name);
+        if matches!(self.read_path(&path), Ok(Some(_))) {
+            write_atomically(&with_extension_added(&path, BACKUP_EXTENSION), &fs::read(&path)?)?;
+        }
+        let contents = with_checksum(contents);
+        match &self.key {
+            Some(key) => write_atomically(&path, &seal(key, contents.as_bytes())),
+            None => write_atomically(&path, contents.as_bytes()),
+        }
+    }
+
+    // Removes the record's backup along with it, so no copy is left behind in the clear when
+    // the profile is locked
+    pub fn remove(&self, profile: &Profile, name: &str) -> io::Result<()> {
+        let path = self.record_path(profile, name);
+        for path in [with_extension_added(&path, BACKUP_EXTENSION), path] {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    // What's wrong with a record found damaged, while the player hasn't dealt with it
+    pub fn damage(&self, name: &str) -> Option<&str> {
+        self.damaged.iter().find(|(damaged, _)| *damaged == name).map(|(_, damage)| damage.as_str())
+    }
+
+    pub fn mark_damaged(&mut self, name: &'static str, damage: String) {
+        self.damaged.retain(|(damaged, _)| *damaged != name);
+        self.damaged.push((name, damage));
+    }
+
+    // The player has dealt with the damage, so the record can be saved again. The damaged file
+    // is moved aside rather than written over, in case it can be mended by hand
+    pub fn clear_damage(&mut self, profile: &Profile, name: &str) -> io::Result<()> {
+        let path = self.record_path(profile, name);
+        match fs::rename(&path, with_extension_added(&path, DAMAGED_EXTENSION)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        self.damaged.retain(|(damaged, _)| *damaged != name);
+        Ok(())
+    }
+
+    // Switch between sealed and plain records, rewriting every record that exists. The new
+    // copies are written before the lock file changes and the old ones go after, so an
+    // interrupted switch leaves the records readable the way the lock file says
+    fn reseal(&mut self, profile: &Profile, lock: Option<ProfileLock>, key: Option<CipherKey>) -> io::Result<()> {
+        // A damaged record can't be read to rewrite, and would be lost with the old copies
+        if !self.damaged.is_empty() {
+            return Err(io::Error::other("a damaged record is waiting on the player"));
+        }
+        let records: Vec<(&str, String)> =
+            RECORD_FILES.iter().filter_map(|&name| Some((name, self.read(profile, name)?))).collect();
+        let resealed = Self { lock, key, damaged: Vec::new() };
+
+        for (name, contents) in &records {
+            resealed.write(profile, name, contents)?;
+        }
+        let lock_path = profile.dir().join(LOCK_FILE);
+        match &resealed.lock {
+            Some(lock) => {
+                let contents = ron::ser::to_string(lock).map_err(io::Error::other)?;
+                fs::write(lock_path, contents)?;
+            }
+            None => fs::remove_file(lock_path)?,
+        }
+        for (name, _) in &records {
+            self.remove(profile, name)?;
+        }
+        *self = resealed;
+        Ok(())
+    }
+
+    fn protect(&mut self, profile: &Profile, passphrase: &str) -> io::Result<()> {
+        let mut salt = vec![0; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = derive_key(passphrase, &salt).ok_or_else(|| io::Error::other("key derivation failed"))?;
+        let lock = ProfileLock { salt, check: seal(&key, CHECK_TEXT) };
+        self.reseal(profile, Some(lock), Some(key))
+    }
+
+    fn unprotect(&mut self, profile: &Profile) -> io::Result<()> {
+        self.reseal(profile, None, None)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PromptStage {
+    Unlock,
+    Choose,
+    // The new passphrase typed again, to catch a typo before it locks the player out
+    Confirm,
+    // The current passphrase, before the lock comes off, so no one else at the computer can
+    // take it off
+    Remove,
+}
+
+// The passphrase being typed, shown masked in a box over the board
+#[derive(Resource, Default)]
+pub struct PassphrasePrompt {
+    stage: Option<PromptStage>,
+    input: String,
+    chosen: String,
+    message: String,
+}
+
+impl PassphrasePrompt {
+    // A locked profile asks for its passphrase as soon as the game opens
+    pub fn for_vault(vault: &ProfileVault) -> Self {
+        Self {
+            stage: vault.is_locked().then_some(PromptStage::Unlock),
+            ..default()
+        }
+    }
+
+    fn open(&mut self, stage: PromptStage, message: &str) {
+        self.stage = Some(stage);
+        self.input.clear();
+        self.message = message.to_string();
+    }
+
+    fn close(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[derive(Component)]
+pub struct PassphrasePromptRoot;
+
+#[derive(Component)]
+pub struct PassphrasePromptText;
+
+#[derive(Component)]
+pub struct ProfileLockLabel;
+
+pub fn profile_lock_label(vault: &ProfileVault) -> String {
+    if vault.is_locked() {
+        "Profile lock: Locked, records not loaded [8 to unlock]".to_string()
+    } else {
+        format!("Profile lock: {} [8]", if vault.is_protected() { "On" } else { "Off" })
+    }
+}
+
+pub fn setup_passphrase_prompt(mut commands: Commands) {
+    spawn_modal(&mut commands, PassphrasePromptRoot, PassphrasePromptText, 640.0);
+}
+
+// 8 in the settings panel locks the profile, asking for a new passphrase, or takes the lock
+// off again once its passphrase is given; a profile still locked this session asks for its
+// passphrase instead
+pub fn toggle_profile_lock(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    panel: Res<SettingsPanel>,
+    vault: Res<ProfileVault>,
+    mut prompt: ResMut<PassphrasePrompt>,
+) {
+    if !panel.open || !keyboard.just_pressed(KeyCode::Digit8) {
+        return;
+    }
+
+    if vault.is_locked() {
+        prompt.open(PromptStage::Unlock, "");
+    } else if !vault.is_protected() {
+        prompt.open(PromptStage::Choose, "");
+    } else {
+        prompt.open(PromptStage::Remove, "");
+    }
+}
+
+// While the prompt is up it takes every key, and clicks don't reach the board behind it.
+// Runs straight after input is read, so nothing else sees what was typed
+#[allow(clippy::too_many_arguments)]
+pub fn type_passphrase(
+    mut events: EventReader<KeyboardInput>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    profile: Res<Profile>,
+    mut prompt: ResMut<PassphrasePrompt>,
+    mut vault: ResMut<ProfileVault>,
+    mut stats: ResMut<PlayerStats>,
+    mut job: ResMut<AnalysisJob>,
+) {
+    // Read every frame, so the key that opened the prompt isn't typed into it
+    let keys: Vec<Key> = events
+        .read()
+        .filter(|event| event.state == ButtonState::Pressed)
+        .map(|event| event.logical_key.clone())
+        .collect();
+    let Some(stage) = prompt.stage else {
+        return;
+    };
+    swallow_input(&mut keyboard, &mut mouse);
+
+    for key in keys {
+        match key {
+            Key::Character(text) => prompt.input.push_str(&text),
+            Key::Space => prompt.input.push(' '),
+            Key::Backspace => {
+                prompt.input.pop();
+            }
+            Key::Escape => {
+                prompt.close();
+                return;
+            }
+            Key::Enter => {
+                submit_passphrase(stage, &profile, &mut prompt, &mut vault, &mut stats, &mut job);
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn submit_passphrase(
+    stage: PromptStage,
+    profile: &Profile,
+    prompt: &mut PassphrasePrompt,
+    vault: &mut ProfileVault,
+    stats: &mut PlayerStats,
+    job: &mut AnalysisJob,
+) {
+    let input = std::mem::take(&mut prompt.input);
+    match stage {
+        PromptStage::Unlock if vault.unlock(&input) => {
+            *stats = PlayerStats::load(profile, vault);
+            // An analysis started while locked had nowhere to save, so keep it running
+            if job.checkpoint.is_none() {
+                *job = AnalysisJob::resume(profile, vault);
+            }
+            prompt.close();
+        }
+        PromptStage::Unlock => {
+            prompt.message = "Wrong passphrase - try again, or Esc to play without your records".to_string();
+        }
+        PromptStage::Choose if input.chars().count() < MIN_PASSPHRASE_LEN => {
+            prompt.message = format!("Use at least {} characters", MIN_PASSPHRASE_LEN);
+        }
+        PromptStage::Choose => {
+            prompt.chosen = input;
+            prompt.open(PromptStage::Confirm, "");
+        }
+        PromptStage::Confirm if input != prompt.chosen => {
+            prompt.open(PromptStage::Choose, "The passphrases didn't match - choose one again");
+        }
+        PromptStage::Confirm => match vault.protect(profile, &input) {
+            Ok(()) => prompt.close(),
+            Err(err) => {
+                warn!("Failed to lock profile '{}': {}", profile.name, err);
+                prompt.open(PromptStage::Choose, "Couldn't lock the profile - see the log");
+            }
+        },
+        PromptStage::Remove if !vault.unlock(&input) => {
+            prompt.message = "Wrong passphrase - try again, or Esc to keep the lock".to_string();
+        }
+        PromptStage::Remove => match vault.unprotect(profile) {
+            Ok(()) => prompt.close(),
+            Err(err) => {
+                warn!("Failed to remove the lock from profile '{}': {}", profile.name, err);
+                prompt.message = "Couldn't remove the lock - see the log".to_string();
+            }
+        },
+    }
+}
+
+pub fn update_passphrase_prompt(
+    prompt: Res<PassphrasePrompt>,
+    vault: Res<ProfileVault>,
+    profile: Res<Profile>,
+    mut root_query: Query<&mut Visibility, With<PassphrasePromptRoot>>,
+    mut text_query: Query<&mut Text, (With<PassphrasePromptText>, Without<ProfileLockLabel>)>,
+    mut label_query: Query<&mut Text, With<ProfileLockLabel>>,
+) {
+    if vault.is_changed() {
+        for mut text in label_query.iter_mut() {
+            text.sections[0].value = profile_lock_label(&vault);
+        }
+    }
+    if !prompt.is_changed() {
+        return;
+    }
+
+    for mut visibility in root_query.iter_mut() {
+        *visibility = if prompt.stage.is_some() { Visibility::Visible } else { Visibility::Hidden };
+    }
+    let Some(stage) = prompt.stage else {
+        return;
+    };
+    let title = match stage {
+        PromptStage::Unlock => format!("Profile '{}' is locked. Enter its passphrase to load your records", profile.name),
+        PromptStage::Choose => "Choose a passphrase to lock this profile's records".to_string(),
+        PromptStage::Confirm => "Type the passphrase again".to_string(),
+        PromptStage::Remove => format!("Enter the passphrase of profile '{}' to take its lock off", profile.name),
+    };
+    let masked = "*".repeat(prompt.input.chars().count());
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!("{}\n\n{}_\n\n{}\nEnter to confirm, Esc to cancel", title, masked, prompt.message);
+    }
+}
@@ -0,0 +1,260 @@
+use bevy::app::{AppExit, ScheduleRunnerPlugin};
+use bevy::prelude::*;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+use image::{Rgba, RgbaImage};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use ttt3d_core::game::{GameState, Position};
+use crate::capture::{CaptureDone, CapturePlugin, CaptureRequest};
+use crate::graphics::{setup_scene, sync_pieces, update_cube_materials, CameraController};
+use crate::lighting::{apply_light_theme, check_light_placement, light_placement, LightTheme};
+use crate::settings::{Profile, Settings};
+use crate::slice::LayerSlice;
+use crate::threat_overlay::ThreatOverlay;
+use crate::xray::XRayHover;
+
+// Stored renders the scenes are compared against, and where failed comparisons are written
+const REFERENCE_DIR: &str = "tests/visual";
+const OUTPUT_DIR: &str = "target/visual";
+
+const RENDER_SIZE: UVec2 = UVec2::new(640, 480);
+
+// Frames for materials and lighting to settle after a scene is set up, before it is captured
+const SETTLE_FRAMES: u32 = 5;
+
+// A pixel differs if any channel is off by more than this, and a render fails if more than this
+// share of its pixels differ. Loose enough for different GPUs and drivers, tight enough to catch
+// a wrong material, a moved cube or a light on the other side of the board
+const CHANNEL_TOLERANCE: u8 = 12;
+const MAX_DIFFERING_SHARE: f64 = 0.01;
+
+// Games sampled per lighting theme when checking the variation stays in bounds
+const LIGHTING_SAMPLES: u64 = 500;
+
+// The fixed look every scene is rendered with
+const THEME: LightTheme = LightTheme::Noon;
+const LIGHT_SALT: u64 = 0;
+
+// An angled view that shows all three layers
+const CAMERA_POSITION: Vec3 = Vec3::new(6.0, 5.0, 7.0);
+
+struct Scene {
+    name: &'static str,
+    // Moves from the start, the human first
+    moves: &'static [Position],
+}
+
+const SCENES: [Scene; 3] = [
+    Scene { name: "empty", moves: &[] },
+    Scene { name: "midgame", moves: &[(1, 1, 1), (0, 0, 0), (2, 0, 1), (0, 2, 2), (1, 2, 0), (2, 2, 2)] },
+    // The human completes the bottom row of the front layer
+    Scene { name: "win", moves: &[(0, 0, 0), (1, 1, 1), (1, 0, 0), (2, 2, 2), (2, 0, 0)] },
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stage {
+    // Waiting this many more frames before asking for the capture
+    Settling(u32),
+    Capturing,
+}
+
+#[derive(Resource)]
+struct VisualCheck {
+    // Overwrite the references with this build's renders instead of comparing
+    bless: bool,
+    scene: usize,
+    stage: Stage,
+    failures: Vec<String>,
+}
+
+// `--visual-check`: render each canonical scene offscreen with the game's own scene setup,
+// materials and lighting, and compare it with the stored reference. Also checks that every
+// lighting theme's per-game variation stays within its bounds. Exits non-zero on any failure.
+// Missing references, or all of them with `--visual-bless`, are written from this build's renders
+pub fn run(bless: bool) -> AppExit {
+    let settings = Settings {
+        light_theme: THEME,
+        light_salt: LIGHT_SALT,
+        ..default()
+    };
+    App::new()
+        // No window or display: everything is drawn to the offscreen capture target, so this
+        // runs on a CI machine or over SSH
+        .add_plugins((
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                })
+                .disable::<WinitPlugin>(),
+            ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / 60.0)),
+        ))
+        .add_plugins(CapturePlugin)
+        .insert_resource(settings)
+        .insert_resource(Profile::default())
+        .init_resource::<GameState>()
+        .init_resource::<LayerSlice>()
+        .init_resource::<ThreatOverlay>()
+        .init_resource::<XRayHover>()
+        .insert_resource(VisualCheck {
+            bless,
+            scene: 0,
+            stage: Stage::Settling(SETTLE_FRAMES),
+            failures: check_lighting(),
+        })
+        .add_systems(Startup, setup_scene)
+        .add_systems(Update, (set_up_scene, sync_pieces, apply_light_theme, update_cube_materials, step_visual_check, compare_captures).chain())
+        .run()
+}
+
+fn report(failures: &[String]) -> AppExit {
+    if failures.is_empty() {
+        println!("Visual check passed");
+        return AppExit::Success;
+    }
+    for failure in failures {
+        eprintln!("FAILED: {}", failure);
+    }
+    AppExit::error()
+}
+
+fn check_lighting() -> Vec<String> {
+    let mut failures = Vec::new();
+    for theme in [LightTheme::Dawn, LightTheme::Noon, LightTheme::Dusk, LightTheme::Night] {
+        for generation in 0..LIGHTING_SAMPLES {
+            let placement = light_placement(theme, LIGHT_SALT, generation);
+            if let Err(err) = check_light_placement(theme, &placement) {
+                failures.push(format!("lighting {} game {}: {}", theme.name(), generation, err));
+                break;
+            }
+        }
+    }
+    failures
+}
+
+// Put the board in the current scene's position and point the camera at it
+fn set_up_scene(
+    check: Res<VisualCheck>,
+    mut game_state: ResMut<GameState>,
+    mut camera_query: Query<&mut Transform, With<CameraController>>,
+) {
+    if check.stage != Stage::Settling(SETTLE_FRAMES) {
+        return;
+    }
+    let Some(scene) = SCENES.get(check.scene) else {
+        return;
+    };
+
+    *game_state = GameState::default();
+    for &(x, y, z) in scene.moves {
+        game_state.make_move(x, y, z);
+    }
+    for mut transform in camera_query.iter_mut() {
+        *transform = Transform::from_translation(CAMERA_POSITION).looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn step_visual_check(mut check: ResMut<VisualCheck>, mut requests: EventWriter<CaptureRequest>, mut exit: EventWriter<AppExit>) {
+    let Some(scene) = SCENES.get(check.scene) else {
+        let failures = std::mem::take(&mut check.failures);
+        exit.send(report(&failures));
+        return;
+    };
+
+    match check.stage {
+        Stage::Settling(0) => {
+            requests.send(CaptureRequest {
+                size: RENDER_SIZE,
+                label: Some(scene.name.to_string()),
+            });
+            check.stage = Stage::Capturing;
+        }
+        Stage::Settling(frames) => check.stage = Stage::Settling(frames - 1),
+        Stage::Capturing => {}
+    }
+}
+
+fn compare_captures(mut check: ResMut<VisualCheck>, mut done: EventReader<CaptureDone>) {
+    for capture in done.read() {
+        let Some(scene) = SCENES.get(check.scene) else {
+            return;
+        };
+        if capture.label.as_deref() != Some(scene.name) {
+            continue;
+        }
+
+        let result = capture
+            .image
+            .clone()
+            .try_into_dynamic()
+            .map_err(|err| format!("{}: the render could not be read: {:?}", scene.name, err))
+            .and_then(|render| compare_with_reference(scene.name, &render.to_rgba8(), check.bless));
+        if let Err(err) = result {
+            check.failures.push(err);
+        }
+        check.scene += 1;
+        check.stage = Stage::Settling(SETTLE_FRAMES);
+    }
+}
+
+fn compare_with_reference(name: &str, render: &RgbaImage, bless: bool) -> Result<(), String> {
+    let reference_path = Path::new(REFERENCE_DIR).join(format!("{}.png", name));
+    if bless || !reference_path.exists() {
+        save(render, &reference_path)?;
+        println!("{}: reference written to {:?}", name, reference_path);
+        return Ok(());
+    }
+
+    let reference = image::open(&reference_path)
+        .map_err(|err| format!("{}: cannot read {:?}: {}", name, reference_path, err))?
+        .to_rgba8();
+    if reference.dimensions() != render.dimensions() {
+        return Err(format!(
+            "{}: rendered at {:?} but the reference is {:?}",
+            name,
+            render.dimensions(),
+            reference.dimensions()
+        ));
+    }
+
+    // Differing pixels are marked red on a faded copy of the render
+    let mut diff = RgbaImage::new(render.width(), render.height());
+    let mut differing = 0;
+    for (x, y, pixel) in render.enumerate_pixels() {
+        let expected = reference.get_pixel(x, y);
+        let differs = pixel.0.iter().zip(expected.0).any(|(&a, b)| a.abs_diff(b) > CHANNEL_TOLERANCE);
+        if differs {
+            differing += 1;
+            diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        } else {
+            diff.put_pixel(x, y, Rgba(pixel.0.map(|channel| channel / 4)));
+        }
+    }
+
+    let share = differing as f64 / (render.width() * render.height()) as f64;
+    if share <= MAX_DIFFERING_SHARE {
+        println!("{}: ok ({:.2}% of pixels differ)", name, share * 100.0);
+        return Ok(());
+    }
+
+    let output = PathBuf::from(OUTPUT_DIR);
+    save(render, &output.join(format!("{}.png", name)))?;
+    save(&diff, &output.join(format!("{}.diff.png", name)))?;
+    Err(format!(
+        "{}: {:.2}% of pixels differ from the reference (at most {:.2}% allowed); see {:?}",
+        name,
+        share * 100.0,
+        MAX_DIFFERING_SHARE * 100.0,
+        output
+    ))
+}
+
+fn save(image: &RgbaImage, path: &Path) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| format!("cannot create {:?}: {}", dir, err))?;
+    }
+    image.save(path).map_err(|err| format!("cannot write {:?}: {}", path, err))
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use ttt3d_core::game::{GameState, Position};
+use crate::graphics::{cell_position, CubeMaterials};
+
+// Share of an empty cube's opacity left to one the cursor is looking through
+pub const XRAY_ALPHA: f32 = 0.25;
+const XRAY_PIECE_ALPHA: f32 = 0.5;
+// The same size as a peeled cell's outline
+const XRAY_OUTLINE_SIZE: f32 = 0.7;
+
+// X-ray hover: Alt moves the target one cube further along the cursor ray, past the ones in
+// front of it and back to the nearest after the last, and the cubes in front of the target turn
+// see-through. Moving the cursor onto a different set of cubes starts again from the nearest
+#[derive(Resource, Default)]
+pub struct XRayHover {
+    // The pickable cubes under the cursor, nearest first, and which of them is targeted
+    pub along: Vec<Entity>,
+    pub depth: usize,
+    // Every cell between the camera and the target while a farther cube is targeted
+    pub occluders: Vec<Position>,
+}
+
+impl XRayHover {
+    pub fn occludes(&self, position: Position) -> bool {
+        self.occluders.contains(&position)
+    }
+
+    // Take the cubes now under the cursor, and step further along them if Alt was pressed
+    pub fn follow(&mut self, along: Vec<Entity>, step: bool) -> Option<Entity> {
+        if self.along != along {
+            self.along = along;
+            self.depth = 0;
+        }
+        if step && !self.along.is_empty() {
+            self.depth = (self.depth + 1) % self.along.len();
+        }
+        self.along.get(self.depth).copied()
+    }
+
+    pub fn clear(&mut self) {
+        if !self.along.is_empty() || !self.occluders.is_empty() {
+            *self = XRayHover::default();
+        }
+    }
+}
+
+// Pieces can't be made see-through one at a time, as each player's share a material, so those
+// in the way are hidden along with peeled ones and outlined here in their own color instead
+pub fn draw_xray_occluders(
+    xray: Res<XRayHover>,
+    game_state: Res<GameState>,
+    cube_materials: Res<CubeMaterials>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut gizmos: Gizmos,
+) {
+    for &(x, y, z) in &xray.occluders {
+        let color = cube_materials
+            .piece(game_state.board[x][y][z])
+            .and_then(|handle| materials.get(&handle))
+            .map(|material| material.base_color.with_alpha(XRAY_PIECE_ALPHA));
+        if let Some(color) = color {
+            gizmos.cuboid(Transform::from_translation(cell_position(x, y, z)).with_scale(Vec3::splat(XRAY_OUTLINE_SIZE)), color);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Pits engine configurations against each other and against the solver, to check an AI change
+//! before merging it:
+//!
+//! ```text
+//! cargo bench -p ttt3d-core --bench engines [-- --games <n>]
+//! ```
+//!
+//! Every configuration plays every other `--games` times (default 10), alternating who moves
+//! first, from openings of two random moves. It then answers a set of fixed positions whose
+//! right moves the solver knows. Engines and openings are seeded, so two runs on one machine
+//! differ only where a search runs out of time.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use ttt3d_core::ai::{Engine, EngineKind};
+use ttt3d_core::game::{CellState, GameState, Player, Position};
+use ttt3d_core::notation::{has_line, parse_board, Board};
+use ttt3d_core::solver::refutation;
+use ttt3d_core::threats::threats;
+use ttt3d_core::Difficulty;
+
+const DEFAULT_GAMES: u32 = 10;
+const OPENING_MOVES: usize = 2;
+
+// Engine and difficulty pairs under test
+const CONFIGS: [(EngineKind, Difficulty); 5] = [
+    (EngineKind::Mcts, Difficulty::Normal),
+    (EngineKind::Mcts, Difficulty::Hard),
+    (EngineKind::MctsStrict, Difficulty::Normal),
+    (EngineKind::AlphaBeta, Difficulty::Normal),
+    (EngineKind::AlphaBeta, Difficulty::Hard),
+];
+
+// What the human, to move, has to find in a fixed position
+#[derive(Clone, Copy)]
+enum Task {
+    // A move that keeps a forced win within this many of the human's moves
+    Win(u32),
+    // A move after which the AI can't complete a line
+    Hold,
+}
+
+// Positions from the puzzles and the difficulty quiz, in the same notation: three layers (z) of
+// three rows (y) of three cells (x)
+const POSITIONS: [(&str, Task, [[&str; 3]; 3]); 7] = [
+    ("win in one", Task::Win(1), [["HH.", "...", "..."], ["...", "...", "..A"], ["...", "...", "A.."]]),
+    ("block", Task::Hold, [["H..", "...", "AA."], ["...", "...", "..."], ["...", "...", ".H."]]),
+    ("fork", Task::Win(2), [["H..", ".A.", "..."], ["...", "...", "..."], ["...", "..A", ".H."]]),
+    ("crossed wires", Task::Win(2), [["A..", "H..", ".A."], ["...", "..H", "H.."], ["...", "...", "A.."]]),
+    ("back corner", Task::Win(2), [["...", "..A", ".AH"], ["..H", "...", "..."], ["..A", ".H.", "..."]]),
+    ("lower deck", Task::Win(2), [["A..", ".A.", "..H"], [".A.", "...", "..."], [".H.", "..H", "..."]]),
+    ("three step", Task::Win(3), [[".A.", "...", "..."], [".H.", "..H", ".A."], ["...", ".H.", "A.."]]),
+];
+
+fn config_name((kind, difficulty): (EngineKind, Difficulty)) -> String {
+    format!("{} {}", kind.name(), difficulty.name())
+}
+
+fn build((kind, difficulty): (EngineKind, Difficulty), seed: u64) -> Arc<dyn Engine> {
+    kind.build(difficulty, None, None, Some(seed))
+}
+
+#[derive(Default)]
+struct Record {
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    // Search work and the time it took, over moves the engine actually searched
+    nodes: u64,
+    thinking: Duration,
+}
+
+impl Record {
+    fn score(&self) -> f64 {
+        let games = self.wins + self.draws + self.losses;
+        (self.wins as f64 + self.draws as f64 / 2.0) / games.max(1) as f64
+    }
+
+    fn nodes_per_second(&self) -> f64 {
+        self.nodes as f64 / self.thinking.as_secs_f64().max(1e-9)
+    }
+}
+
+// Ask `engine` for a move, counting its search towards `record`. Moves it found without
+// searching, such as an immediate win, leave the previous search's statistics in place
+fn think(engine: &dyn Engine, game: &GameState, record: &mut Record) -> Option<Position> {
+    let started = Instant::now();
+    let reply = engine.best_move(game, None);
+    if let Some(stats) = engine.search_stats().filter(|stats| stats.board == game.board) {
+        record.nodes += stats.nodes;
+        record.thinking += started.elapsed();
+    }
+    reply
+}
+
+// One game from a random opening; returns the winner, if any
+fn play(first: &dyn Engine, second: &dyn Engine, records: [&mut Record; 2], rng: &mut StdRng) -> Option<Player> {
+    let [first_record, second_record] = records;
+    let mut game = GameState::default();
+    game.reset();
+
+    for _ in 0..OPENING_MOVES {
+        let moves = game.get_empty_positions();
+        let (x, y, z) = moves[rng.gen_range(0..moves.len())];
+        game.make_move(x, y, z);
+    }
+    while !game.game_over {
+        let reply = match game.current_player {
+            Player::Human => think(first, &game, first_record),
+            _ => think(second, &game, second_record),
+        };
+        let Some((x, y, z)) = reply else {
+            break;
+        };
+        game.make_move(x, y, z);
+    }
+    game.winner
+}
+
+// Whether the move is one the solver accepts for the task
+fn solves(board: &Board, task: Task, (x, y, z): Position) -> bool {
+    let mut after = *board;
+    after[x][y][z] = CellState::Human;
+    match task {
+        Task::Win(_) if has_line(&after, CellState::Human) => true,
+        Task::Win(moves) => moves > 1 && refutation(&mut after, moves - 1).is_none(),
+        Task::Hold => threats(&after, Player::AI).is_empty(),
+    }
+}
+
+fn main() {
+    // `cargo bench` passes `--bench`, which needs no handling here
+    let mut args = std::env::args().skip(1);
+    let mut games = DEFAULT_GAMES;
+    while let Some(arg) = args.next() {
+        if arg == "--games" {
+            games = args.next().and_then(|value| value.parse().ok()).filter(|&games| games > 0).unwrap_or_else(|| {
+                eprintln!("error: --games needs a positive number");
+                std::process::exit(2);
+            });
+        }
+    }
+
+    let mut records: Vec<Record> = CONFIGS.iter().map(|_| Record::default()).collect();
+    let mut pairings = Vec::new();
+    for a in 0..CONFIGS.len() {
+        for b in a + 1..CONFIGS.len() {
+            pairings.push((a, b));
+        }
+    }
+
+    println!("Head to head, {} games a pairing:\n", games);
+    for &(a, b) in &pairings {
+        let (mut a_wins, mut draws, mut b_wins) = (0, 0, 0);
+        for game in 0..games {
+            let seed = game as u64;
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (engine_a, engine_b) = (build(CONFIGS[a], seed), build(CONFIGS[b], seed));
+            let (before, from_b) = records.split_at_mut(b);
+            let (record_a, record_b) = (&mut before[a], &mut from_b[0]);
+            // `a` takes the first seat in even games
+            let winner = if game % 2 == 0 {
+                play(&*engine_a, &*engine_b, [record_a, record_b], &mut rng)
+            } else {
+                play(&*engine_b, &*engine_a, [record_b, record_a], &mut rng).map(|winner| winner.next(2))
+            };
+            match winner {
+                Some(Player::Human) => a_wins += 1,
+                Some(_) => b_wins += 1,
+                None => draws += 1,
+            }
+        }
+        records[a].wins += a_wins;
+        records[a].draws += draws;
+        records[a].losses += b_wins;
+        records[b].wins += b_wins;
+        records[b].draws += draws;
+        records[b].losses += a_wins;
+        println!("  {:<24} {:>3}-{:<3} {:>3} drawn  {}", config_name(CONFIGS[a]), a_wins, b_wins, draws, config_name(CONFIGS[b]));
+    }
+
+    println!("\nFixed positions, checked against the solver:\n");
+    let mut misses = Vec::new();
+    let mut solved = vec![0; CONFIGS.len()];
+    for (name, task, layers) in POSITIONS {
+        let layers: Vec<Vec<String>> = layers.iter().map(|rows| rows.iter().map(|row| row.to_string()).collect()).collect();
+        let board = parse_board(&layers).expect("bench positions are well formed");
+        for (index, &config) in CONFIGS.iter().enumerate() {
+            let mut game = GameState::default();
+            game.reset();
+            game.load_position(board, Player::Human);
+            let reply = think(&*build(config, 0), &game, &mut records[index]);
+            match reply.filter(|&position| solves(&board, task, position)) {
+                Some(_) => solved[index] += 1,
+                None => misses.push(format!("{} played {:?} in '{}'", config_name(config), reply, name)),
+            }
+        }
+    }
+
+    println!("{:<24}  {:>5}  {:>13}  {:>9}  {:>10}", "configuration", "score", "won/drew/lost", "solved", "nodes/s");
+    for (index, &config) in CONFIGS.iter().enumerate() {
+        let record = &records[index];
+        println!(
+            "{:<24}  {:>5.2}  {:>13}  {:>9}  {:>10.0}",
+            config_name(config),
+            record.score(),
+            format!("{}/{}/{}", record.wins, record.draws, record.losses),
+            format!("{}/{}", solved[index], POSITIONS.len()),
+            record.nodes_per_second()
+        );
+    }
+
+    if misses.is_empty() {
+        println!("\nEvery configuration found every right move");
+    } else {
+        println!("\nMissed moves:");
+        for miss in &misses {
+            println!("  {}", miss);
+        }
+    }
+}
@@ -0,0 +1,1336 @@
+//! The [`Engine`] trait every AI implements, the Monte Carlo tree search engine, and
+//! [`EngineKind`] for building an engine at a [`Difficulty`]
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::alphabeta::AlphaBetaAi;
+use crate::difficulty::Difficulty;
+use crate::game::{completed_lines, place_piece, GameState, Player, CellState, Placements, Position, Rules, SCORING_MOVE_LIMIT};
+use crate::network::{Evaluator, NetworkOutput};
+use crate::symmetry::{canonical, PositionKey, Symmetry};
+use crate::threats::{fork_block, forks};
+
+// Rollouts under the fading rule can cycle forever, so they're scored as a draw past this length
+const MAX_ROLLOUT_PLIES: usize = 64;
+
+// Fewest simulations worth a tree of their own: split any finer and each tree is too shallow
+// to tell the moves apart
+const MIN_WORKER_SIMULATIONS: u32 = 2000;
+
+// Iterations between updates of the statistics a running search shows
+const STATS_INTERVAL: u32 = 250;
+// Simulations each search thread does between adding them to the shared progress count
+const PROGRESS_INTERVAL: u32 = 64;
+
+// Search depth under a think time: every cell filled, so time is the only limit
+const MAX_SEARCH_DEPTH: u32 = 27;
+
+// Line-scoring margins are squashed into (-1, 1) so they weigh like wins and losses in the tree;
+// a lead of this many lines counts as roughly three quarters of a win
+const SCORE_MARGIN_SCALE: f64 = 3.0;
+
+// With a network, how strongly its move priors pull the search towards a move, and how much
+// its value counts against the rollout's result at a new leaf
+const PRIOR_WEIGHT: f64 = 1.0;
+const NETWORK_VALUE_WEIGHT: f64 = 0.5;
+
+// Default RAVE equivalence parameter; see `MCTSAi::rave_equivalence`. Against plain UCT it won
+// 56% of self-play games at 2000 simulations and 65% under line scoring at 300, and on random
+// positions with a forced win in three it found the win more often at 100, 300 and 1000
+const RAVE_EQUIVALENCE: f64 = 300.0;
+
+// Pondering searches in slices of this many iterations, letting go of the tree in between, and
+// stops once the tree holds this many positions
+const PONDER_SLICE: u32 = 100;
+const MAX_PONDER_NODES: usize = 200_000;
+
+// A pass is spent for good, so passing has to look clearly better than playing on
+const PASS_MARGIN: f64 = 0.1;
+
+// When the AI lapses (see `MCTSAi::blunder_rate`), a move this far below the best one's win
+// rate is taken about a third as often as the best, and one twice as far about a seventh
+const BLUNDER_TOLERANCE: f64 = 0.1;
+// Lapses only look this far down the AI's own ranking
+const BLUNDER_CHOICES: usize = 3;
+
+// A network sees only whose pieces are where, so it is only asked about plain two-player games;
+// Notakto, fading and line scoring are left to the rollouts
+fn network_applies(rules: &Rules) -> bool {
+    rules.player_count() == 2 && !rules.notakto && !rules.fading && !rules.line_scoring
+}
+
+/// Everything a search needs to know about a position, including the rule-specific bookkeeping
+#[derive(Clone, PartialEq)]
+pub struct SearchState {
+    /// The cells, indexed `board[x][y][z]`
+    pub board: [[[CellState; 3]; 3]; 3],
+    /// Each player's pieces in the order they were placed, for the fading rule
+    pub placements: Placements,
+    /// Lines completed by each player under line scoring (indexed by `Player::index`)
+    pub scores: [u32; 3],
+    /// Moves played so far, for line scoring's move limit
+    pub moves_made: usize,
+    /// The player whose turn it is
+    pub to_move: Player,
+}
+
+impl SearchState {
+    /// The position a game stands at
+    pub fn from_game(game_state: &GameState) -> Self {
+        Self {
+            board: game_state.board,
+            placements: game_state.placements.clone(),
+            scores: game_state.scores,
+            moves_made: game_state.move_history.len(),
+            to_move: game_state.current_player,
+        }
+    }
+
+    /// Play a move for whoever is to move; returns each player's reward if that ended the game
+    pub fn play(&mut self, position: Position, rules: &Rules) -> Option<[f64; 3]> {
+        let mover = self.to_move;
+        place_piece(&mut self.board, &mut self.placements, rules, mover, position);
+        self.moves_made += 1;
+        self.to_move = mover.next(rules.player_count());
+
+        let lines = completed_lines(&self.board, position);
+        let board_full = MCTSAi::get_possible_moves_for_state(&self.board).is_empty();
+        if rules.line_scoring {
+            self.scores[rules.line_winner(mover).index()] += lines;
+            (board_full || self.moves_made >= SCORING_MOVE_LIMIT).then(|| score_rewards(&self.scores, rules))
+        } else if lines > 0 {
+            Some(win_rewards(Some(rules.line_winner(mover))))
+        } else {
+            board_full.then(|| win_rewards(None))
+        }
+    }
+}
+
+// 1 for the winner and -1 for everyone else, or 0 all round for a draw
+fn win_rewards(winner: Option<Player>) -> [f64; 3] {
+    let mut rewards = [0.0; 3];
+    if let Some(winner) = winner {
+        for player in [Player::Human, Player::AI, Player::Third] {
+            rewards[player.index()] = if player == winner { 1.0 } else { -1.0 };
+        }
+    }
+    rewards
+}
+
+// Each player's margin in lines over their best opponent
+fn score_rewards(scores: &[u32; 3], rules: &Rules) -> [f64; 3] {
+    let players = &[Player::Human, Player::AI, Player::Third][..rules.player_count()];
+    let mut rewards = [0.0; 3];
+    for &player in players {
+        let best_opponent = players
+            .iter()
+            .filter(|&&other| other != player)
+            .map(|other| scores[other.index()])
+            .max()
+            .unwrap_or(0);
+        let margin = scores[player.index()] as f64 - best_opponent as f64;
+        rewards[player.index()] = (margin / SCORE_MARGIN_SCALE).tanh();
+    }
+    rewards
+}
+
+/// One position in the search tree, with the results of the simulations through it
+pub struct MCTSNode {
+    /// The position, as first reached
+    pub state: SearchState,
+    /// Moves tried from this position, each with the arena index of the node it leads to. Symmetric
+    /// positions share a node, stored as first reached, so moves are in this node's orientation.
+    /// The symmetry takes the position after the move to its canonical form
+    pub children: Vec<(Position, usize, Symmetry)>,
+    /// Moves from this position that have no child yet
+    pub untried: Vec<Position>,
+    /// Simulations that passed through this node
+    pub visits: u32,
+    /// Sum of rollout rewards per player (indexed by `Player::index`)
+    pub total_rewards: [f64; 3],
+    /// Set when the game is over at this node
+    pub outcome: Option<[f64; 3]>,
+    /// The network's view of this position, once it has been asked
+    pub evaluation: Option<NetworkOutput>,
+    /// Takes this node's state to its canonical form
+    pub frame: Symmetry,
+    /// All-moves-as-first statistics for RAVE: each move the player to move here went on to play
+    /// anywhere later in an iteration through this node, with its visits and the total reward
+    /// it earned that player
+    pub amaf: Vec<(Position, u32, f64)>,
+}
+
+impl MCTSNode {
+    fn new(state: SearchState, outcome: Option<[f64; 3]>, frame: Symmetry) -> Self {
+        let untried = if outcome.is_some() {
+            Vec::new()
+        } else {
+            MCTSAi::get_possible_moves_for_state(&state.board)
+        };
+        Self {
+            state,
+            children: Vec::new(),
+            untried,
+            visits: 0,
+            total_rewards: [0.0; 3],
+            outcome,
+            evaluation: None,
+            frame,
+            amaf: Vec::new(),
+        }
+    }
+
+    fn mean_reward(&self, player: Player) -> f64 {
+        self.total_rewards[player.index()] / self.visits.max(1) as f64
+    }
+
+    // The mean AMAF reward of `position` for the player to move here, if it has been played
+    fn amaf_reward(&self, position: Position) -> Option<f64> {
+        self.amaf
+            .iter()
+            .find(|&&(played, _, _)| played == position)
+            .map(|&(_, visits, total)| total / visits as f64)
+    }
+
+    fn record_amaf(&mut self, position: Position, reward: f64) {
+        match self.amaf.iter_mut().find(|(played, _, _)| *played == position) {
+            Some((_, visits, total)) => {
+                *visits += 1;
+                *total += reward;
+            }
+            None => self.amaf.push((position, 1, reward)),
+        }
+    }
+}
+
+/// The search tree, kept between moves so the subtree for the position actually reached
+/// carries its statistics into the next search. Positions are looked up by their canonical
+/// key, so transpositions and rotations or reflections of a position pool their statistics
+pub struct SearchTree {
+    nodes: Vec<MCTSNode>,
+    // Transposition table: canonical key to arena index
+    index: HashMap<PositionKey, usize>,
+    root: usize,
+    // Maps the root node's moves onto the board actually being searched, which may be a
+    // rotation or reflection of the position the node was stored as
+    root_view: (Symmetry, Symmetry),
+    rules: Rules,
+}
+
+impl SearchTree {
+    fn new(state: SearchState, rules: Rules) -> Self {
+        let (key, symmetry) = canonical(&state);
+        Self {
+            nodes: vec![MCTSNode::new(state, None, symmetry)],
+            index: HashMap::from([(key, 0)]),
+            root: 0,
+            root_view: (symmetry, symmetry),
+            rules,
+        }
+    }
+
+    // Move the root to `state`, if a position equivalent to it has already been searched
+    fn reroot(&mut self, state: &SearchState, rules: &Rules) -> bool {
+        if self.rules != *rules {
+            return false;
+        }
+
+        let (key, view) = canonical(state);
+        let Some(&node) = self.index.get(&key) else {
+            return false;
+        };
+        self.root = node;
+        self.root_view = (canonical(&self.nodes[node].state).1, view);
+        true
+    }
+
+    // UCT: the child with the best upper confidence bound for the player choosing at `node`,
+    // plus a bonus for moves the network likes that fades as they are visited. With RAVE, a
+    // move's reward starts out as its AMAF reward and hands over to its own as visits build up;
+    // `rave_equivalence` is about the visit count at which the two weigh the same
+    fn select_child(&self, node: usize, exploration_param: f64, rave_equivalence: f64) -> (Position, usize, Symmetry) {
+        let parent = &self.nodes[node];
+        let chooser = parent.state.to_move;
+        let log_visits = (parent.visits.max(1) as f64).ln();
+        let sqrt_visits = (parent.visits as f64).sqrt();
+
+        let uct = |&(position, child, _): &(Position, usize, Symmetry)| {
+            let child = &self.nodes[child];
+            let mut reward = child.mean_reward(chooser);
+            if let Some(amaf) = parent.amaf_reward(position).filter(|_| rave_equivalence > 0.0) {
+                let beta = (rave_equivalence / (3.0 * child.visits as f64 + rave_equivalence)).sqrt();
+                reward = (1.0 - beta) * reward + beta * amaf;
+            }
+            // Rewards are in [-1, 1]; UCB1 expects [0, 1]
+            let exploitation = (reward + 1.0) / 2.0;
+            let prior = parent.evaluation.as_ref().map_or(0.0, |evaluation| {
+                PRIOR_WEIGHT * evaluation.prior(position) * sqrt_visits / (1.0 + child.visits as f64)
+            });
+            exploitation + exploration_param * (log_visits / child.visits.max(1) as f64).sqrt() + prior
+        };
+
+        parent
+            .children
+            .iter()
+            .copied()
+            .max_by(|a, b| uct(a).total_cmp(&uct(b)))
+            .expect("select_child needs an expanded node")
+    }
+
+    // Ask the network about `node` if there is one and it hasn't been asked yet; returns its
+    // value for the side to move there
+    fn evaluate(&mut self, node: usize, network: Option<&dyn Evaluator>) -> Option<f64> {
+        let network = network.filter(|_| network_applies(&self.rules))?;
+        let node = &mut self.nodes[node];
+        if node.evaluation.is_none() {
+            node.evaluation = network.evaluate(&node.state);
+        }
+        node.evaluation.as_ref().map(|evaluation| evaluation.value)
+    }
+
+    // Play one untried move from `node`, returning the new child: the network's favorite if it
+    // has looked at the position, otherwise one at random. A move that leads to the same node as
+    // a sibling is symmetric to it, and is dropped rather than searched twice
+    fn expand(&mut self, node: usize, rng: &mut impl Rng) -> Option<(Position, usize, Symmetry)> {
+        while !self.nodes[node].untried.is_empty() {
+            let untried = &self.nodes[node].untried;
+            let pick = match &self.nodes[node].evaluation {
+                Some(evaluation) => (0..untried.len())
+                    .max_by(|&a, &b| evaluation.prior(untried[a]).total_cmp(&evaluation.prior(untried[b])))
+                    .unwrap_or(0),
+                None => rng.gen_range(0..untried.len()),
+            };
+            let position = self.nodes[node].untried.swap_remove(pick);
+            let mut state = self.nodes[node].state.clone();
+            let outcome = state.play(position, &self.rules);
+
+            let (key, symmetry) = canonical(&state);
+            let child = match self.index.get(&key) {
+                Some(&existing) => existing,
+                None => {
+                    self.nodes.push(MCTSNode::new(state, outcome, symmetry));
+                    self.index.insert(key, self.nodes.len() - 1);
+                    self.nodes.len() - 1
+                }
+            };
+            if self.nodes[node].children.iter().any(|&(_, existing, _)| existing == child) {
+                continue;
+            }
+            self.nodes[node].children.push((position, child, symmetry));
+            return Some((position, child, symmetry));
+        }
+        None
+    }
+
+    // One iteration: select down the tree, expand one new move, roll out, and back the result up
+    fn iterate(&mut self, ai: &MCTSAi, rng: &mut impl Rng) {
+        let mut path = vec![self.root];
+        // The move taken out of each node on the path but the last
+        let mut edges = Vec::new();
+        let mut node = self.root;
+        let network = ai.network.as_deref();
+
+        loop {
+            if self.nodes[node].outcome.is_some() {
+                break;
+            }
+            // Priors for choosing among this node's moves
+            self.evaluate(node, network);
+
+            if let Some(edge) = self.expand(node, rng) {
+                node = edge.1;
+                path.push(node);
+                edges.push(edge);
+                break;
+            }
+
+            if self.nodes[node].children.is_empty() {
+                break;
+            }
+            let edge = self.select_child(node, ai.exploration_param, ai.rave_equivalence);
+            node = edge.1;
+            path.push(node);
+            edges.push(edge);
+        }
+
+        // The rollout's moves are only needed for RAVE
+        let mut played = Vec::new();
+        let rave = ai.rave_equivalence > 0.0;
+        let rewards = match self.nodes[node].outcome {
+            Some(outcome) => outcome,
+            None => {
+                let state = self.nodes[node].state.clone();
+                let mut rewards = ai.play_out(state, self.rules, rng, rave.then_some(&mut played));
+                // Blend in the network's judgement of the leaf, which only covers two players
+                if let Some(value) = self.evaluate(node, network) {
+                    let mover = self.nodes[node].state.to_move;
+                    for player in [mover, mover.next(2)] {
+                        let judged = if player == mover { value } else { -value };
+                        let reward = &mut rewards[player.index()];
+                        *reward = (1.0 - NETWORK_VALUE_WEIGHT) * *reward + NETWORK_VALUE_WEIGHT * judged;
+                    }
+                }
+                rewards
+            }
+        };
+
+        for &visited in &path {
+            let node = &mut self.nodes[visited];
+            node.visits += 1;
+            for (total, reward) in node.total_rewards.iter_mut().zip(rewards) {
+                *total += reward;
+            }
+        }
+        if rave {
+            self.update_amaf(&path, &edges, played, rewards);
+        }
+    }
+
+    // Credit every move of the iteration but the rollout's forced ones, at each node on the
+    // path, to the node's player if they were the first to play on that cell from there. Working
+    // up from the leaf, `played` holds the moves after the node in hand, in that node's orientation
+    fn update_amaf(&mut self, path: &[usize], edges: &[(Position, usize, Symmetry)], mut played: Vec<(Player, Position)>, rewards: [f64; 3]) {
+        for (depth, &node) in path.iter().enumerate().rev() {
+            if let Some(&(position, child, view)) = edges.get(depth) {
+                let frame = self.nodes[child].frame;
+                for (_, played_at) in played.iter_mut() {
+                    *played_at = view.invert(frame.apply(*played_at));
+                }
+                played.insert(0, (self.nodes[node].state.to_move, position));
+            }
+
+            let node = &mut self.nodes[node];
+            let chooser = node.state.to_move;
+            let mut taken = [[[false; 3]; 3]; 3];
+            for &(mover, (x, y, z)) in &played {
+                if std::mem::replace(&mut taken[x][y][z], true) {
+                    continue;
+                }
+                if mover == chooser {
+                    node.record_amaf((x, y, z), rewards[chooser.index()]);
+                }
+            }
+        }
+    }
+
+    // Each move searched from the root, mapped onto the board, with its visits and the total
+    // reward it earned the player to move
+    fn root_moves(&self) -> impl Iterator<Item = (Position, u32, f64)> + '_ {
+        let root = &self.nodes[self.root];
+        let me = root.state.to_move;
+        let (stored, view) = self.root_view;
+        root.children.iter().map(move |&(position, child, _)| {
+            let child = &self.nodes[child];
+            (view.invert(stored.apply(position)), child.visits, child.total_rewards[me.index()])
+        })
+    }
+}
+
+// Root moves of trees searched side by side from the same position, their statistics added
+// together. Ordered by position, so a seeded search breaks ties the same way each time
+fn merged_root_moves(trees: &[SearchTree]) -> Vec<MoveStats> {
+    let mut moves: BTreeMap<Position, (u32, f64)> = BTreeMap::new();
+    for (position, visits, reward) in trees.iter().flat_map(SearchTree::root_moves) {
+        let merged = moves.entry(position).or_default();
+        merged.0 += visits;
+        merged.1 += reward;
+    }
+    moves
+        .into_iter()
+        .filter(|&(_, (visits, _))| visits > 0)
+        .map(|(position, (visits, reward))| MoveStats {
+            position,
+            visits,
+            win_rate: (reward / visits as f64 + 1.0) / 2.0,
+        })
+        .collect()
+}
+
+// The most visited move, ties going to the better average
+fn most_visited(moves: &[MoveStats]) -> Option<Position> {
+    moves
+        .iter()
+        .max_by(|a, b| a.visits.cmp(&b.visits).then(a.win_rate.total_cmp(&b.win_rate)))
+        .map(|stats| stats.position)
+}
+
+/// How a search rated one move from the position it searched
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveStats {
+    /// The move's cell
+    pub position: Position,
+    /// Simulations that started with this move
+    pub visits: u32,
+    /// Average result for the player to move, from 0 (every rollout lost) to 1 (every one won),
+    /// a draw counting as half
+    pub win_rate: f64,
+}
+
+/// What the engine considered on its latest search, for showing to the player
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchStats {
+    /// The board that was searched, so statistics for an earlier position can be told apart
+    pub board: [[[CellState; 3]; 3]; 3],
+    /// Empty for an engine that doesn't rate each move
+    pub moves: Vec<MoveStats>,
+    /// Work done so far: MCTS iterations, or positions alpha-beta visited
+    pub nodes: u64,
+    /// How the chosen move looks for the searching side, from 0 (lost) to 1 (won), when the
+    /// engine can tell
+    pub win_rate: Option<f64>,
+}
+
+/// A move-choosing engine; `GameState.ai` holds whichever one the settings pick
+pub trait Engine: Send + Sync {
+    /// Search stops early once the optional deadline passes (used by the turn clock)
+    fn best_move(&self, game_state: &GameState, deadline: Option<Instant>) -> Option<Position>;
+
+    /// Pie rule: whether to take over the opening move rather than reply to it
+    fn should_swap(&self, game_state: &GameState) -> bool;
+
+    /// Pass variant: whether to pass this turn rather than place a piece
+    fn should_pass(&self, game_state: &GameState) -> bool;
+
+    /// Think during an opponent's turn, so the engine's reply is ready sooner; runs until `stop`
+    /// is set or there is nothing more worth doing
+    fn ponder(&self, game_state: &GameState, stop: &AtomicBool);
+
+    /// Statistics from the latest search, updated as it runs, if the engine keeps any
+    fn search_stats(&self) -> Option<SearchStats>;
+
+    /// How much the move search in progress, or the last one, has done so far across all its
+    /// threads: simulations for MCTS, positions for alpha-beta. Safe to read while it runs
+    fn search_progress(&self) -> u64;
+}
+
+/// Each player's reward if the game ended where it stands, as it does once everyone passes
+pub fn rewards_on_passing(game_state: &GameState) -> [f64; 3] {
+    if game_state.rules.line_scoring {
+        score_rewards(&game_state.scores, &game_state.rules)
+    } else {
+        win_rewards(None)
+    }
+}
+
+/// The search algorithms the AI can use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EngineKind {
+    /// Monte Carlo tree search, with the immediate win/block shortcuts
+    #[default]
+    Mcts,
+    /// MCTS without the immediate win/block shortcuts, to see what the search finds on its own
+    MctsStrict,
+    /// Iterative-deepening alpha-beta; see [`AlphaBetaAi`]
+    AlphaBeta,
+}
+
+impl EngineKind {
+    /// Every engine, in the order the settings panel cycles through them. Front ends list and
+    /// select engines from here, so a new one only needs adding to this list and `build`
+    pub const ALL: [EngineKind; 3] = [EngineKind::Mcts, EngineKind::MctsStrict, EngineKind::AlphaBeta];
+
+    /// Name shown to the player
+    pub fn name(self) -> &'static str {
+        match self {
+            EngineKind::Mcts => "MCTS",
+            EngineKind::MctsStrict => "MCTS (strict)",
+            EngineKind::AlphaBeta => "Alpha-beta",
+        }
+    }
+
+    /// Short name for command lines
+    pub fn id(self) -> &'static str {
+        match self {
+            EngineKind::Mcts => "mcts",
+            EngineKind::MctsStrict => "strict",
+            EngineKind::AlphaBeta => "alphabeta",
+        }
+    }
+
+    /// The engine with the given short name, as taken by `id`
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|engine| engine.id() == id)
+    }
+
+    /// Every engine's id, as "mcts|strict|alphabeta", for usage and error messages
+    pub fn ids() -> String {
+        Self::ALL.map(EngineKind::id).join("|")
+    }
+
+    /// The engine after this one in `ALL`, wrapping back to the first
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&engine| engine == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// The engine at the given strength: rollouts for MCTS, search depth for alpha-beta. With a
+    /// think time, either one searches for that long instead, however far it gets. A network,
+    /// if given, steers MCTS; alpha-beta has no use for one. A seed makes MCTS play the same
+    /// moves from the same positions; alpha-beta is deterministic already
+    pub fn build(
+        self,
+        difficulty: Difficulty,
+        think_time: Option<Duration>,
+        network: Option<Arc<dyn Evaluator>>,
+        seed: Option<u64>,
+    ) -> Arc<dyn Engine> {
+        self.build_with_budget(difficulty.simulations(), difficulty.search_depth(), difficulty.blunder_rate(), think_time, network, seed)
+    }
+
+    /// `build` with the search budget and blunder rate given directly rather than by a
+    /// difficulty, for strengths in between the difficulties. Only MCTS blunders, as alpha-beta
+    /// doesn't rate each of its moves
+    pub fn build_with_budget(
+        self,
+        simulations: u32,
+        search_depth: u32,
+        blunder_rate: f64,
+        think_time: Option<Duration>,
+        network: Option<Arc<dyn Evaluator>>,
+        seed: Option<u64>,
+    ) -> Arc<dyn Engine> {
+        match self {
+            EngineKind::Mcts | EngineKind::MctsStrict => {
+                let mut ai = MCTSAi::new();
+                ai.simulations = simulations;
+                ai.strict = self == EngineKind::MctsStrict;
+                ai.time_budget = think_time;
+                ai.network = network;
+                ai.seed = seed;
+                ai.blunder_rate = blunder_rate;
+                Arc::new(ai)
+            }
+            EngineKind::AlphaBeta => {
+                let mut ai = AlphaBetaAi::new();
+                ai.max_depth = search_depth;
+                if let Some(think_time) = think_time {
+                    ai.max_depth = MAX_SEARCH_DEPTH;
+                    ai.time_budget = think_time;
+                }
+                Arc::new(ai)
+            }
+        }
+    }
+}
+
+/// How rollouts pick their moves once no line needs winning or blocking
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RolloutWeights {
+    /// Chance of a heuristic move rather than a random one
+    pub smart_chance: f64,
+    /// Score for the center, falling off with distance from it
+    pub center: f64,
+    /// Extra score for the eight corners
+    pub corner: f64,
+}
+
+impl Default for RolloutWeights {
+    fn default() -> Self {
+        Self {
+            smart_chance: 0.7,
+            center: 10.0,
+            corner: 5.0,
+        }
+    }
+}
+
+/// Monte Carlo tree search with RAVE, optionally steered by a value/policy network
+pub struct MCTSAi {
+    /// Simulations per search when there is no time budget
+    pub simulations: u32,
+    /// UCT's exploration constant: higher tries less-visited moves more often
+    pub exploration_param: f64,
+    /// RAVE's equivalence parameter: roughly how many visits a move needs before its own reward
+    /// counts as much as its all-moves-as-first one. 0 turns RAVE off
+    pub rave_equivalence: f64,
+    /// How rollouts pick their moves
+    pub rollout: RolloutWeights,
+    /// Skip the checks for an immediate win or a move that must be blocked, leaving every
+    /// move to the search
+    pub strict: bool,
+    /// Search until this much time has passed, however many simulations that takes, rather
+    /// than for a fixed number of simulations
+    pub time_budget: Option<Duration>,
+    /// Value/policy network steering the search; without one it runs on rollouts alone
+    pub network: Option<Arc<dyn Evaluator>>,
+    /// Makes every search repeatable: the same position, searched from the same tree, gets the
+    /// same move, as long as it runs for a simulation count rather than a think time, on the same
+    /// number of threads. Unseeded searches draw fresh randomness each time
+    pub seed: Option<u64>,
+    /// Most trees to search side by side, one per thread, merging their root moves at the end.
+    /// Each one gets at least `MIN_WORKER_SIMULATIONS`, so small searches stay on one thread
+    pub threads: usize,
+    /// Chance that a searched move of the AI's own is a lapse, taken from its best few rather
+    /// than being the best; see `lapse`. 0 always plays the best
+    pub blunder_rate: f64,
+    // Tree from the previous search, reused when the game continues from it
+    tree: Mutex<Option<SearchTree>>,
+    // Root moves of the search in progress, or of the last one
+    stats: Mutex<Option<SearchStats>>,
+    // Simulations of the search in progress, or of the last one
+    progress: AtomicU64,
+}
+
+impl MCTSAi {
+    /// A Normal-strength engine with the default search settings
+    pub fn new() -> Self {
+        Self {
+            simulations: 2000, // Increased for better play
+            exploration_param: 1.414, // sqrt(2)
+            rave_equivalence: RAVE_EQUIVALENCE,
+            rollout: RolloutWeights::default(),
+            strict: false,
+            time_budget: None,
+            network: None,
+            seed: None,
+            threads: thread::available_parallelism().map_or(1, |count| count.get()),
+            blunder_rate: 0.0,
+            tree: Mutex::new(None),
+            stats: Mutex::new(None),
+            progress: AtomicU64::new(0),
+        }
+    }
+
+    /// Randomness for one search from `state`. With a seed, the position is mixed in so each
+    /// move of a game draws its own stream, yet replaying the game replays every stream
+    pub fn search_rng(&self, state: &SearchState) -> StdRng {
+        let Some(seed) = self.seed else {
+            return StdRng::from_entropy();
+        };
+        let position = state.board.iter().flatten().flatten().fold(state.to_move.index() as u64, |hash, &cell| {
+            hash.wrapping_mul(0x100_0000_01B3) ^ cell as u64
+        });
+        StdRng::seed_from_u64(seed ^ position.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+
+    fn publish_stats(&self, board: [[[CellState; 3]; 3]; 3], moves: Vec<MoveStats>, nodes: u64) {
+        let chosen = most_visited(&moves);
+        let win_rate = moves.iter().find(|stats| Some(stats.position) == chosen).map(|stats| stats.win_rate);
+        *self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(SearchStats { board, moves, nodes, win_rate });
+    }
+
+    // A lapse, as a weaker player makes: with `blunder_rate`, draw from the most searched moves,
+    // each weighted by how far its win rate falls short of the best one's. A move nearly as good
+    // is often taken, and a losing one hardly ever. `None` plays the best move as usual. Wins,
+    // blocks and forks are found before any search, so a lapse never misses one of those
+    fn lapse(&self, moves: &[MoveStats], rng: &mut impl Rng) -> Option<Position> {
+        if self.blunder_rate <= 0.0 || !rng.gen_bool(self.blunder_rate.min(1.0)) {
+            return None;
+        }
+
+        let mut ranked = moves.to_vec();
+        ranked.sort_by(|a, b| b.visits.cmp(&a.visits).then(b.win_rate.total_cmp(&a.win_rate)));
+        ranked.truncate(BLUNDER_CHOICES);
+        let best = ranked.first()?.win_rate;
+        let weights: Vec<f64> = ranked.iter().map(|stats| (-(best - stats.win_rate).max(0.0) / BLUNDER_TOLERANCE).exp()).collect();
+        let mut pick = rng.gen::<f64>() * weights.iter().sum::<f64>();
+        for (stats, weight) in ranked.iter().zip(&weights) {
+            if pick < *weight {
+                return Some(stats.position);
+            }
+            pick -= weight;
+        }
+        None
+    }
+
+    // Randomness for one of the extra trees searched alongside the main one, each worker
+    // drawing a stream of its own
+    fn worker_rng(&self, state: &SearchState, worker: usize) -> StdRng {
+        match self.seed {
+            Some(_) => StdRng::seed_from_u64(self.search_rng(state).gen::<u64>().wrapping_add(worker as u64)),
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
+impl Default for MCTSAi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for MCTSAi {
+    fn best_move(&self, game_state: &GameState, deadline: Option<Instant>) -> Option<Position> {
+        self.progress.store(0, Ordering::Relaxed);
+        if game_state.game_over {
+            return None;
+        }
+
+        let empty_positions = game_state.get_empty_positions();
+        if empty_positions.is_empty() {
+            return None;
+        }
+
+        // The engine plays whichever AI-controlled seat is to move
+        let me = game_state.current_player;
+        let rules = game_state.rules;
+        let player_count = rules.player_count();
+
+        // Under Notakto completing a line loses and under line scoring it doesn't end the game,
+        // so the win/block shortcuts don't apply and the search weighs those moves instead
+        if !self.strict && !rules.notakto && !rules.line_scoring {
+            // First, check if AI can win immediately
+            if let Some(winning_move) = self.find_winning_move(game_state, me) {
+                return Some(winning_move);
+            }
+
+            // Second, block opponents from winning, starting with whoever moves next
+            let mut opponent = me.next(player_count);
+            while opponent != me {
+                if let Some(blocking_move) = self.find_winning_move(game_state, opponent) {
+                    return Some(blocking_move);
+                }
+                opponent = opponent.next(player_count);
+            }
+
+            // Then forks, which win by force between two players: one reply stops only one of
+            // the two threats. Fading can take a threatening piece away, so it's left to the search
+            if player_count == 2 && !rules.fading {
+                let opponent = me.next(player_count);
+                if let Some(&fork) = forks(&game_state.board, me).first() {
+                    return Some(fork);
+                }
+                if let Some(block) = fork_block(&game_state.board, me, opponent) {
+                    return Some(block);
+                }
+            }
+        }
+
+        // Carry over the part of the last search that this position was reached through
+        let state = SearchState::from_game(game_state);
+        let mut rng = self.search_rng(&state);
+        let mut saved = self.tree.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut tree = saved
+            .take()
+            .and_then(|mut tree| tree.reroot(&state, &rules).then_some(tree))
+            .unwrap_or_else(|| SearchTree::new(state.clone(), rules));
+
+        // The turn clock's deadline and the think time both bound the search; without a
+        // think time it runs for its simulation count
+        let budget = self.time_budget.map(|budget| Instant::now() + budget);
+        let deadline = match (deadline, budget) {
+            (Some(deadline), Some(budget)) => Some(deadline.min(budget)),
+            (deadline, budget) => deadline.or(budget),
+        };
+        // Only the main tree reports its progress as it goes; the merged statistics follow at the end
+        let board = state.board;
+        let search = |tree: &mut SearchTree, rng: &mut StdRng, target: u32, live: bool| {
+            let mut iterations = 0;
+            while budget.is_some() || tree.nodes[tree.root].visits < target {
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    break;
+                }
+                tree.iterate(self, rng);
+                iterations += 1;
+                if iterations % PROGRESS_INTERVAL == 0 {
+                    self.progress.fetch_add(PROGRESS_INTERVAL as u64, Ordering::Relaxed);
+                }
+                if live && iterations % STATS_INTERVAL == 0 {
+                    self.publish_stats(board, merged_root_moves(std::slice::from_ref(tree)), iterations as u64);
+                }
+            }
+            self.progress.fetch_add((iterations % PROGRESS_INTERVAL) as u64, Ordering::Relaxed);
+        };
+
+        // Without a think time, visits already made from this position, say while pondering,
+        // count towards the simulations; the rest are shared out between fresh trees on other
+        // threads. With one, every thread searches until it runs out
+        let searched = tree.nodes[tree.root].visits;
+        let remaining = self.simulations.saturating_sub(searched);
+        let workers = match budget {
+            Some(_) => self.threads,
+            None => (remaining / MIN_WORKER_SIMULATIONS) as usize,
+        }
+        .clamp(1, self.threads.max(1));
+        let share = remaining / workers as u32;
+        let mut trees = thread::scope(|scope| {
+            let search = &search;
+            let handles: Vec<_> = (1..workers)
+                .map(|worker| {
+                    let state = state.clone();
+                    scope.spawn(move || {
+                        let mut rng = self.worker_rng(&state, worker);
+                        let mut tree = SearchTree::new(state, rules);
+                        search(&mut tree, &mut rng, share, false);
+                        tree
+                    })
+                })
+                .collect();
+            search(&mut tree, &mut rng, searched + remaining - share * (workers as u32 - 1), true);
+            handles.into_iter().map(|handle| handle.join().expect("search thread panicked")).collect::<Vec<_>>()
+        });
+        trees.insert(0, tree);
+
+        // Without any search (or before the first iteration finishes), fall back on the
+        // position heuristics, which don't apply to Notakto
+        let moves = merged_root_moves(&trees);
+        let best_move = most_visited(&moves).or_else(|| {
+            empty_positions.iter().copied().max_by(|&(ax, ay, az), &(bx, by, bz)| {
+                let value = |x, y, z| if rules.notakto { 0.0 } else { self.evaluate_position(x, y, z, game_state) };
+                value(ax, ay, az).total_cmp(&value(bx, by, bz))
+            })
+        });
+
+        // Only the AI's own moves lapse; a hint or a move played for the human is the best found
+        let best_move = match game_state.is_ai_turn() {
+            true => self.lapse(&moves, &mut rng).or(best_move),
+            false => best_move,
+        };
+
+        // Visits carried over from an earlier search weren't this search's work
+        let nodes = trees.iter().map(|tree| tree.nodes[tree.root].visits as u64).sum::<u64>();
+        self.publish_stats(board, moves, nodes.saturating_sub(searched as u64));
+        // Only the main tree is kept for the next search
+        *saved = trees.into_iter().next();
+        best_move
+    }
+
+    // Compare playing on against taking over the opening piece, using rollouts from both positions
+    fn should_swap(&self, game_state: &GameState) -> bool {
+        let Some(&(x, y, z)) = game_state.move_history.first() else {
+            return false;
+        };
+
+        let me = game_state.current_player;
+        let rollouts = (self.simulations / 4).max(1);
+        let mut rng = self.search_rng(&SearchState::from_game(game_state));
+        let mut average_value = |board: [[[CellState; 3]; 3]; 3], placements: &Placements, to_move: Player| {
+            let state = SearchState {
+                board,
+                placements: placements.clone(),
+                to_move,
+                ..SearchState::from_game(game_state)
+            };
+            let total: f64 = (0..rollouts)
+                .map(|_| self.simulate_smart_game(state.clone(), game_state.rules, &mut rng)[me.index()])
+                .sum();
+            total / rollouts as f64
+        };
+
+        // Declining: the opener keeps their piece and the AI replies
+        let decline_value = average_value(game_state.board, &game_state.placements, me);
+
+        // Swapping: the piece becomes the AI's and the opener moves next
+        let mut swapped_board = game_state.board;
+        swapped_board[x][y][z] = me.cell();
+        let mut swapped_placements = game_state.placements.clone();
+        if game_state.rules.fading {
+            swapped_placements[me.next(2).index()].retain(|&position| position != (x, y, z));
+            swapped_placements[me.index()].push_back((x, y, z));
+        }
+        let swap_value = average_value(swapped_board, &swapped_placements, me.next(2));
+
+        swap_value > decline_value
+    }
+
+    // Compare passing against playing on, by rollouts from both positions. Rollouts never pass,
+    // so this mostly finds the turns where any piece would hurt (Notakto, fading, a line handed
+    // over), and games a pass would end on a better result than playing on is likely to get
+    fn should_pass(&self, game_state: &GameState) -> bool {
+        if !game_state.can_pass() {
+            return false;
+        }
+
+        let me = game_state.current_player;
+        let rules = game_state.rules;
+        if !rules.notakto && !rules.line_scoring && self.find_winning_move(game_state, me).is_some() {
+            return false;
+        }
+
+        let rollouts = (self.simulations / 4).max(1);
+        let state = SearchState::from_game(game_state);
+        let mut rng = self.search_rng(&state);
+        let mut average_value = |state: &SearchState| {
+            let total: f64 = (0..rollouts)
+                .map(|_| self.simulate_smart_game(state.clone(), rules, &mut rng)[me.index()])
+                .sum();
+            total / rollouts as f64
+        };
+
+        let play_value = average_value(&state);
+        let pass_value = if game_state.pass_ends_game() {
+            rewards_on_passing(game_state)[me.index()]
+        } else {
+            average_value(&SearchState {
+                to_move: me.next(rules.player_count()),
+                ..state.clone()
+            })
+        };
+        pass_value > play_value + PASS_MARGIN
+    }
+
+    // Search the opponent's position, which spreads over their likely replies, into the tree
+    // `best_move` reroots onto the move they actually play. The tree is only held a slice at a
+    // time, so a hint or the engine's own search never waits long for it. A seeded engine
+    // doesn't ponder, since how far it got would depend on how long the opponent took
+    fn ponder(&self, game_state: &GameState, stop: &AtomicBool) {
+        if self.seed.is_some() || game_state.game_over {
+            return;
+        }
+
+        let state = SearchState::from_game(game_state);
+        let rules = game_state.rules;
+        let mut rng = self.search_rng(&state);
+        while !stop.load(Ordering::Relaxed) {
+            let mut saved = self.tree.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let mut tree = saved
+                .take()
+                .and_then(|mut tree| tree.reroot(&state, &rules).then_some(tree))
+                .unwrap_or_else(|| SearchTree::new(state.clone(), rules));
+            for _ in 0..PONDER_SLICE {
+                if stop.load(Ordering::Relaxed) || tree.nodes[tree.root].outcome.is_some() {
+                    break;
+                }
+                tree.iterate(self, &mut rng);
+            }
+            let full = tree.nodes.len() >= MAX_PONDER_NODES || tree.nodes[tree.root].outcome.is_some();
+            *saved = Some(tree);
+            if full {
+                return;
+            }
+        }
+    }
+
+    fn search_stats(&self) -> Option<SearchStats> {
+        self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    fn search_progress(&self) -> u64 {
+        self.progress.load(Ordering::Relaxed)
+    }
+}
+
+impl MCTSAi {
+
+    // Find if a player can win on their next move
+    fn find_winning_move(&self, game_state: &GameState, player: Player) -> Option<(usize, usize, usize)> {
+        let empty_positions = game_state.get_empty_positions();
+        
+        for &(x, y, z) in &empty_positions {
+            // Place through the rules so a fading piece that would come off is accounted for
+            let mut test_state = game_state.board;
+            let mut placements = game_state.placements.clone();
+            place_piece(&mut test_state, &mut placements, &game_state.rules, player, (x, y, z));
+            
+            if MCTSAi::check_winner_for_state(&test_state).is_some() {
+                return Some((x, y, z));
+            }
+        }
+        
+        None
+    }
+
+    // Evaluate strategic value of a position
+    fn evaluate_position(&self, x: usize, y: usize, z: usize, game_state: &GameState) -> f64 {
+        let mut score = 0.0;
+        
+        // Center positions are more valuable
+        let center_distance = ((x as f64 - 1.0).abs() + (y as f64 - 1.0).abs() + (z as f64 - 1.0).abs()) / 3.0;
+        score += (1.0 - center_distance) * 0.1;
+        
+        // Corner positions have strategic value
+        if (x == 0 || x == 2) && (y == 0 || y == 2) && (z == 0 || z == 2) {
+            score += 0.05;
+        }
+        
+        // Count potential winning lines through this position
+        score += self.count_potential_lines(x, y, z, game_state) * 0.02;
+        
+        score
+    }
+
+    // Count how many winning lines pass through this position
+    fn count_potential_lines(&self, x: usize, y: usize, z: usize, game_state: &GameState) -> f64 {
+        let mut count = 0.0;
+        
+        // All possible lines through position (x, y, z)
+        let lines = [
+            // X-axis lines
+            [(0, y, z), (1, y, z), (2, y, z)],
+            // Y-axis lines  
+            [(x, 0, z), (x, 1, z), (x, 2, z)],
+            // Z-axis lines
+            [(x, y, 0), (x, y, 1), (x, y, 2)],
+        ];
+        
+        // Add diagonal lines if applicable
+        let mut diagonal_lines = Vec::new();
+        
+        // XY plane diagonals
+        if x == y {
+            diagonal_lines.push([(0, 0, z), (1, 1, z), (2, 2, z)]);
+        }
+        if x + y == 2 {
+            diagonal_lines.push([(0, 2, z), (1, 1, z), (2, 0, z)]);
+        }
+        
+        // XZ plane diagonals
+        if x == z {
+            diagonal_lines.push([(0, y, 0), (1, y, 1), (2, y, 2)]);
+        }
+        if x + z == 2 {
+            diagonal_lines.push([(0, y, 2), (1, y, 1), (2, y, 0)]);
+        }
+        
+        // YZ plane diagonals
+        if y == z {
+            diagonal_lines.push([(x, 0, 0), (x, 1, 1), (x, 2, 2)]);
+        }
+        if y + z == 2 {
+            diagonal_lines.push([(x, 0, 2), (x, 1, 1), (x, 2, 0)]);
+        }
+        
+        // 3D space diagonals
+        if x == y && y == z {
+            diagonal_lines.push([(0, 0, 0), (1, 1, 1), (2, 2, 2)]);
+        }
+        if x == y && y + z == 2 {
+            diagonal_lines.push([(0, 0, 2), (1, 1, 1), (2, 2, 0)]);
+        }
+        if x + y == 2 && y == z {
+            diagonal_lines.push([(0, 2, 0), (1, 1, 1), (2, 0, 2)]);
+        }
+        if x + y == 2 && y + z == 2 {
+            diagonal_lines.push([(0, 2, 2), (1, 1, 1), (2, 0, 0)]);
+        }
+        
+        // Check all lines for potential
+        for line in lines.iter().chain(diagonal_lines.iter()) {
+            if line.contains(&(x, y, z)) {
+                let mut own_count = 0;
+                let mut opponent_count = 0;
+                
+                for &(lx, ly, lz) in line {
+                    let cell = game_state.board[lx][ly][lz];
+                    match cell.owner() {
+                        Some(owner) if owner == game_state.current_player => own_count += 1,
+                        Some(_) => opponent_count += 1,
+                        // A blocked cell kills the line just like an opponent's piece
+                        None if cell == CellState::Blocked => opponent_count += 1,
+                        None => {},
+                    }
+                }
+                
+                // Line is valuable if it's not blocked by an opponent
+                if opponent_count == 0 {
+                    count += 1.0 + own_count as f64; // More valuable if AI already has pieces in line
+                }
+            }
+        }
+        
+        count
+    }
+
+    /// Play out from `state` with mostly sensible moves; returns each player's reward
+    pub fn simulate_smart_game(&self, state: SearchState, rules: Rules, rng: &mut impl Rng) -> [f64; 3] {
+        self.play_out(state, rules, rng, None)
+    }
+
+    // `simulate_smart_game`, listing each move and who made it in `played` if given. A win or
+    // block is left out: the cell was forced by the moves before it, so crediting it to RAVE as
+    // though it could have been played first rates it far above what it's worth on its own
+    fn play_out(&self, mut state: SearchState, rules: Rules, rng: &mut impl Rng, mut played: Option<&mut Vec<(Player, Position)>>) -> [f64; 3] {
+        for _ in 0..MAX_ROLLOUT_PLIES {
+            let moves = MCTSAi::get_possible_moves_for_state(&state.board);
+            if moves.is_empty() {
+                break;
+            }
+
+            // Try to make smarter moves during simulation
+            let (chosen_move, forced) = if rng.gen_bool(self.rollout.smart_chance) {
+                self.choose_smart_move(&state.board, state.to_move, &moves, rules, rng)
+            } else {
+                // The rest are random, to add variety
+                (moves[rng.gen_range(0..moves.len())], false)
+            };
+
+            if let Some(played) = played.as_deref_mut().filter(|_| !forced) {
+                played.push((state.to_move, chosen_move));
+            }
+            // Playing through the rules also expires the mover's oldest piece under fading,
+            // ends line-scoring games on the move limit, and decides Notakto against the mover
+            if let Some(rewards) = state.play(chosen_move, &rules) {
+                return rewards;
+            }
+        }
+
+        // Out of moves or cut off while pieces keep fading
+        if rules.line_scoring {
+            score_rewards(&state.scores, &rules)
+        } else {
+            win_rewards(None) // Draw
+        }
+    }
+
+    // Choose a strategic move during simulation, and whether it was forced: a win or a block
+    fn choose_smart_move(&self, state: &[[[CellState; 3]; 3]; 3], player: Player, moves: &[(usize, usize, usize)], rules: Rules, rng: &mut impl Rng) -> ((usize, usize, usize), bool) {
+        // Notakto: avoid completing a line, otherwise play anywhere
+        if rules.notakto {
+            let safe_moves: Vec<_> = moves.iter().copied().filter(|&(x, y, z)| {
+                let mut test_state = *state;
+                test_state[x][y][z] = CellState::Neutral;
+                MCTSAi::check_winner_for_state(&test_state).is_none()
+            }).collect();
+            let candidates = if safe_moves.is_empty() { moves } else { &safe_moves };
+            return (candidates[rng.gen_range(0..candidates.len())], false);
+        }
+        
+        // First priority: win immediately if possible
+        for &(x, y, z) in moves {
+            let mut test_state = *state;
+            test_state[x][y][z] = player.cell();
+            
+            if MCTSAi::check_winner_for_state(&test_state).is_some() {
+                return ((x, y, z), true);
+            }
+        }
+        
+        // Second priority: block opponents from winning, starting with whoever moves next
+        let mut opponent = player.next(rules.player_count());
+        while opponent != player {
+            for &(x, y, z) in moves {
+                let mut test_state = *state;
+                test_state[x][y][z] = opponent.cell();
+                
+                if MCTSAi::check_winner_for_state(&test_state).is_some() {
+                    return ((x, y, z), true);
+                }
+            }
+            opponent = opponent.next(rules.player_count());
+        }
+        
+        // Third priority: prefer center and strategic positions
+        let mut scored_moves: Vec<_> = moves.iter().map(|&(x, y, z)| {
+            let mut score = 0.0;
+            
+            // Center preference
+            let center_distance = ((x as f64 - 1.0).abs() + (y as f64 - 1.0).abs() + (z as f64 - 1.0).abs()) / 3.0;
+            score += (1.0 - center_distance) * self.rollout.center;
+            
+            // Corner preference
+            if (x == 0 || x == 2) && (y == 0 || y == 2) && (z == 0 || z == 2) {
+                score += self.rollout.corner;
+            }
+            
+            ((x, y, z), score)
+        }).collect();
+        
+        scored_moves.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        
+        // Pick from top 3 moves with some randomness
+        let top_moves = std::cmp::min(3, scored_moves.len());
+        (scored_moves[rng.gen_range(0..top_moves)].0, false)
+    }
+
+    // Returns the piece forming a completed line, if any; who that line credits depends on the rules
+    fn check_winner_for_state(state: &[[[CellState; 3]; 3]; 3]) -> Option<CellState> {
+        // Check all possible winning lines in 3D
+        // Lines along X axis
+        for y in 0..3 {
+            for z in 0..3 {
+                if MCTSAi::check_line_for_state(state, [(0, y, z), (1, y, z), (2, y, z)]) {
+                    return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, y, z), (1, y, z), (2, y, z)]));
+                }
+            }
+        }
+
+        // Lines along Y axis
+        for x in 0..3 {
+            for z in 0..3 {
+                if MCTSAi::check_line_for_state(state, [(x, 0, z), (x, 1, z), (x, 2, z)]) {
+                    return Some(MCTSAi::get_winner_from_line_for_state(state, [(x, 0, z), (x, 1, z), (x, 2, z)]));
+                }
+            }
+        }
+
+        // Lines along Z axis
+        for x in 0..3 {
+            for y in 0..3 {
+                if MCTSAi::check_line_for_state(state, [(x, y, 0), (x, y, 1), (x, y, 2)]) {
+                    return Some(MCTSAi::get_winner_from_line_for_state(state, [(x, y, 0), (x, y, 1), (x, y, 2)]));
+                }
+            }
+        }
+
+        // Face diagonals on XY planes
+        for z in 0..3 {
+            if MCTSAi::check_line_for_state(state, [(0, 0, z), (1, 1, z), (2, 2, z)]) {
+                return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, 0, z), (1, 1, z), (2, 2, z)]));
+            }
+            if MCTSAi::check_line_for_state(state, [(0, 2, z), (1, 1, z), (2, 0, z)]) {
+                return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, 2, z), (1, 1, z), (2, 0, z)]));
+            }
+        }
+
+        // Face diagonals on XZ planes
+        for y in 0..3 {
+            if MCTSAi::check_line_for_state(state, [(0, y, 0), (1, y, 1), (2, y, 2)]) {
+                return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, y, 0), (1, y, 1), (2, y, 2)]));
+            }
+            if MCTSAi::check_line_for_state(state, [(0, y, 2), (1, y, 1), (2, y, 0)]) {
+                return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, y, 2), (1, y, 1), (2, y, 0)]));
+            }
+        }
+
+        // Face diagonals on YZ planes
+        for x in 0..3 {
+            if MCTSAi::check_line_for_state(state, [(x, 0, 0), (x, 1, 1), (x, 2, 2)]) {
+                return Some(MCTSAi::get_winner_from_line_for_state(state, [(x, 0, 0), (x, 1, 1), (x, 2, 2)]));
+            }
+            if MCTSAi::check_line_for_state(state, [(x, 0, 2), (x, 1, 1), (x, 2, 0)]) {
+                return Some(MCTSAi::get_winner_from_line_for_state(state, [(x, 0, 2), (x, 1, 1), (x, 2, 0)]));
+            }
+        }
+
+        // 3D diagonals (corner to corner)
+        if MCTSAi::check_line_for_state(state, [(0, 0, 0), (1, 1, 1), (2, 2, 2)]) {
+            return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, 0, 0), (1, 1, 1), (2, 2, 2)]));
+        }
+        if MCTSAi::check_line_for_state(state, [(0, 0, 2), (1, 1, 1), (2, 2, 0)]) {
+            return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, 0, 2), (1, 1, 1), (2, 2, 0)]));
+        }
+        if MCTSAi::check_line_for_state(state, [(0, 2, 0), (1, 1, 1), (2, 0, 2)]) {
+            return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, 2, 0), (1, 1, 1), (2, 0, 2)]));
+        }
+        if MCTSAi::check_line_for_state(state, [(0, 2, 2), (1, 1, 1), (2, 0, 0)]) {
+            return Some(MCTSAi::get_winner_from_line_for_state(state, [(0, 2, 2), (1, 1, 1), (2, 0, 0)]));
+        }
+
+        None
+    }
+
+    fn check_line_for_state(state: &[[[CellState; 3]; 3]; 3], positions: [(usize, usize, usize); 3]) -> bool {
+        let cells = [
+            state[positions[0].0][positions[0].1][positions[0].2],
+            state[positions[1].0][positions[1].1][positions[1].2],
+            state[positions[2].0][positions[2].1][positions[2].2],
+        ];
+
+        cells[0].is_piece() && cells[0] == cells[1] && cells[1] == cells[2]
+    }
+
+    fn get_winner_from_line_for_state(state: &[[[CellState; 3]; 3]; 3], positions: [(usize, usize, usize); 3]) -> CellState {
+        state[positions[0].0][positions[0].1][positions[0].2]
+    }
+
+    /// Every empty cell of a board
+    pub fn get_possible_moves_for_state(state: &[[[CellState; 3]; 3]; 3]) -> Vec<(usize, usize, usize)> {
+        let mut moves = Vec::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    if state[x][y][z] == CellState::Empty {
+                        moves.push((x, y, z));
+                    }
+                }
+            }
+        }
+        moves
+    }
+} 
\ No newline at end of file
@@ -0,0 +1,404 @@
+//! The iterative-deepening alpha-beta engine
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::ai::{rewards_on_passing, Engine, MCTSAi, SearchState, SearchStats};
+use crate::game::{line_index, CellState, GameState, Player, Position, Rules};
+use crate::symmetry::{canonical, PositionKey};
+
+// Scores for decided games dwarf any evaluation; quicker wins (and slower losses) score higher
+const WIN_SCORE: f64 = 10_000.0;
+
+// Evaluation weights for a line that only one player has pieces in
+const ONE_IN_LINE: f64 = 1.0;
+const TWO_IN_LINE: f64 = 10.0;
+// A completed line under line scoring
+const SCORED_LINE: f64 = 50.0;
+
+// How a stored value relates to the true one: exact, or only a bound because of a cutoff
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+struct TableEntry {
+    depth: u32,
+    // Decided games are stored as distance from this position rather than from the root
+    value: f64,
+    bound: Bound,
+}
+
+/// Iterative-deepening minimax with alpha-beta pruning over a handcrafted evaluation. With a
+/// third player the others are assumed to gang up on the engine (paranoid search). Positions
+/// are remembered by canonical key, so rotations and reflections of one are only searched once
+pub struct AlphaBetaAi {
+    /// Deepest the search goes, in plies
+    pub max_depth: u32,
+    /// Thinking time per move, on top of any deadline the turn clock sets
+    pub time_budget: Duration,
+    // How much the latest search did, without any per-move ratings
+    stats: Mutex<Option<SearchStats>>,
+    // Positions the move search in progress, or the last one, has visited
+    progress: AtomicU64,
+}
+
+impl AlphaBetaAi {
+    /// An engine searching 6 plies deep
+    pub fn new() -> Self {
+        Self {
+            max_depth: 6,
+            time_budget: Duration::from_secs(1),
+            stats: Mutex::new(None),
+            progress: AtomicU64::new(0),
+        }
+    }
+
+    // Search `state` to `max_depth`, deepening one ply at a time until time runs out.
+    // Returns the best move of the deepest search that finished, and its value for `me`
+    fn search(&self, state: &SearchState, rules: &Rules, me: Player, deadline: Instant) -> Option<(Position, f64)> {
+        let mut best: Option<(Position, f64)> = None;
+        // Shared across iterations, so each one starts from the last one's values
+        let mut search = Search::new(rules, me, deadline);
+        search.progress = Some(&self.progress);
+        for depth in 1..=self.max_depth {
+            let Some(result) = search.root(state, depth, best.map(|(position, _)| position)) else {
+                break;
+            };
+            best = Some(result);
+            // A forced result won't change with more depth
+            if result.1.abs() >= WIN_SCORE / 2.0 {
+                break;
+            }
+        }
+        // The evaluation isn't a probability, but a forced result is certain
+        let win_rate = best.and_then(|(_, value)| match value {
+            value if value >= WIN_SCORE / 2.0 => Some(1.0),
+            value if value <= -WIN_SCORE / 2.0 => Some(0.0),
+            _ => None,
+        });
+        *self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(SearchStats {
+            board: state.board,
+            moves: Vec::new(),
+            nodes: search.nodes,
+            win_rate,
+        });
+        best
+    }
+
+    fn deadline(&self, deadline: Option<Instant>) -> Instant {
+        let budget = Instant::now() + self.time_budget;
+        deadline.map_or(budget, |deadline| deadline.min(budget))
+    }
+}
+
+impl Default for AlphaBetaAi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for AlphaBetaAi {
+    fn best_move(&self, game_state: &GameState, deadline: Option<Instant>) -> Option<Position> {
+        if game_state.game_over {
+            return None;
+        }
+
+        self.progress.store(0, Ordering::Relaxed);
+        let state = SearchState::from_game(game_state);
+        let deadline = self.deadline(deadline);
+        self.search(&state, &game_state.rules, game_state.current_player, deadline)
+            .map(|(position, _)| position)
+            // Out of time before even one ply finished; any legal move beats none
+            .or_else(|| game_state.get_empty_positions().first().copied())
+    }
+
+    // Pie rule: search both the position after declining and the one after swapping
+    fn should_swap(&self, game_state: &GameState) -> bool {
+        let Some(&(x, y, z)) = game_state.move_history.first() else {
+            return false;
+        };
+
+        let me = game_state.current_player;
+        let rules = &game_state.rules;
+        let decline = SearchState::from_game(game_state);
+        let mut swapped = decline.clone();
+        swapped.board[x][y][z] = me.cell();
+        if rules.fading {
+            swapped.placements[me.next(2).index()].retain(|&position| position != (x, y, z));
+            swapped.placements[me.index()].push_back((x, y, z));
+        }
+        swapped.to_move = me.next(2);
+
+        // Half the budget for each side of the comparison
+        let half = self.time_budget / 2;
+        let value = |state: &SearchState| {
+            let mut search = Search::new(rules, me, Instant::now() + half);
+            (1..=self.max_depth)
+                .map_while(|depth| search.value(state, depth, 0, f64::NEG_INFINITY, f64::INFINITY))
+                .last()
+                .unwrap_or_else(|| evaluate(state, rules, me))
+        };
+        value(&swapped) > value(&decline)
+    }
+
+    // Pass variant: search the position as it is against the one with the turn handed on, or
+    // against the final result if the pass would end the game
+    fn should_pass(&self, game_state: &GameState) -> bool {
+        if !game_state.can_pass() {
+            return false;
+        }
+
+        let me = game_state.current_player;
+        let rules = &game_state.rules;
+        let play = SearchState::from_game(game_state);
+        let half = self.time_budget / 2;
+        let value = |state: &SearchState| {
+            let mut search = Search::new(rules, me, Instant::now() + half);
+            (1..=self.max_depth)
+                .map_while(|depth| search.value(state, depth, 0, f64::NEG_INFINITY, f64::INFINITY))
+                .last()
+                .unwrap_or_else(|| evaluate(state, rules, me))
+        };
+
+        let pass_value = if game_state.pass_ends_game() {
+            terminal_value(&rewards_on_passing(game_state), me, 0)
+        } else {
+            value(&SearchState {
+                to_move: me.next(rules.player_count()),
+                ..play.clone()
+            })
+        };
+        pass_value > value(&play)
+    }
+
+    // Nothing carries over from one search to the next, so there is nothing to get ahead on
+    fn ponder(&self, _game_state: &GameState, _stop: &AtomicBool) {}
+
+    // Only the final score of each search is kept, not a rating for every move, so this only
+    // tells how much was searched
+    fn search_stats(&self) -> Option<SearchStats> {
+        self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    fn search_progress(&self) -> u64 {
+        self.progress.load(Ordering::Relaxed)
+    }
+}
+
+struct Search<'a> {
+    rules: &'a Rules,
+    me: Player,
+    deadline: Instant,
+    nodes: u64,
+    table: HashMap<PositionKey, TableEntry>,
+    // Where a move search reports its nodes as it goes
+    progress: Option<&'a AtomicU64>,
+}
+
+impl<'a> Search<'a> {
+    fn new(rules: &'a Rules, me: Player, deadline: Instant) -> Self {
+        Self {
+            rules,
+            me,
+            deadline,
+            nodes: 0,
+            table: HashMap::new(),
+            progress: None,
+        }
+    }
+
+    // Only look at the clock, and report progress, every so often
+    fn out_of_time(&mut self) -> bool {
+        self.nodes += 1;
+        if !self.nodes.is_multiple_of(256) {
+            return false;
+        }
+        if let Some(progress) = self.progress {
+            progress.fetch_add(256, Ordering::Relaxed);
+        }
+        Instant::now() >= self.deadline
+    }
+
+    fn root(&mut self, state: &SearchState, depth: u32, first: Option<Position>) -> Option<(Position, f64)> {
+        let mut best: Option<(Position, f64)> = None;
+        let mut alpha = f64::NEG_INFINITY;
+        let mut seen = Vec::new();
+        for position in order_moves(state, first) {
+            let mut child = state.clone();
+            let value = match child.play(position, self.rules) {
+                Some(rewards) => terminal_value(&rewards, self.me, 1),
+                None => {
+                    // A move symmetric to one already searched can't be any better
+                    let key = canonical(&child).0;
+                    if seen.contains(&key) {
+                        continue;
+                    }
+                    seen.push(key);
+                    self.value(&child, depth - 1, 1, alpha, f64::INFINITY)?
+                }
+            };
+            if best.is_none_or(|(_, best_value)| value > best_value) {
+                best = Some((position, value));
+                alpha = alpha.max(value);
+            }
+        }
+        best
+    }
+
+    // Minimax value of `state` for the engine, or None if time ran out partway
+    fn value(&mut self, state: &SearchState, depth: u32, ply: u32, mut alpha: f64, mut beta: f64) -> Option<f64> {
+        if self.out_of_time() {
+            return None;
+        }
+        if depth == 0 {
+            return Some(evaluate(state, self.rules, self.me));
+        }
+
+        let key = canonical(state).0;
+        if let Some(entry) = self.table.get(&key).filter(|entry| entry.depth >= depth) {
+            let value = from_table(entry.value, ply);
+            match entry.bound {
+                Bound::Exact => return Some(value),
+                Bound::Lower => alpha = alpha.max(value),
+                Bound::Upper => beta = beta.min(value),
+            }
+            if alpha >= beta {
+                return Some(value);
+            }
+        }
+        let (alpha_in, beta_in) = (alpha, beta);
+
+        let maximizing = state.to_move == self.me;
+        let mut best = if maximizing { f64::NEG_INFINITY } else { f64::INFINITY };
+        let mut seen = Vec::new();
+        for position in order_moves(state, None) {
+            let mut child = state.clone();
+            let value = match child.play(position, self.rules) {
+                Some(rewards) => terminal_value(&rewards, self.me, ply + 1),
+                None => {
+                    let key = canonical(&child).0;
+                    if seen.contains(&key) {
+                        continue;
+                    }
+                    seen.push(key);
+                    self.value(&child, depth - 1, ply + 1, alpha, beta)?
+                }
+            };
+
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(value);
+            } else {
+                best = best.min(value);
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= alpha_in {
+            Bound::Upper
+        } else if best >= beta_in {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(key, TableEntry { depth, value: to_table(best, ply), bound });
+        Some(best)
+    }
+}
+
+// Decided games score by distance from the root, which differs between transpositions;
+// the table stores them by distance from the position itself
+fn to_table(value: f64, ply: u32) -> f64 {
+    if value.abs() >= WIN_SCORE / 2.0 {
+        value + value.signum() * ply as f64
+    } else {
+        value
+    }
+}
+
+fn from_table(value: f64, ply: u32) -> f64 {
+    if value.abs() >= WIN_SCORE / 2.0 {
+        value - value.signum() * ply as f64
+    } else {
+        value
+    }
+}
+
+fn terminal_value(rewards: &[f64; 3], me: Player, ply: u32) -> f64 {
+    let reward = rewards[me.index()];
+    // Prefer the quickest win, and put off a loss for as long as possible
+    reward * (WIN_SCORE - ply as f64)
+}
+
+// Cells on more lines first (the centre, then corners), with the last iteration's best move
+// ahead of everything so the cutoffs come early
+fn order_moves(state: &SearchState, first: Option<Position>) -> Vec<Position> {
+    let mut moves = MCTSAi::get_possible_moves_for_state(&state.board);
+    moves.sort_by_key(|&(x, y, z)| {
+        let preferred = first == Some((x, y, z));
+        (!preferred, std::cmp::Reverse(line_index().lines_through(x, y, z).count()))
+    });
+    moves
+}
+
+// Handcrafted evaluation for `me`: lines that only one player has pieces in are threats,
+// worth more the closer they are to done, plus any completed lines under line scoring
+fn evaluate(state: &SearchState, rules: &Rules, me: Player) -> f64 {
+    // Under Notakto every piece is shared, so there's nothing to tell the players apart
+    if rules.notakto {
+        return 0.0;
+    }
+
+    let mut score = 0.0;
+    for line in &line_index().lines {
+        let mut owner = None;
+        let mut pieces = 0;
+        let mut live = true;
+        for &(x, y, z) in line {
+            match state.board[x][y][z] {
+                CellState::Empty => {}
+                // Blocked cells never form part of a line
+                CellState::Blocked => live = false,
+                cell => {
+                    match (owner, cell.owner()) {
+                        (None, Some(player)) => owner = Some(player),
+                        (Some(existing), Some(player)) if existing != player => live = false,
+                        _ => {}
+                    }
+                    pieces += 1;
+                }
+            }
+        }
+
+        let Some(owner) = owner.filter(|_| live) else {
+            continue;
+        };
+        let weight = match pieces {
+            1 => ONE_IN_LINE,
+            2 => TWO_IN_LINE,
+            _ => 0.0,
+        };
+        score += if owner == me { weight } else { -weight };
+    }
+
+    if rules.line_scoring {
+        let players = &[Player::Human, Player::AI, Player::Third][..rules.player_count()];
+        let best_opponent = players
+            .iter()
+            .filter(|&&player| player != me)
+            .map(|player| state.scores[player.index()])
+            .max()
+            .unwrap_or(0);
+        score += (state.scores[me.index()] as f64 - best_opponent as f64) * SCORED_LINE;
+    }
+
+    score
+}
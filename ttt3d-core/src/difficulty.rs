@@ -0,0 +1,59 @@
+//! The difficulty levels, and the search budget each one gives the engines
+
+use serde::{Deserialize, Serialize};
+
+/// How hard the AI plays; each engine turns it into its own search budget
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Few simulations and frequent lapses, for beginners
+    Easy,
+    /// Plays soundly but sometimes lapses
+    #[default]
+    Normal,
+    /// A strong search with no lapses
+    Hard,
+    /// The strongest search
+    Expert,
+}
+
+impl Difficulty {
+    /// Name shown to the player
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+        }
+    }
+
+    /// Rollout budget per AI move
+    pub fn simulations(self) -> u32 {
+        match self {
+            Difficulty::Easy => 200,
+            Difficulty::Normal => 2000,
+            Difficulty::Hard => 5000,
+            Difficulty::Expert => 10000,
+        }
+    }
+
+    /// Deepest search for the alpha-beta engine, in plies
+    pub fn search_depth(self) -> u32 {
+        match self {
+            Difficulty::Easy => 2,
+            Difficulty::Normal => 4,
+            Difficulty::Hard => 6,
+            Difficulty::Expert => 8,
+        }
+    }
+
+    /// Chance that a searched MCTS move is a lapse rather than the best it found, so the lower
+    /// difficulties make the odd human-looking mistake on top of searching less
+    pub fn blunder_rate(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.3,
+            Difficulty::Normal => 0.1,
+            Difficulty::Hard | Difficulty::Expert => 0.0,
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Short explanations of a move from the lines it completes, blocks or threatens
+
+use crate::game::{line_index, line_kind, place_piece, CellState, GameState, Line, LineKind, Player, Position};
+use crate::threats::{forks, threats};
+
+/// Why a move was played, read off the lines through its cell the way the engines' win, block
+/// and threat checks see them
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveExplanation {
+    /// Who played the move
+    pub player: Player,
+    /// The line the move completed
+    pub completed: Option<Line>,
+    /// Lines an opponent was one piece from completing, and whose they were
+    pub blocked: Vec<(Player, Line)>,
+    /// Lines the move left one piece from done
+    pub threats: Vec<Line>,
+    /// Whether those threats need answering in two different cells, so one reply can't stop them
+    pub fork: bool,
+    /// The two-player opponent who could have forked on this cell
+    pub fork_blocked: Option<Player>,
+}
+
+impl MoveExplanation {
+    /// Explain the player to move playing at `position`, before the move is made. Under Notakto
+    /// every piece is shared and lines are to be avoided, so there is nothing of this kind to say,
+    /// and nor is there for a move that neither completes, blocks nor threatens a line
+    pub fn of(game: &GameState, (x, y, z): Position) -> Option<Self> {
+        let rules = &game.rules;
+        if rules.notakto || game.board[x][y][z] != CellState::Empty {
+            return None;
+        }
+
+        let player = game.current_player;
+        let piece = rules.piece(player);
+        let cell = |board: &[[[CellState; 3]; 3]; 3], (x, y, z): Position| board[x][y][z];
+
+        // The lines that were an opponent's piece away from done, before this one went in
+        let blocked = line_index()
+            .lines_through(x, y, z)
+            .filter_map(|line| {
+                let mut owners = others(line, (x, y, z)).map(|other| cell(&game.board, other).owner());
+                match (owners.next()?, owners.next()?) {
+                    (Some(a), Some(b)) if a == b && a != player => Some((a, *line)),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        // Placed through the rules, so a piece fading off the board is accounted for
+        let mut after = game.board;
+        let mut placements = game.placements.clone();
+        place_piece(&mut after, &mut placements, rules, player, (x, y, z));
+        let completed = line_index()
+            .lines_through(x, y, z)
+            .find(|line| others(line, (x, y, z)).all(|other| cell(&after, other) == piece))
+            .copied();
+        let fork = threats(&after, player).len() >= 2;
+        let threats = line_index()
+            .lines_through(x, y, z)
+            .filter(|line| {
+                let (mine, empty) = others(line, (x, y, z)).fold((0, 0), |(mine, empty), other| match cell(&after, other) {
+                    c if c == piece => (mine + 1, empty),
+                    CellState::Empty => (mine, empty + 1),
+                    _ => (mine, empty),
+                });
+                mine == 1 && empty == 1
+            })
+            .copied()
+            .collect();
+
+        let opponent = player.next(2);
+        let fork_blocked = (rules.player_count() == 2 && forks(&game.board, opponent).contains(&(x, y, z))).then_some(opponent);
+
+        let explanation = Self { player, completed, blocked, threats, fork, fork_blocked };
+        let said = explanation.completed.is_some()
+            || !explanation.blocked.is_empty()
+            || !explanation.threats.is_empty()
+            || explanation.fork_blocked.is_some();
+        said.then_some(explanation)
+    }
+
+    /// One short sentence for the status line, e.g. "AI blocked your space diagonal
+    /// (0, 0, 0)-(2, 2, 2)"; names come from `game`
+    pub fn summary(&self, game: &GameState) -> String {
+        format!("{} {}", game.player_name(self.player), self.parts(game, "took").join(" and "))
+    }
+
+    /// The same for a move that wasn't played, e.g. "would have blocked your space diagonal
+    /// (0, 0, 0)-(2, 2, 2)"
+    pub fn hypothetical(&self, game: &GameState) -> String {
+        format!("would have {}", self.parts(game, "taken").join(" and "))
+    }
+
+    // What the move did, with the verb for taking a fork in the form the sentence needs
+    fn parts(&self, game: &GameState, took: &str) -> Vec<String> {
+        let whose = |player: Player| match player {
+            Player::Human => "your".to_string(),
+            Player::AI => "the AI's".to_string(),
+            player => format!("{}'s", game.player_name(player)),
+        };
+
+        let mut parts = Vec::new();
+        if let Some(line) = self.completed {
+            parts.push(format!("completed the {}", describe(&line)));
+        }
+        for &(owner, line) in &self.blocked {
+            parts.push(format!("blocked {} {}", whose(owner), describe(&line)));
+        }
+        if let Some(owner) = self.fork_blocked {
+            parts.push(format!("{} {} fork", took, whose(owner)));
+        }
+        match self.threats.first() {
+            _ if self.fork => parts.push("created a double threat".to_string()),
+            Some(line) => parts.push(format!("threatened the {}", describe(line))),
+            None => {}
+        }
+        parts
+    }
+}
+
+// The two cells of `line` besides `position`
+fn others(line: &Line, position: Position) -> impl Iterator<Item = Position> + '_ {
+    line.iter().copied().filter(move |&other| other != position)
+}
+
+fn describe(line: &Line) -> String {
+    let kind = match line_kind(line) {
+        LineKind::Axis => "straight line",
+        LineKind::FaceDiagonal => "face diagonal",
+        LineKind::SpaceDiagonal => "space diagonal",
+    };
+    let [(ax, ay, az), _, (bx, by, bz)] = *line;
+    format!("{} ({}, {}, {})-({}, {}, {})", kind, ax, ay, az, bx, by, bz)
+}
@@ -0,0 +1,961 @@
+//! Cells, players, rule variants, the 49 winning lines, and [`GameState`], which plays moves
+//! and decides results under the chosen rules
+
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, OnceLock};
+use crate::ai::{Engine, MCTSAi};
+use crate::explain::MoveExplanation;
+
+/// A cell as `(x, y, z)`, each from 0 to 2
+pub type Position = (usize, usize, usize);
+/// Three cells in a row, from one end to the other
+pub type Line = [Position; 3];
+/// Each player's pieces in the order they were placed (indexed by `Player::index`)
+pub type Placements = [VecDeque<Position>; 3];
+
+/// Turns `GameState::snapshots` keeps, for rewinding without replaying the whole game
+pub const SNAPSHOT_TURNS: usize = 8;
+
+/// Pieces each player may have on the board at once under the fading rule
+pub const FADING_PIECE_LIMIT: usize = 6;
+
+/// Line scoring ends when the board is full; with fading pieces it never fills, so cap the moves
+pub const SCORING_MOVE_LIMIT: usize = 27;
+
+/// Every winning line on the board, plus an index of which lines pass through each cell
+pub struct LineIndex {
+    /// All 49 lines
+    pub lines: Vec<Line>,
+    by_cell: [[[Vec<usize>; 3]; 3]; 3],
+}
+
+impl LineIndex {
+    fn build() -> Self {
+        let mut lines = Vec::new();
+
+        // One representative of each of the 13 line directions (the other 13 are their negations)
+        let mut directions = Vec::new();
+        for dx in -1i32..=1 {
+            for dy in -1i32..=1 {
+                for dz in -1i32..=1 {
+                    if (dx, dy, dz) > (0, 0, 0) {
+                        directions.push((dx, dy, dz));
+                    }
+                }
+            }
+        }
+
+        let in_bounds = |v: i32| (0..3).contains(&v);
+        for x in 0..3i32 {
+            for y in 0..3i32 {
+                for z in 0..3i32 {
+                    for &(dx, dy, dz) in &directions {
+                        // Only start lines at a board edge so each line is found exactly once
+                        let starts_at_edge = !(in_bounds(x - dx) && in_bounds(y - dy) && in_bounds(z - dz));
+                        let fits = in_bounds(x + 2 * dx) && in_bounds(y + 2 * dy) && in_bounds(z + 2 * dz);
+                        if starts_at_edge && fits {
+                            let cell = |i: i32| ((x + i * dx) as usize, (y + i * dy) as usize, (z + i * dz) as usize);
+                            lines.push([cell(0), cell(1), cell(2)]);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut by_cell: [[[Vec<usize>; 3]; 3]; 3] = Default::default();
+        for (i, line) in lines.iter().enumerate() {
+            for &(x, y, z) in line {
+                by_cell[x][y][z].push(i);
+            }
+        }
+
+        Self { lines, by_cell }
+    }
+
+    /// The lines passing through a cell
+    pub fn lines_through(&self, x: usize, y: usize, z: usize) -> impl Iterator<Item = &Line> {
+        self.by_cell[x][y][z].iter().map(|&i| &self.lines[i])
+    }
+}
+
+/// The three shapes a winning line comes in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineKind {
+    /// Along one axis, e.g. a row or column of a layer
+    Axis,
+    /// Diagonally across one face or slice of the cube
+    FaceDiagonal,
+    /// Corner to corner through the center
+    SpaceDiagonal,
+}
+
+/// A line's kind is set by how many coordinates change along it
+pub fn line_kind(line: &Line) -> LineKind {
+    let (start, end) = (line[0], line[2]);
+    let changing = [start.0 != end.0, start.1 != end.1, start.2 != end.2]
+        .iter()
+        .filter(|&&changes| changes)
+        .count();
+    match changing {
+        1 => LineKind::Axis,
+        2 => LineKind::FaceDiagonal,
+        _ => LineKind::SpaceDiagonal,
+    }
+}
+
+/// The board's lines, built on first use and shared after that
+pub fn line_index() -> &'static LineIndex {
+    static INDEX: OnceLock<LineIndex> = OnceLock::new();
+    INDEX.get_or_init(LineIndex::build)
+}
+
+/// A seat at the board
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Player {
+    /// The local player, who moves first by default
+    Human,
+    /// The computer opponent
+    AI,
+    /// Optional third participant, controlled by a human or the AI depending on the rules
+    Third,
+}
+
+impl Player {
+    /// The piece this player owns (see `Rules::piece` for the one they place)
+    pub fn cell(self) -> CellState {
+        match self {
+            Player::Human => CellState::Human,
+            Player::AI => CellState::AI,
+            Player::Third => CellState::Third,
+        }
+    }
+
+    /// The player's position in per-player arrays such as `GameState::scores`
+    pub fn index(self) -> usize {
+        match self {
+            Player::Human => 0,
+            Player::AI => 1,
+            Player::Third => 2,
+        }
+    }
+
+    /// Turn order is Human -> AI -> Third (when playing with three) -> Human
+    pub fn next(self, player_count: usize) -> Player {
+        match self {
+            Player::Human => Player::AI,
+            Player::AI if player_count > 2 => Player::Third,
+            Player::AI | Player::Third => Player::Human,
+        }
+    }
+}
+
+/// What a cell holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellState {
+    /// Nobody has played here
+    Empty,
+    /// A piece of `Player::Human`
+    Human,
+    /// A piece of `Player::AI`
+    AI,
+    /// A piece of `Player::Third`
+    Third,
+    /// Shared piece used by every player in the Notakto variant
+    Neutral,
+    /// Handicap cell that nobody can play in and that never forms part of a line
+    Blocked,
+}
+
+impl CellState {
+    /// The player a piece belongs to; `None` for empty, blocked and shared cells
+    pub fn owner(self) -> Option<Player> {
+        match self {
+            CellState::Empty => None,
+            CellState::Human => Some(Player::Human),
+            CellState::AI => Some(Player::AI),
+            CellState::Third => Some(Player::Third),
+            CellState::Neutral | CellState::Blocked => None,
+        }
+    }
+
+    /// Whether a piece has been played here (as opposed to an empty or blocked cell)
+    pub fn is_piece(self) -> bool {
+        !matches!(self, CellState::Empty | CellState::Blocked)
+    }
+}
+
+/// Whether a third player takes part, and who controls them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThirdPlayer {
+    /// Two-player game
+    #[default]
+    Off,
+    /// A third player on the same screen
+    Human,
+    /// A third player controlled by the AI
+    AI,
+}
+
+/// Optional rule variants, chosen in settings and applied at the start of a game
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    /// After the first move the second player may take it over instead of replying
+    pub pie_rule: bool,
+    /// Whether a third player joins the game
+    pub third_player: ThirdPlayer,
+    /// Notakto (misère): everyone places the same piece and completing a line loses
+    pub notakto: bool,
+    /// Number of cells blocked at random at the start of each game
+    pub blocked_cells: usize,
+    /// Fading: placing a piece beyond the limit removes that player's oldest piece
+    pub fading: bool,
+    /// Line scoring: play on until the board is full, and whoever completed more lines wins
+    pub line_scoring: bool,
+    /// Passes: each player may skip this many turns per game (0 turns it off), and the game
+    /// ends once everyone passes in a row
+    pub passes: u32,
+}
+
+impl Rules {
+    /// Players taking turns: 2, or 3 with a third player
+    pub fn player_count(&self) -> usize {
+        match self.third_player {
+            ThirdPlayer::Off => 2,
+            ThirdPlayer::Human | ThirdPlayer::AI => 3,
+        }
+    }
+
+    /// The piece a player puts on the board
+    pub fn piece(&self, player: Player) -> CellState {
+        if self.notakto {
+            CellState::Neutral
+        } else {
+            player.cell()
+        }
+    }
+
+    /// Who wins when `mover` completes a line; under Notakto the mover loses to the next player
+    pub fn line_winner(&self, mover: Player) -> Player {
+        if self.notakto {
+            mover.next(self.player_count())
+        } else {
+            mover
+        }
+    }
+}
+
+/// Put a player's piece on the board; under the fading rule their oldest piece beyond the
+/// limit comes off again. Returns where a piece was removed, if one was
+pub fn place_piece(
+    board: &mut [[[CellState; 3]; 3]; 3],
+    placements: &mut Placements,
+    rules: &Rules,
+    player: Player,
+    (x, y, z): Position,
+) -> Option<Position> {
+    board[x][y][z] = rules.piece(player);
+    if !rules.fading {
+        return None;
+    }
+
+    let queue = &mut placements[player.index()];
+    queue.push_back((x, y, z));
+    if queue.len() <= FADING_PIECE_LIMIT {
+        return None;
+    }
+
+    let (ox, oy, oz) = queue.pop_front()?;
+    board[ox][oy][oz] = CellState::Empty;
+    Some((ox, oy, oz))
+}
+
+/// Number of lines the piece just placed at `position` completed
+pub fn completed_lines(board: &[[[CellState; 3]; 3]; 3], (x, y, z): Position) -> u32 {
+    let piece = board[x][y][z];
+    if !piece.is_piece() {
+        return 0;
+    }
+
+    line_index()
+        .lines_through(x, y, z)
+        .filter(|line| line.iter().all(|&(lx, ly, lz)| board[lx][ly][lz] == piece))
+        .count() as u32
+}
+
+/// What the moves played at the moment do
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamePhase {
+    /// Normal play
+    Playing,
+    /// Pie rule: the player to move may swap sides instead of replying to the opening move
+    SwapOffer,
+    /// A position is being set up in the board editor; no moves are played
+    Editing,
+}
+
+/// How a finished game came to an end
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOverReason {
+    /// A line was completed (which, under Notakto, loses)
+    Line,
+    /// Line scoring ran out of moves or room, and the scores decide
+    Scores,
+    /// The board filled up with no line completed
+    BoardFull,
+    /// Everyone passed in a row
+    Passes,
+    /// The player to move ran out of time on the turn clock
+    Time,
+    /// The player to move gave the game up
+    Resignation,
+    /// Both sides agreed to a draw
+    DrawAgreed,
+}
+
+/// How the game stood just before a turn: enough to put it back exactly. The lists that only grow
+/// during a game are kept as their lengths, to be cut back to
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    board: [[[CellState; 3]; 3]; 3],
+    current_player: Player,
+    phase: GamePhase,
+    swapped: bool,
+    last_move: Option<Position>,
+    last_removed: Option<(Position, CellState)>,
+    placements: Placements,
+    scores: [u32; 3],
+    passes_used: [u32; 3],
+    moves: usize,
+    passes: usize,
+    delegated_moves: usize,
+    explanations: usize,
+}
+
+impl Snapshot {
+    fn turns_taken(&self) -> usize {
+        self.moves + self.passes
+    }
+}
+
+/// A game in progress: the board, whose turn it is, the history, and the result once there
+/// is one
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::system::Resource))]
+#[derive(Clone)]
+pub struct GameState {
+    /// The cells, indexed `board[x][y][z]`
+    pub board: [[[CellState; 3]; 3]; 3],
+    /// The player whose turn it is
+    pub current_player: Player,
+    /// Set once the game has ended, however it did
+    pub game_over: bool,
+    /// Who won; `None` for a draw or a game still going
+    pub winner: Option<Player>,
+    /// Shared so a search can run on a snapshot of the game off the main thread
+    pub ai: Arc<dyn Engine>,
+    /// The cell picked with the keyboard, if any
+    pub selected_cube: Option<(usize, usize, usize)>,
+    /// The cell of the latest move
+    pub last_move: Option<(usize, usize, usize)>,
+    /// Every move of the game in order
+    pub move_history: Vec<(usize, usize, usize)>,
+    /// Indices into `move_history` of moves the engine played on a human's behalf
+    pub delegated_moves: Vec<usize>,
+    /// Why the AI played its moves, by index into `move_history`; moves with nothing to say
+    /// about them are left out
+    pub explanations: Vec<(usize, MoveExplanation)>,
+    /// Each player's pieces in the order they were placed, for the fading rule
+    pub placements: Placements,
+    /// Piece taken off the board by the most recent move under the fading rule, for animations
+    pub last_removed: Option<(Position, CellState)>,
+    /// Lines completed by each player under line scoring (indexed by `Player::index`)
+    pub scores: [u32; 3],
+    /// The line that ended the game, if one did
+    pub winning_line: Option<Line>,
+    /// Set once the game is over
+    pub game_over_reason: Option<GameOverReason>,
+    /// Bumped on every reset so results computed for an older game can be recognised and dropped
+    pub generation: u64,
+    /// The rules the game is played under
+    pub rules: Rules,
+    /// Whether the game is being played, offering a swap, or edited
+    pub phase: GamePhase,
+    /// Who moves first after a reset
+    pub first_player: Player,
+    /// Whether the opening move was taken over under the pie rule
+    pub swapped: bool,
+    /// Passes each player has made (indexed by `Player::index`)
+    pub passes_used: [u32; 3],
+    /// How many moves had been played at each pass, so passes replay in place between the
+    /// moves of `move_history`
+    pub passes: Vec<usize>,
+    /// Position the game started from (blocked cells, or a loaded puzzle or edited position),
+    /// so it can be replayed for analysis
+    pub start_board: [[[CellState; 3]; 3]; 3],
+    /// Who was to move in `start_board`
+    pub start_player: Player,
+    /// The game as it stood before each of its latest turns, oldest first, at most
+    /// `SNAPSHOT_TURNS` of them. Moves, passes and the pie rule's swap each add one
+    pub snapshots: VecDeque<Snapshot>,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self {
+            board: [[[CellState::Empty; 3]; 3]; 3],
+            current_player: Player::Human,
+            game_over: false,
+            winner: None,
+            ai: Arc::new(MCTSAi::new()),
+            selected_cube: None,
+            last_move: None,
+            move_history: Vec::new(),
+            delegated_moves: Vec::new(),
+            explanations: Vec::new(),
+            placements: Placements::default(),
+            last_removed: None,
+            scores: [0; 3],
+            winning_line: None,
+            game_over_reason: None,
+            generation: 0,
+            rules: Rules::default(),
+            phase: GamePhase::Playing,
+            first_player: Player::Human,
+            swapped: false,
+            passes_used: [0; 3],
+            passes: Vec::new(),
+            start_board: [[[CellState::Empty; 3]; 3]; 3],
+            start_player: Player::Human,
+            snapshots: VecDeque::new(),
+        }
+    }
+}
+
+impl GameState {
+    /// Whether the AI plays the current turn; never while editing
+    pub fn is_ai_turn(&self) -> bool {
+        if self.phase == GamePhase::Editing {
+            return false;
+        }
+
+        match self.current_player {
+            Player::Human => false,
+            Player::AI => true,
+            Player::Third => self.rules.third_player == ThirdPlayer::AI,
+        }
+    }
+
+    /// Whether a person plays the current turn
+    pub fn is_human_turn(&self) -> bool {
+        !self.is_ai_turn()
+    }
+
+    /// Display name used in status messages
+    pub fn player_name(&self, player: Player) -> &'static str {
+        match player {
+            Player::Human => "You",
+            Player::AI => "AI",
+            Player::Third if self.rules.third_player == ThirdPlayer::AI => "Blue AI",
+            Player::Third => "Blue",
+        }
+    }
+
+    /// Play the current player's piece at a cell and move the turn on, ending the game if that
+    /// move decides it. False, with nothing changed, if the cell isn't empty or no move can be made
+    pub fn make_move(&mut self, x: usize, y: usize, z: usize) -> bool {
+        if self.game_over || self.phase == GamePhase::Editing || self.board[x][y][z] != CellState::Empty {
+            return false;
+        }
+
+        self.take_snapshot();
+        // Replying to the opening move declines the swap
+        self.phase = GamePhase::Playing;
+
+        let piece = self.rules.piece(self.current_player);
+        self.last_removed = place_piece(&mut self.board, &mut self.placements, &self.rules, self.current_player, (x, y, z))
+            .map(|removed| (removed, piece));
+
+        // Track the last move for animations
+        self.last_move = Some((x, y, z));
+        self.move_history.push((x, y, z));
+
+        if self.rules.line_scoring {
+            // Every line counts, and the game only ends once there is nowhere left to play
+            let lines = completed_lines(&self.board, (x, y, z));
+            self.scores[self.rules.line_winner(self.current_player).index()] += lines;
+        }
+
+        if self.rules.line_scoring && (self.is_board_full() || self.move_history.len() >= SCORING_MOVE_LIMIT) {
+            self.game_over = true;
+            self.winner = self.score_leader();
+            self.game_over_reason = Some(GameOverReason::Scores);
+        } else if !self.rules.line_scoring && self.check_winner() {
+            self.game_over = true;
+            self.winner = Some(self.rules.line_winner(self.current_player));
+            self.game_over_reason = Some(GameOverReason::Line);
+            // Only the move just made can have completed a line
+            self.winning_line = line_index().lines_through(x, y, z).find(|line| self.check_line(**line)).copied();
+        } else if self.is_board_full() {
+            self.game_over = true;
+            self.winner = None; // Draw
+            self.game_over_reason = Some(GameOverReason::BoardFull);
+        } else {
+            self.current_player = self.current_player.next(self.rules.player_count());
+
+            // The swap only makes sense between two sides who own their pieces
+            if self.rules.pie_rule
+                && self.rules.player_count() == 2
+                && !self.rules.notakto
+                && self.move_history.len() == 1
+            {
+                self.phase = GamePhase::SwapOffer;
+            }
+        }
+
+        true
+    }
+
+    /// Pie rule: the player to move takes over the opening piece, and the opener moves again
+    pub fn swap_sides(&mut self) -> bool {
+        if self.game_over || self.phase != GamePhase::SwapOffer {
+            return false;
+        }
+
+        let Some(&(x, y, z)) = self.move_history.first() else {
+            return false;
+        };
+
+        self.take_snapshot();
+        // The taken-over piece now counts towards the swapper's fading limit
+        if let (true, Some(opener)) = (self.rules.fading, self.board[x][y][z].owner()) {
+            self.placements[opener.index()].retain(|&position| position != (x, y, z));
+            self.placements[self.current_player.index()].push_back((x, y, z));
+        }
+        self.board[x][y][z] = self.current_player.cell();
+        self.current_player = self.current_player.next(self.rules.player_count());
+        self.phase = GamePhase::Playing;
+        self.swapped = true;
+        true
+    }
+
+    /// Whether the player to move may pass instead of placing a piece
+    pub fn can_pass(&self) -> bool {
+        !self.game_over && self.phase != GamePhase::Editing && self.passes_used[self.current_player.index()] < self.rules.passes
+    }
+
+    /// Passes made since the last piece was placed
+    pub fn consecutive_passes(&self) -> usize {
+        self.passes.iter().rev().take_while(|&&moves| moves == self.move_history.len()).count()
+    }
+
+    /// Whether a pass now would be the last of everyone passing in a row, ending the game
+    pub fn pass_ends_game(&self) -> bool {
+        self.consecutive_passes() + 1 >= self.rules.player_count()
+    }
+
+    /// Pass variant: skip the turn; once everyone has passed in a row the game ends, as a draw
+    /// or, under line scoring, on the scores so far
+    pub fn pass(&mut self) -> bool {
+        if !self.can_pass() {
+            return false;
+        }
+
+        self.take_snapshot();
+        // Passing on the opening move declines the swap
+        self.phase = GamePhase::Playing;
+        let ends_game = self.pass_ends_game();
+        self.passes_used[self.current_player.index()] += 1;
+        self.passes.push(self.move_history.len());
+        // Nothing was placed, so nothing should animate as if it just was
+        self.last_move = None;
+        self.last_removed = None;
+
+        if ends_game {
+            self.game_over = true;
+            self.winner = if self.rules.line_scoring { self.score_leader() } else { None };
+            self.game_over_reason = Some(GameOverReason::Passes);
+        } else {
+            self.current_player = self.current_player.next(self.rules.player_count());
+        }
+        true
+    }
+
+    /// Moves and passes made so far, for telling turns apart when a pass leaves the board as it was
+    pub fn turns_taken(&self) -> usize {
+        self.move_history.len() + self.passes.len()
+    }
+
+    fn take_snapshot(&mut self) {
+        if self.snapshots.len() >= SNAPSHOT_TURNS {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(Snapshot {
+            board: self.board,
+            current_player: self.current_player,
+            phase: self.phase,
+            swapped: self.swapped,
+            last_move: self.last_move,
+            last_removed: self.last_removed,
+            placements: self.placements.clone(),
+            scores: self.scores,
+            passes_used: self.passes_used,
+            moves: self.move_history.len(),
+            passes: self.passes.len(),
+            delegated_moves: self.delegated_moves.len(),
+            explanations: self.explanations.len(),
+        });
+    }
+
+    /// Whether the snapshots still fit the game's own record of its turns: each a turn after the
+    /// one before it (or the same turn, for the swap, which isn't one), none ahead of the game,
+    /// and each naming as the last move the move the history has there
+    pub fn snapshots_consistent(&self) -> bool {
+        let mut turns = None;
+        for snapshot in &self.snapshots {
+            let in_order = turns.is_none_or(|turns| snapshot.turns_taken() == turns || snapshot.turns_taken() == turns + 1);
+            let in_history = snapshot.moves <= self.move_history.len()
+                && snapshot.passes <= self.passes.len()
+                && snapshot.delegated_moves <= self.delegated_moves.len()
+                && snapshot.explanations <= self.explanations.len();
+            let last_move_logged = snapshot.last_move.is_none_or(|position| {
+                snapshot.moves > 0 && self.move_history[snapshot.moves - 1] == position
+            });
+            if !in_order || !in_history || !last_move_logged {
+                return false;
+            }
+            turns = Some(snapshot.turns_taken());
+        }
+        turns.is_none_or(|turns| turns <= self.turns_taken())
+    }
+
+    /// Take back the latest turn, if it's still in the snapshots. A finished game stays finished,
+    /// and snapshots that have come apart from the history are dropped rather than trusted
+    pub fn rewind(&mut self) -> bool {
+        if self.game_over || self.phase == GamePhase::Editing {
+            return false;
+        }
+        if !self.snapshots_consistent() {
+            self.snapshots.clear();
+            return false;
+        }
+        let Some(snapshot) = self.snapshots.pop_back() else {
+            return false;
+        };
+
+        self.board = snapshot.board;
+        self.current_player = snapshot.current_player;
+        self.phase = snapshot.phase;
+        self.swapped = snapshot.swapped;
+        self.last_move = snapshot.last_move;
+        self.last_removed = snapshot.last_removed;
+        self.placements = snapshot.placements;
+        self.scores = snapshot.scores;
+        self.passes_used = snapshot.passes_used;
+        self.move_history.truncate(snapshot.moves);
+        self.passes.truncate(snapshot.passes);
+        self.delegated_moves.truncate(snapshot.delegated_moves);
+        self.explanations.truncate(snapshot.explanations);
+        true
+    }
+
+    /// Take back turns until it's a human's to play again, as it was before their latest one;
+    /// nothing if that turn is no longer in the snapshots
+    pub fn rewind_to_human_turn(&mut self) -> bool {
+        let human = |game: &Self, player: Player| match player {
+            Player::Human => true,
+            Player::AI => false,
+            Player::Third => game.rules.third_player != ThirdPlayer::AI,
+        };
+        let Some(back) = self.snapshots.iter().rev().position(|snapshot| human(self, snapshot.current_player)) else {
+            return false;
+        };
+        (0..=back).all(|_| self.rewind())
+    }
+
+    /// Under the pie rule, play on without swapping sides
+    pub fn decline_swap(&mut self) {
+        if self.phase == GamePhase::SwapOffer {
+            self.phase = GamePhase::Playing;
+        }
+    }
+
+    /// "Play for me": apply an engine move for the human to move and mark it in the history
+    pub fn make_delegated_move(&mut self, x: usize, y: usize, z: usize) -> bool {
+        let index = self.move_history.len();
+        if !self.make_move(x, y, z) {
+            return false;
+        }
+        self.delegated_moves.push(index);
+        true
+    }
+
+    /// Apply a move computed for a specific game; stale results from before a reset are ignored
+    pub fn make_move_for_generation(&mut self, generation: u64, x: usize, y: usize, z: usize) -> bool {
+        if generation != self.generation {
+            return false;
+        }
+        self.make_move(x, y, z)
+    }
+
+    /// Play the AI's chosen move like `make_move_for_generation`, noting why it was played
+    pub fn make_explained_move(&mut self, generation: u64, x: usize, y: usize, z: usize) -> bool {
+        let explanation = MoveExplanation::of(self, (x, y, z));
+        if !self.make_move_for_generation(generation, x, y, z) {
+            return false;
+        }
+        if let Some(explanation) = explanation {
+            self.explanations.push((self.move_history.len() - 1, explanation));
+        }
+        true
+    }
+
+    /// Why the latest move was played, if it was explained and nobody has passed since
+    pub fn last_explanation(&self) -> Option<&MoveExplanation> {
+        self.last_move?;
+        let index = self.move_history.len().checked_sub(1)?;
+        self.explanations.iter().rev().find(|(i, _)| *i == index).map(|(_, explanation)| explanation)
+    }
+
+    /// Start playing from a set-up position (puzzles and the board editor)
+    pub fn load_position(&mut self, board: [[[CellState; 3]; 3]; 3], to_move: Player) {
+        self.board = board;
+        self.current_player = to_move;
+        self.phase = GamePhase::Playing;
+        self.start_board = board;
+        self.start_player = to_move;
+        self.snapshots.clear();
+    }
+
+    /// Clear the board for the editor; positions are always set up under the standard rules
+    pub fn start_editing(&mut self) {
+        self.rules = Rules::default();
+        self.reset();
+        self.phase = GamePhase::Editing;
+    }
+
+    /// Set a cell in the board editor. False outside of editing
+    pub fn edit_cell(&mut self, x: usize, y: usize, z: usize, cell: CellState) -> bool {
+        if self.phase != GamePhase::Editing {
+            return false;
+        }
+        self.board[x][y][z] = cell;
+        true
+    }
+
+    /// Whoever completed the most lines, or None when the top score is shared
+    pub fn score_leader(&self) -> Option<Player> {
+        let players = [Player::Human, Player::AI, Player::Third];
+        let best = players.iter().map(|player| self.scores[player.index()]).max()?;
+        let mut leaders = players.iter().filter(|player| self.scores[player.index()] == best);
+        match (leaders.next(), leaders.next()) {
+            (Some(&leader), None) => Some(leader),
+            _ => None,
+        }
+    }
+
+    /// The player to move ran out of time on the turn clock and loses;
+    /// with three players the win goes to whoever was due to move next
+    pub fn forfeit_on_time(&mut self) {
+        if self.game_over {
+            return;
+        }
+
+        self.game_over = true;
+        self.game_over_reason = Some(GameOverReason::Time);
+        self.winner = Some(self.current_player.next(self.rules.player_count()));
+    }
+
+    /// The player to move gives up and loses, the win going to whoever was due to move next
+    pub fn resign(&mut self) {
+        if self.game_over || self.phase == GamePhase::Editing {
+            return;
+        }
+
+        self.game_over = true;
+        self.game_over_reason = Some(GameOverReason::Resignation);
+        self.winner = Some(self.current_player.next(self.rules.player_count()));
+    }
+
+    /// End the game as a draw both sides agreed to
+    pub fn agree_draw(&mut self) {
+        if self.game_over || self.phase == GamePhase::Editing {
+            return;
+        }
+
+        self.game_over = true;
+        self.game_over_reason = Some(GameOverReason::DrawAgreed);
+        self.winner = None;
+    }
+
+    /// Whether no line can be completed any more, each holding two owners' pieces or a cell
+    /// nobody owns, so the rest of the game can only be a draw. Line scoring, Notakto and fading
+    /// don't end on a line the same way, so they are never dead drawn
+    pub fn is_dead_drawn(&self) -> bool {
+        if self.game_over || self.rules.line_scoring || self.rules.notakto || self.rules.fading {
+            return false;
+        }
+
+        line_index().lines.iter().all(|line| {
+            let mut owner = None;
+            line.iter().any(|&(x, y, z)| match self.board[x][y][z] {
+                CellState::Empty => false,
+                cell => match cell.owner() {
+                    Some(player) => owner.replace(player).is_some_and(|other| other != player),
+                    None => true,
+                },
+            })
+        })
+    }
+
+    /// Whether any line is held entirely by one kind of piece
+    pub fn check_winner(&self) -> bool {
+        // Check all possible winning lines in 3D
+        // Lines along X axis
+        for y in 0..3 {
+            for z in 0..3 {
+                if self.check_line([(0, y, z), (1, y, z), (2, y, z)]) {
+                    return true;
+                }
+            }
+        }
+
+        // Lines along Y axis
+        for x in 0..3 {
+            for z in 0..3 {
+                if self.check_line([(x, 0, z), (x, 1, z), (x, 2, z)]) {
+                    return true;
+                }
+            }
+        }
+
+        // Lines along Z axis
+        for x in 0..3 {
+            for y in 0..3 {
+                if self.check_line([(x, y, 0), (x, y, 1), (x, y, 2)]) {
+                    return true;
+                }
+            }
+        }
+
+        // Face diagonals on XY planes
+        for z in 0..3 {
+            if self.check_line([(0, 0, z), (1, 1, z), (2, 2, z)]) ||
+               self.check_line([(0, 2, z), (1, 1, z), (2, 0, z)]) {
+                return true;
+            }
+        }
+
+        // Face diagonals on XZ planes
+        for y in 0..3 {
+            if self.check_line([(0, y, 0), (1, y, 1), (2, y, 2)]) ||
+               self.check_line([(0, y, 2), (1, y, 1), (2, y, 0)]) {
+                return true;
+            }
+        }
+
+        // Face diagonals on YZ planes
+        for x in 0..3 {
+            if self.check_line([(x, 0, 0), (x, 1, 1), (x, 2, 2)]) ||
+               self.check_line([(x, 0, 2), (x, 1, 1), (x, 2, 0)]) {
+                return true;
+            }
+        }
+
+        // 3D diagonals (corner to corner)
+        if self.check_line([(0, 0, 0), (1, 1, 1), (2, 2, 2)]) ||
+           self.check_line([(0, 0, 2), (1, 1, 1), (2, 2, 0)]) ||
+           self.check_line([(0, 2, 0), (1, 1, 1), (2, 0, 2)]) ||
+           self.check_line([(0, 2, 2), (1, 1, 1), (2, 0, 0)]) {
+            return true;
+        }
+
+        false
+    }
+
+    fn check_line(&self, positions: [(usize, usize, usize); 3]) -> bool {
+        let cells = [
+            self.board[positions[0].0][positions[0].1][positions[0].2],
+            self.board[positions[1].0][positions[1].1][positions[1].2],
+            self.board[positions[2].0][positions[2].1][positions[2].2],
+        ];
+
+        cells[0].is_piece() && cells[0] == cells[1] && cells[1] == cells[2]
+    }
+
+    fn is_board_full(&self) -> bool {
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    if self.board[x][y][z] == CellState::Empty {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Every empty cell
+    pub fn get_empty_positions(&self) -> Vec<(usize, usize, usize)> {
+        let mut positions = Vec::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    if self.board[x][y][z] == CellState::Empty {
+                        positions.push((x, y, z));
+                    }
+                }
+            }
+        }
+        positions
+    }
+
+    /// Start a new game under the same rules, with `first_player` to move
+    pub fn reset(&mut self) {
+        self.board = [[[CellState::Empty; 3]; 3]; 3];
+        self.current_player = self.first_player;
+        self.game_over = false;
+        self.winner = None;
+        self.selected_cube = None;
+        self.last_move = None;
+        self.move_history.clear();
+        self.delegated_moves.clear();
+        self.explanations.clear();
+        self.placements = Placements::default();
+        self.last_removed = None;
+        self.scores = [0; 3];
+        self.winning_line = None;
+        self.game_over_reason = None;
+        self.generation = self.generation.wrapping_add(1);
+        self.phase = GamePhase::Playing;
+        self.swapped = false;
+        self.passes_used = [0; 3];
+        self.passes.clear();
+        self.snapshots.clear();
+        self.block_random_cells();
+        self.start_board = self.board;
+        self.start_player = self.current_player;
+    }
+
+    fn block_random_cells(&mut self) {
+        let mut positions = self.get_empty_positions();
+        positions.shuffle(&mut rand::thread_rng());
+        for &(x, y, z) in positions.iter().take(self.rules.blocked_cells) {
+            self.board[x][y][z] = CellState::Blocked;
+        }
+    }
+
+    /// Manually block a cell while setting up a handicap, before anyone has moved
+    pub fn block_cell(&mut self, x: usize, y: usize, z: usize) -> bool {
+        if !self.move_history.is_empty() || self.board[x][y][z] != CellState::Empty {
+            return false;
+        }
+        self.board[x][y][z] = CellState::Blocked;
+        self.start_board = self.board;
+        // Passes made before the block would otherwise rewind it away
+        self.snapshots.clear();
+        true
+    }
+} 
\ No newline at end of file
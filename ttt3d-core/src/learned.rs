@@ -0,0 +1,190 @@
+//! [`LinearEvaluator`], an [`Evaluator`] whose weights are fitted
+//! to self-play results, and the position features it weighs
+
+use serde::{Deserialize, Serialize};
+use crate::ai::SearchState;
+use crate::game::{line_index, CellState};
+use crate::network::{cell_index, Evaluator, NetworkOutput};
+
+/// What the evaluator looks at, all from one player's point of view:
+///   0: always 1, so a weight can stand for simply having the move
+///   1, 2: lines holding one or two of the player's pieces and nothing else
+///   3, 4: the same for the opponent's pieces
+///   5, 6: the center held by the player, or by the opponent
+///   7, 8: corners held by the player, or by the opponent
+pub const FEATURE_COUNT: usize = 9;
+
+// Gradient descent settings for `LinearEvaluator::fit`
+const LEARNING_RATE: f64 = 0.05;
+const EPOCHS: u32 = 2000;
+// Pulls every weight towards 0 a little, so features the games rarely show stay small
+const WEIGHT_DECAY: f64 = 1e-4;
+
+/// A position from a finished game, as features for the side to move, and how the game went for
+/// that side: 1 won, -1 lost, 0 drawn
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    /// The position's features, from `features`
+    pub features: [f64; FEATURE_COUNT],
+    /// 1 if the side to move went on to win, -1 if it lost, 0 for a draw
+    pub outcome: f64,
+}
+
+impl Sample {
+    /// A sample of the position, for the side to move in it
+    pub fn new(state: &SearchState, outcome: f64) -> Self {
+        Self {
+            features: features(&state.board, state.to_move.cell()),
+            outcome,
+        }
+    }
+}
+
+/// An evaluation learned from self-play: the value is tanh of a weighted sum of `features`, and
+/// each move's prior is how that value looks after playing it. Cheap enough to ask at every node,
+/// and written to a file as plain weights
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinearEvaluator {
+    /// One weight per feature
+    pub weights: [f64; FEATURE_COUNT],
+}
+
+impl LinearEvaluator {
+    // The weights' verdict before squashing, for `mine` to move
+    fn score(&self, board: &[[[CellState; 3]; 3]; 3], mine: CellState) -> f64 {
+        self.weights.iter().zip(features(board, mine)).map(|(weight, feature)| weight * feature).sum()
+    }
+
+    /// Least squares on tanh of the weighted features against each sample's outcome, by full-batch
+    /// gradient descent from all-zero weights
+    pub fn fit(samples: &[Sample]) -> Self {
+        let mut weights = [0.0; FEATURE_COUNT];
+        if samples.is_empty() {
+            return Self { weights };
+        }
+
+        for _ in 0..EPOCHS {
+            let mut gradient = [0.0; FEATURE_COUNT];
+            for sample in samples {
+                let value = weights.iter().zip(sample.features).map(|(weight, feature)| weight * feature).sum::<f64>().tanh();
+                // d/dw of (tanh(w.f) - outcome)^2, dropping the constant 2
+                let slope = (value - sample.outcome) * (1.0 - value * value);
+                for (total, feature) in gradient.iter_mut().zip(sample.features) {
+                    *total += slope * feature;
+                }
+            }
+            for (weight, total) in weights.iter_mut().zip(gradient) {
+                *weight -= LEARNING_RATE * (total / samples.len() as f64 + WEIGHT_DECAY * *weight);
+            }
+        }
+        Self { weights }
+    }
+
+    /// Mean squared error against the samples' outcomes, for reporting how well a fit went
+    pub fn error(&self, samples: &[Sample]) -> f64 {
+        let total: f64 = samples
+            .iter()
+            .map(|sample| {
+                let value = self.weights.iter().zip(sample.features).map(|(weight, feature)| weight * feature).sum::<f64>().tanh();
+                (value - sample.outcome).powi(2)
+            })
+            .sum();
+        total / samples.len().max(1) as f64
+    }
+}
+
+impl Evaluator for LinearEvaluator {
+    // Only meaningful for two players, which is all the search asks a network about
+    fn evaluate(&self, state: &SearchState) -> Option<NetworkOutput> {
+        let mine = state.to_move.cell();
+        let theirs = state.to_move.next(2).cell();
+
+        // A move's score is the opponent's score after it, negated; a move that completes a
+        // line takes all of the prior
+        let mut scores = [f64::NEG_INFINITY; 27];
+        let mut winning = Vec::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    if state.board[x][y][z] != CellState::Empty {
+                        continue;
+                    }
+                    let mut after = state.board;
+                    after[x][y][z] = mine;
+                    let completes = line_index()
+                        .lines_through(x, y, z)
+                        .any(|line| line.iter().all(|&(lx, ly, lz)| after[lx][ly][lz] == mine));
+                    if completes {
+                        winning.push(cell_index((x, y, z)));
+                    }
+                    scores[cell_index((x, y, z))] = -self.score(&after, theirs);
+                }
+            }
+        }
+
+        let mut priors = [0.0; 27];
+        if !winning.is_empty() {
+            for index in winning {
+                priors[index] = 1.0;
+            }
+        } else {
+            // Softmax over the empty cells; filled ones stay at 0
+            let best = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            if best.is_finite() {
+                let total: f64 = scores.iter().map(|score| (score - best).exp()).sum();
+                for (prior, score) in priors.iter_mut().zip(scores) {
+                    *prior = (score - best).exp() / total;
+                }
+            }
+        }
+
+        Some(NetworkOutput {
+            value: self.score(&state.board, mine).tanh(),
+            priors,
+        })
+    }
+}
+
+/// The features of `board` for the player whose pieces are `mine`. Blocked cells and shared
+/// pieces belong to nobody, so a line holding one counts for neither side
+pub fn features(board: &[[[CellState; 3]; 3]; 3], mine: CellState) -> [f64; FEATURE_COUNT] {
+    let mut features = [0.0; FEATURE_COUNT];
+    features[0] = 1.0;
+
+    for line in &line_index().lines {
+        let (mut own, mut other, mut dead) = (0, 0, false);
+        for &(x, y, z) in line {
+            match board[x][y][z] {
+                CellState::Empty => {}
+                cell if cell == mine => own += 1,
+                cell if cell.owner().is_some() => other += 1,
+                _ => dead = true,
+            }
+        }
+        match (own, other, dead) {
+            (1 | 2, 0, false) => features[own] += 1.0,
+            (0, 1 | 2, false) => features[2 + other] += 1.0,
+            _ => {}
+        }
+    }
+
+    let side = |cell: CellState| match cell {
+        CellState::Empty => None,
+        cell if cell == mine => Some(0),
+        cell if cell.owner().is_some() => Some(1),
+        _ => None,
+    };
+    if let Some(side) = side(board[1][1][1]) {
+        features[5 + side] += 1.0;
+    }
+    for x in [0, 2] {
+        for y in [0, 2] {
+            for z in [0, 2] {
+                if let Some(side) = side(board[x][y][z]) {
+                    features[7 + side] += 1.0;
+                }
+            }
+        }
+    }
+    features
+}
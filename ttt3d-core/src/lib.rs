@@ -0,0 +1,54 @@
+//! The rules and AI of 3D tic-tac-toe on a 3x3x3 board, with no front-end attached, so the
+//! game, a terminal client, a web build or a bot can all share one implementation.
+//!
+//! # Stability
+//!
+//! Every `pub` item reachable from the modules below is part of the API and follows semver:
+//! while the version is 0.x, breaking changes bump the minor version and additions bump the
+//! patch. Anything not `pub` may change at any time. Every public item is documented, and the
+//! `missing_docs` lint keeps it that way.
+//!
+//! - [`game`]: cells, players, rule variants, the 49 winning lines, and [`game::GameState`],
+//!   which plays moves and decides results under the chosen rules
+//! - [`ai`]: the [`ai::Engine`] trait, the Monte Carlo tree search engine, and
+//!   [`ai::EngineKind`] for building an engine at a [`Difficulty`]
+//! - [`alphabeta`]: the iterative-deepening alpha-beta engine
+//! - [`explain`]: short explanations of a move from the lines it completes, blocks or threatens
+//! - [`learned`]: [`learned::LinearEvaluator`], an [`network::Evaluator`] whose weights are
+//!   fitted to self-play results, and the position features it weighs
+//! - [`symmetry`]: the cube's 48 symmetries and canonical position keys
+//! - [`threats`]: cells where a player would complete a line or fork, shared by the engines'
+//!   shortcuts and move explanations
+//! - [`network`]: the [`network::Evaluator`] trait for value/policy networks that steer MCTS,
+//!   and the input encoding they read
+//! - [`notation`]: the three-layer text notation puzzles and quiz positions are written in
+//! - [`solver`]: exhaustive forced-win search (feature `solver`, on by default)
+//!
+//! # Features
+//!
+//! - `solver` (default): the forced-win search. Heavier precomputed components, such as a
+//!   tablebase, would go behind features of their own in the same way.
+//! - `onnx`: [`network::OnnxEvaluator`], which runs a model file with ONNX Runtime. The
+//!   runtime library is loaded when a model is, not linked, so builds need nothing extra.
+//! - `bevy`: derives Bevy's `Resource` for [`game::GameState`], so a Bevy app can hold it
+//!   directly. Nothing else in the crate depends on Bevy.
+
+// Every public item is part of the documented API
+#![warn(missing_docs)]
+// The board is indexed as board[x][y][z] throughout
+#![allow(clippy::needless_range_loop)]
+
+pub mod ai;
+pub mod alphabeta;
+pub mod difficulty;
+pub mod explain;
+pub mod game;
+pub mod learned;
+pub mod network;
+pub mod notation;
+#[cfg(feature = "solver")]
+pub mod solver;
+pub mod symmetry;
+pub mod threats;
+
+pub use difficulty::Difficulty;
@@ -0,0 +1,138 @@
+//! The [`Evaluator`] trait for value/policy networks that steer MCTS, and the input encoding
+//! they read
+
+use crate::ai::SearchState;
+use crate::game::{CellState, Position};
+
+/// Three planes of 27 cells, each cell at x * 9 + y * 3 + z: the side to move's pieces, every
+/// other piece a player owns or shares, and cells nobody can play
+pub const INPUT_SIZE: usize = 81;
+
+/// What a value/policy network makes of a position, from the side to move's point of view
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkOutput {
+    /// Expected result for the side to move, from -1 (loss) to 1 (win)
+    pub value: f64,
+    /// How promising each cell looks, indexed like the input planes; only the empty cells
+    /// matter, and they needn't sum to 1
+    pub priors: [f64; 27],
+}
+
+impl NetworkOutput {
+    /// The prior of one cell
+    pub fn prior(&self, (x, y, z): Position) -> f64 {
+        self.priors[cell_index((x, y, z))]
+    }
+}
+
+/// Anything that can score a position for the tree search. Returning `None` leaves the position
+/// to the rollouts, so a model that fails on some input costs strength rather than the game
+pub trait Evaluator: Send + Sync {
+    /// The network's view of a position, or `None` to leave it to the rollouts
+    fn evaluate(&self, state: &SearchState) -> Option<NetworkOutput>;
+}
+
+/// A cell's place in an input plane and in `NetworkOutput::priors`
+pub fn cell_index((x, y, z): Position) -> usize {
+    x * 9 + y * 3 + z
+}
+
+/// The input planes for a position, with the side to move always in the first plane so the
+/// network needn't learn each seat separately
+pub fn encode(state: &SearchState) -> [f32; INPUT_SIZE] {
+    let mine = state.to_move.cell();
+    let mut input = [0.0; INPUT_SIZE];
+    for x in 0..3 {
+        for y in 0..3 {
+            for z in 0..3 {
+                let plane = match state.board[x][y][z] {
+                    CellState::Empty => continue,
+                    cell if cell == mine => 0,
+                    CellState::Blocked => 2,
+                    _ => 1,
+                };
+                input[plane * 27 + cell_index((x, y, z))] = 1.0;
+            }
+        }
+    }
+    input
+}
+
+#[cfg(feature = "onnx")]
+pub use onnx::OnnxEvaluator;
+
+#[cfg(feature = "onnx")]
+mod onnx {
+    use super::{encode, Evaluator, NetworkOutput, INPUT_SIZE};
+    use crate::ai::SearchState;
+    use ort::session::Session;
+    use ort::value::Tensor;
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    /// A value/policy network run with ONNX Runtime, which is loaded from the system at run
+    /// time (`ORT_DYLIB_PATH`, or `onnxruntime` next to the executable).
+    ///
+    /// The model takes one float input of shape [1, 81], laid out as `encode` writes it, and
+    /// gives two float outputs: `value`, shape [1, 1], and `policy`, shape [1, 27], as logits
+    pub struct OnnxEvaluator {
+        // Running a session needs it mutably
+        session: Mutex<Session>,
+    }
+
+    impl OnnxEvaluator {
+        /// Fails, rather than panicking, when either the model or ONNX Runtime itself is missing
+        /// or unusable, so callers can carry on without a network
+        pub fn load(path: &Path) -> Result<Self, String> {
+            if !path.is_file() {
+                return Err(format!("no model file at {:?}", path));
+            }
+            // ONNX Runtime panics if its library can't be loaded
+            let session = std::panic::catch_unwind(|| Session::builder()?.commit_from_file(path))
+                .map_err(|_| "ONNX Runtime could not be loaded".to_string())?
+                .map_err(|err| format!("invalid model: {}", err))?;
+
+            let evaluator = Self { session: Mutex::new(session) };
+            // Try it once on the empty board so a model with the wrong shape is refused here
+            // rather than on every position of every search
+            evaluator.run(&[0.0; INPUT_SIZE])?;
+            Ok(evaluator)
+        }
+
+        fn run(&self, input: &[f32; INPUT_SIZE]) -> Result<NetworkOutput, String> {
+            let tensor = Tensor::from_array(([1usize, INPUT_SIZE], input.to_vec())).map_err(|err| err.to_string())?;
+            let mut session = self.session.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let outputs = session.run(ort::inputs![tensor]).map_err(|err| err.to_string())?;
+
+            let extract = |name: &str, len: usize| -> Result<Vec<f32>, String> {
+                let output = outputs.get(name).ok_or_else(|| format!("the model has no `{}` output", name))?;
+                let (_, values) = output.try_extract_tensor::<f32>().map_err(|err| err.to_string())?;
+                if values.len() != len {
+                    return Err(format!("`{}` has {} values, expected {}", name, values.len(), len));
+                }
+                Ok(values.to_vec())
+            };
+            let value = extract("value", 1)?;
+            let policy = extract("policy", 27)?;
+
+            // Softmax the logits; illegal cells are ignored by the search anyway
+            let max = policy.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let weights: Vec<f64> = policy.iter().map(|&logit| ((logit - max) as f64).exp()).collect();
+            let total: f64 = weights.iter().sum();
+            let mut priors = [0.0; 27];
+            for (prior, weight) in priors.iter_mut().zip(weights) {
+                *prior = weight / total;
+            }
+            Ok(NetworkOutput {
+                value: (value[0] as f64).clamp(-1.0, 1.0),
+                priors,
+            })
+        }
+    }
+
+    impl Evaluator for OnnxEvaluator {
+        fn evaluate(&self, state: &SearchState) -> Option<NetworkOutput> {
+            self.run(&encode(state)).ok()
+        }
+    }
+}
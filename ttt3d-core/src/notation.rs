@@ -0,0 +1,40 @@
+//! The three-layer text notation puzzles and quiz positions are written in
+
+use crate::game::{line_index, CellState};
+
+/// A board in the notation's own terms, indexed `board[x][y][z]`
+pub type Board = [[[CellState; 3]; 3]; 3];
+
+/// Positions are written as three layers (z), each three rows (y) of three cells (x):
+/// 'H' human, 'A' AI, '.' empty
+pub fn parse_board(layers: &[Vec<String>]) -> Result<Board, String> {
+    if layers.len() != 3 || layers.iter().any(|rows| rows.len() != 3) {
+        return Err("the board needs 3 layers of 3 rows".to_string());
+    }
+
+    let mut board = [[[CellState::Empty; 3]; 3]; 3];
+    for (z, rows) in layers.iter().enumerate() {
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != 3 {
+                return Err(format!("row '{}' must have 3 cells", row));
+            }
+            for (x, cell) in row.chars().enumerate() {
+                board[x][y][z] = match cell {
+                    'H' => CellState::Human,
+                    'A' => CellState::AI,
+                    '.' => CellState::Empty,
+                    other => return Err(format!("unknown cell '{}'", other)),
+                };
+            }
+        }
+    }
+    Ok(board)
+}
+
+/// Whether `piece` fills any line
+pub fn has_line(board: &Board, piece: CellState) -> bool {
+    line_index()
+        .lines
+        .iter()
+        .any(|line| line.iter().all(|&(x, y, z)| board[x][y][z] == piece))
+}
@@ -0,0 +1,61 @@
+//! Exhaustive forced-win search over two-player standard-rules positions, as used to verify
+//! puzzles and quiz positions
+
+use crate::game::{CellState, Position};
+use crate::notation::{has_line, Board};
+
+fn empty_cells(board: &Board) -> Vec<Position> {
+    let mut cells = Vec::new();
+    for x in 0..3 {
+        for y in 0..3 {
+            for z in 0..3 {
+                if board[x][y][z] == CellState::Empty {
+                    cells.push((x, y, z));
+                }
+            }
+        }
+    }
+    cells
+}
+
+/// Whether the human, to move, can force a win within `moves` of their own moves
+pub fn forced_win(board: &mut Board, moves: u32) -> bool {
+    for (x, y, z) in empty_cells(board) {
+        board[x][y][z] = CellState::Human;
+        let wins = has_line(board, CellState::Human)
+            || (moves > 1 && !empty_cells(board).is_empty() && refutation(board, moves - 1).is_none());
+        board[x][y][z] = CellState::Empty;
+        if wins {
+            return true;
+        }
+    }
+    false
+}
+
+/// The human's first moves that each force a win within `moves`; a sound puzzle has exactly one
+pub fn winning_moves(board: &mut Board, moves: u32) -> Vec<Position> {
+    let mut winning = Vec::new();
+    for (x, y, z) in empty_cells(board) {
+        board[x][y][z] = CellState::Human;
+        let wins = has_line(board, CellState::Human)
+            || (moves > 1 && !empty_cells(board).is_empty() && refutation(board, moves - 1).is_none());
+        board[x][y][z] = CellState::Empty;
+        if wins {
+            winning.push((x, y, z));
+        }
+    }
+    winning
+}
+
+/// With the AI to move, a reply that escapes a forced win in `moves`, if there is one
+pub fn refutation(board: &mut Board, moves: u32) -> Option<Position> {
+    for (x, y, z) in empty_cells(board) {
+        board[x][y][z] = CellState::AI;
+        let escapes = has_line(board, CellState::AI) || !forced_win(board, moves);
+        board[x][y][z] = CellState::Empty;
+        if escapes {
+            return Some((x, y, z));
+        }
+    }
+    None
+}
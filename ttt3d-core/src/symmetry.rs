@@ -0,0 +1,187 @@
+//! The cube's 48 symmetries and canonical position keys
+
+use std::sync::OnceLock;
+use crate::ai::SearchState;
+use crate::game::{CellState, Position, Rules};
+
+/// Identifies a search position up to the cube's symmetries; two positions get the same key
+/// exactly when one is a rotation or reflection of the other
+pub type PositionKey = Vec<u8>;
+
+/// One of the cube's 48 symmetries: a permutation of the axes, then a reflection of any of them.
+/// Every one maps the board's lines onto lines, so symmetric positions play out identically
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Symmetry {
+    axes: [usize; 3],
+    flips: [bool; 3],
+}
+
+impl Symmetry {
+    /// Where a cell goes under the symmetry
+    pub fn apply(&self, (x, y, z): Position) -> Position {
+        let coords = [x, y, z];
+        let axis = |i: usize| {
+            let value = coords[self.axes[i]];
+            if self.flips[i] { 2 - value } else { value }
+        };
+        (axis(0), axis(1), axis(2))
+    }
+
+    /// The cell that `apply` takes to the given one
+    pub fn invert(&self, (x, y, z): Position) -> Position {
+        let mapped = [x, y, z];
+        let mut coords = [0; 3];
+        for i in 0..3 {
+            coords[self.axes[i]] = if self.flips[i] { 2 - mapped[i] } else { mapped[i] };
+        }
+        (coords[0], coords[1], coords[2])
+    }
+}
+
+/// All 48 symmetries, the identity first
+pub fn symmetries() -> &'static [Symmetry] {
+    static SYMMETRIES: OnceLock<Vec<Symmetry>> = OnceLock::new();
+    SYMMETRIES.get_or_init(|| {
+        let permutations = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        let mut symmetries = Vec::with_capacity(48);
+        for axes in permutations {
+            for bits in 0..8 {
+                let flips = [bits & 1 != 0, bits & 2 != 0, bits & 4 != 0];
+                symmetries.push(Symmetry { axes, flips });
+            }
+        }
+        symmetries
+    })
+}
+
+fn cell_code(cell: CellState) -> u8 {
+    match cell {
+        CellState::Empty => 0,
+        CellState::Human => 1,
+        CellState::AI => 2,
+        CellState::Third => 3,
+        CellState::Neutral => 4,
+        CellState::Blocked => 5,
+    }
+}
+
+fn cell_index((x, y, z): Position) -> u8 {
+    (x * 9 + y * 3 + z) as u8
+}
+
+// The state as seen through `symmetry`, flattened to bytes
+fn encode(state: &SearchState, symmetry: &Symmetry) -> PositionKey {
+    let mut cells = [0; 27];
+    for x in 0..3 {
+        for y in 0..3 {
+            for z in 0..3 {
+                cells[cell_index(symmetry.apply((x, y, z))) as usize] = cell_code(state.board[x][y][z]);
+            }
+        }
+    }
+
+    let mut key = cells.to_vec();
+    key.push(state.to_move.index() as u8);
+    key.extend((state.moves_made as u32).to_le_bytes());
+    for score in state.scores {
+        key.extend(score.to_le_bytes());
+    }
+    // The order pieces fade in matters, so placements are kept in sequence
+    for queue in &state.placements {
+        key.push(queue.len() as u8);
+        key.extend(queue.iter().map(|&position| cell_index(symmetry.apply(position))));
+    }
+    key
+}
+
+/// The smallest encoding over all 48 symmetries, and the symmetry that produces it
+pub fn canonical(state: &SearchState) -> (PositionKey, Symmetry) {
+    symmetries()
+        .iter()
+        .map(|symmetry| (encode(state, symmetry), *symmetry))
+        .min_by(|a, b| a.0.cmp(&b.0))
+        .expect("the cube has symmetries")
+}
+
+/// Whether two moves from `state` lead to positions that are rotations or reflections of each other
+pub fn equivalent_moves(state: &SearchState, a: Position, b: Position, rules: &Rules) -> bool {
+    let after = |position| {
+        let mut state = state.clone();
+        state.play(position, rules);
+        canonical(&state).0
+    };
+    a == b || after(a) == after(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Player;
+
+    fn position(moves: &[Position]) -> SearchState {
+        let mut state = SearchState {
+            board: [[[CellState::Empty; 3]; 3]; 3],
+            placements: Default::default(),
+            scores: [0; 3],
+            moves_made: 0,
+            to_move: Player::Human,
+        };
+        for &position in moves {
+            state.play(position, &Rules::default());
+        }
+        state
+    }
+
+    // The same position turned or reflected by `symmetry`
+    fn image(state: &SearchState, symmetry: &Symmetry) -> SearchState {
+        let mut image = state.clone();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    let (ix, iy, iz) = symmetry.apply((x, y, z));
+                    image.board[ix][iy][iz] = state.board[x][y][z];
+                }
+            }
+        }
+        for queue in image.placements.iter_mut() {
+            for position in queue.iter_mut() {
+                *position = symmetry.apply(*position);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn invert_undoes_apply() {
+        for symmetry in symmetries() {
+            for x in 0..3 {
+                for y in 0..3 {
+                    for z in 0..3 {
+                        assert_eq!(symmetry.invert(symmetry.apply((x, y, z))), (x, y, z), "{:?}", symmetry);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_image_has_the_same_key() {
+        let state = position(&[(0, 0, 0), (1, 0, 0), (2, 1, 0), (0, 1, 2)]);
+        let (key, _) = canonical(&state);
+        for symmetry in symmetries() {
+            assert_eq!(canonical(&image(&state, symmetry)).0, key, "{:?}", symmetry);
+        }
+    }
+
+    #[test]
+    fn different_positions_have_different_keys() {
+        let corner = position(&[(0, 0, 0)]);
+        let center = position(&[(1, 1, 1)]);
+        assert_ne!(canonical(&corner).0, canonical(&center).0);
+
+        // Two pieces each, the second beside the corner on an edge or in the middle of a face
+        let edge = position(&[(0, 0, 0), (1, 0, 0)]);
+        let face = position(&[(0, 0, 0), (1, 1, 0)]);
+        assert_ne!(canonical(&edge).0, canonical(&face).0);
+    }
+}
@@ -0,0 +1,87 @@
+//! Cells where a player would complete a line or fork, shared by the engines' shortcuts and
+//! move explanations
+
+use crate::game::{line_index, CellState, Line, Player, Position};
+
+type Board = [[[CellState; 3]; 3]; 3];
+
+/// The empty cells where `player`'s next piece would complete a line, each listed once however
+/// many lines it would complete. Every one of them is a threat the other side has to answer
+pub fn threats(board: &Board, player: Player) -> Vec<Position> {
+    let mut cells = Vec::new();
+    for (_, cell) in threat_lines(board, player) {
+        if !cells.contains(&cell) {
+            cells.push(cell);
+        }
+    }
+    cells
+}
+
+/// Every line holding two of `player`'s pieces and an empty third cell, with that empty cell
+pub fn threat_lines(board: &Board, player: Player) -> Vec<(Line, Position)> {
+    let piece = player.cell();
+    let mut lines = Vec::new();
+    for line in &line_index().lines {
+        let mut own = 0;
+        let mut empty = None;
+        for &(x, y, z) in line {
+            match board[x][y][z] {
+                CellState::Empty => empty = Some((x, y, z)),
+                cell if cell == piece => own += 1,
+                _ => {}
+            }
+        }
+        if let (2, Some(cell)) = (own, empty) {
+            lines.push((*line, cell));
+        }
+    }
+    lines
+}
+
+/// How many lines through the piece at `position` its owner could still complete: those with no
+/// other piece or blocked cell on them
+pub fn live_lines(board: &Board, (x, y, z): Position) -> usize {
+    let piece = board[x][y][z];
+    line_index()
+        .lines_through(x, y, z)
+        .filter(|line| line.iter().all(|&(x, y, z)| board[x][y][z] == piece || board[x][y][z] == CellState::Empty))
+        .count()
+}
+
+/// The empty cells where `player`'s piece would make two threats at once: a fork, which one
+/// reply can't stop
+pub fn forks(board: &Board, player: Player) -> Vec<Position> {
+    empty_cells(board)
+        .filter(|&(x, y, z)| {
+            let mut after = *board;
+            after[x][y][z] = player.cell();
+            threats(&after, player).len() >= 2
+        })
+        .collect()
+}
+
+/// A move for `player` that keeps `opponent` from forking next turn. A single fork cell is simply
+/// taken. With several, no one cell covers them all, so instead this looks for a threat of
+/// `player`'s own whose forced reply doesn't land on a fork. `None` if there is nothing to stop,
+/// or nothing that stops it
+pub fn fork_block(board: &Board, player: Player, opponent: Player) -> Option<Position> {
+    let opponent_forks = forks(board, opponent);
+    match opponent_forks.as_slice() {
+        [] => None,
+        [cell] => Some(*cell),
+        _ => empty_cells(board).find(|&(x, y, z)| {
+            let mut after = *board;
+            after[x][y][z] = player.cell();
+            match threats(&after, player).as_slice() {
+                [reply] => !forks(&after, opponent).contains(reply),
+                _ => false,
+            }
+        }),
+    }
+}
+
+fn empty_cells(board: &Board) -> impl Iterator<Item = Position> + '_ {
+    (0..27)
+        .map(|index| (index / 9, index / 3 % 3, index % 3))
+        .filter(|&(x, y, z)| board[x][y][z] == CellState::Empty)
+}
@@ -0,0 +1,94 @@
+use ttt3d_core::ai::EngineKind;
+use ttt3d_core::game::{Player, Rules};
+use ttt3d_core::Difficulty;
+
+const USAGE: &str = "\
+Usage: ttt3d-tui [OPTIONS]
+
+Options:
+  --difficulty <easy|normal|hard|expert>     AI search strength
+  --engine <mcts|strict|alphabeta>           Which AI engine to play against
+  --variant <classic|notakto|fading|scoring|pass>
+                                             Rule variant
+  --ai-first                                 Let the AI open every game
+  --seed <n>                                 Seed for the AI, so its moves can be reproduced
+  --help                                     Print this message";
+
+// Passes each player gets under `--variant pass`
+const VARIANT_PASSES: u32 = 2;
+
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    pub difficulty: Difficulty,
+    pub engine: EngineKind,
+    pub rules: Rules,
+    pub first_player: Option<Player>,
+    pub seed: Option<u64>,
+}
+
+impl Options {
+    // Parse the process arguments, printing usage and exiting on --help or a malformed flag
+    pub fn from_env() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(Some(options)) => options,
+            Ok(None) => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("error: {}\n\n{}", err, USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // Returns Ok(None) when --help was requested
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Options::default();
+
+        while let Some(flag) = args.next() {
+            let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+
+            match flag.as_str() {
+                "--help" | "-h" => return Ok(None),
+                "--ai-first" => options.first_player = Some(Player::AI),
+                "--difficulty" => {
+                    options.difficulty = match value("--difficulty")?.as_str() {
+                        "easy" => Difficulty::Easy,
+                        "normal" => Difficulty::Normal,
+                        "hard" => Difficulty::Hard,
+                        "expert" => Difficulty::Expert,
+                        other => return Err(format!("unknown difficulty '{}'", other)),
+                    };
+                }
+                "--engine" => {
+                    let engine = value("--engine")?;
+                    options.engine = EngineKind::from_id(&engine)
+                        .ok_or_else(|| format!("unknown engine '{}' (expected one of {})", engine, EngineKind::ids()))?;
+                }
+                "--variant" => {
+                    let variant = value("--variant")?;
+                    if !["classic", "notakto", "fading", "scoring", "pass"].contains(&variant.as_str()) {
+                        return Err(format!("unknown variant '{}'", variant));
+                    }
+                    options.rules = Rules {
+                        notakto: variant == "notakto",
+                        fading: variant == "fading",
+                        line_scoring: variant == "scoring",
+                        passes: if variant == "pass" { VARIANT_PASSES } else { 0 },
+                        ..options.rules
+                    };
+                }
+                "--seed" => {
+                    let seed = value("--seed")?
+                        .parse()
+                        .map_err(|_| "--seed needs a non-negative number".to_string())?;
+                    options.seed = Some(seed);
+                }
+                other => return Err(format!("unknown option '{}'", other)),
+            }
+        }
+
+        Ok(Some(options))
+    }
+}
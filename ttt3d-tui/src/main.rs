@@ -0,0 +1,310 @@
+// A terminal front-end on the shared core: the three layers side by side, moved around with the
+// keyboard, against the same engines as the 3D game. Plain text and key presses only, so it
+// works over SSH and on servers without a display
+
+// The board is indexed as board[x][y][z] throughout
+#![allow(clippy::needless_range_loop)]
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, PrintStyledContent, Stylize};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+use ttt3d_core::ai::EngineKind;
+use ttt3d_core::game::{CellState, GameState, Player, Position, FADING_PIECE_LIMIT};
+use ttt3d_core::Difficulty;
+
+mod cli;
+
+use cli::Options;
+
+// How often the screen refreshes while the AI thinks, for the spinner
+const TICK: Duration = Duration::from_millis(100);
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+const KEYS: &str = "arrows/hjkl move   tab or [ ] change layer   1-3 go to layer   enter/space place   p pass   n new game   q quit";
+
+// Puts the terminal into raw mode on an alternate screen, and restores it when dropped, even
+// if the game panics
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// What the AI decided to do with its turn
+enum Reply {
+    Place(Position),
+    Pass,
+}
+
+struct App {
+    game: GameState,
+    engine: EngineKind,
+    difficulty: Difficulty,
+    // The cell the next move goes in, as (x, y, z) with z the layer
+    cursor: Position,
+    // The AI's reply being searched on another thread, tagged with the game it is for
+    thinking: Option<(u64, Receiver<Option<Reply>>)>,
+    ticks: usize,
+    message: String,
+}
+
+impl App {
+    fn new(options: &Options) -> Self {
+        let mut game = GameState {
+            ai: options.engine.build(options.difficulty, None, None, options.seed),
+            rules: options.rules,
+            first_player: options.first_player.unwrap_or(Player::Human),
+            ..GameState::default()
+        };
+        game.reset();
+        Self {
+            game,
+            engine: options.engine,
+            difficulty: options.difficulty,
+            cursor: (1, 1, 1),
+            thinking: None,
+            ticks: 0,
+            message: String::new(),
+        }
+    }
+
+    fn new_game(&mut self) {
+        self.game.reset();
+        self.thinking = None;
+        self.message.clear();
+    }
+
+    // Start the AI's search if it is to move, and play its move once the search is done;
+    // returns whether the board changed
+    fn update_ai(&mut self) -> bool {
+        if self.game.game_over || !self.game.is_ai_turn() {
+            return false;
+        }
+
+        match &self.thinking {
+            None => {
+                let (sender, receiver) = mpsc::channel();
+                let snapshot = self.game.clone();
+                thread::spawn(move || {
+                    let reply = if snapshot.ai.should_pass(&snapshot) {
+                        Some(Reply::Pass)
+                    } else {
+                        snapshot.ai.best_move(&snapshot, None).map(Reply::Place)
+                    };
+                    let _ = sender.send(reply);
+                });
+                self.thinking = Some((self.game.generation, receiver));
+                false
+            }
+            Some((generation, receiver)) => {
+                let Ok(reply) = receiver.try_recv() else {
+                    return false;
+                };
+                let generation = *generation;
+                self.thinking = None;
+                match reply {
+                    Some(Reply::Place((x, y, z))) => self.game.make_explained_move(generation, x, y, z),
+                    Some(Reply::Pass) => generation == self.game.generation && self.game.pass(),
+                    None => false,
+                }
+            }
+        }
+    }
+
+    // Returns false once the player asks to quit
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let (x, y, z) = self.cursor;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('n') => self.new_game(),
+            KeyCode::Left | KeyCode::Char('h') => self.cursor.0 = (x + 2) % 3,
+            KeyCode::Right | KeyCode::Char('l') => self.cursor.0 = (x + 1) % 3,
+            KeyCode::Up | KeyCode::Char('k') => self.cursor.1 = (y + 2) % 3,
+            KeyCode::Down | KeyCode::Char('j') => self.cursor.1 = (y + 1) % 3,
+            KeyCode::Tab | KeyCode::Char(']') => self.cursor.2 = (z + 1) % 3,
+            KeyCode::BackTab | KeyCode::Char('[') => self.cursor.2 = (z + 2) % 3,
+            KeyCode::Char(digit @ '1'..='3') => self.cursor.2 = digit as usize - '1' as usize,
+            KeyCode::Enter | KeyCode::Char(' ') => self.place(),
+            KeyCode::Char('p') => self.pass(),
+            _ => {}
+        }
+        true
+    }
+
+    fn place(&mut self) {
+        if self.game.game_over || self.game.is_ai_turn() {
+            return;
+        }
+        let (x, y, z) = self.cursor;
+        self.message = if self.game.make_move(x, y, z) {
+            String::new()
+        } else {
+            "That cell is taken.".to_string()
+        };
+    }
+
+    fn pass(&mut self) {
+        if self.game.game_over || self.game.is_ai_turn() {
+            return;
+        }
+        self.message = if self.game.pass() {
+            String::new()
+        } else if self.game.rules.passes == 0 {
+            "Passing is off; start with --variant pass.".to_string()
+        } else {
+            "You have no passes left.".to_string()
+        };
+    }
+
+    fn status(&self) -> String {
+        let game = &self.game;
+        if game.game_over {
+            let result = match game.winner {
+                Some(Player::Human) => "You win!".to_string(),
+                Some(winner) => format!("{} wins.", game.player_name(winner)),
+                None => "It's a draw.".to_string(),
+            };
+            return format!("{}  Press n for a new game.", result);
+        }
+        let said = self.game.last_explanation().map_or(String::new(), |explanation| format!("{}. ", explanation.summary(game)));
+        if game.is_ai_turn() {
+            let spinner = SPINNER[self.ticks % SPINNER.len()];
+            return format!("The AI is thinking {}", spinner);
+        }
+        if game.can_pass() {
+            let left = game.rules.passes - game.passes_used[game.current_player.index()];
+            let ends = if game.pass_ends_game() { " and ends the game" } else { "" };
+            return format!("{}Your move. p passes{} ({} left)", said, ends, left);
+        }
+        format!("{}Your move.", said)
+    }
+
+    // Whether the piece at `position` is the next to go under the fading rule
+    fn fades_next(&self, position: Position) -> bool {
+        let game = &self.game;
+        game.rules.fading
+            && game.placements.iter().any(|queue| queue.len() >= FADING_PIECE_LIMIT && queue.front() == Some(&position))
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let game = &self.game;
+        queue!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+
+        // Under Notakto everyone places the same piece
+        let pieces = if game.rules.notakto { "everyone plays *" } else { "you are X, the AI is O" };
+        let title = format!(
+            "3D Tic-Tac-Toe  -  {} AI, {}  -  {}",
+            self.engine.name(),
+            self.difficulty.name(),
+            pieces
+        );
+        queue!(out, MoveTo(2, 1), PrintStyledContent(title.bold()))?;
+
+        // Three 3x3 grids side by side, each cell three columns wide
+        for z in 0..3 {
+            let left = 4 + z as u16 * 16;
+            let label = format!("Layer {}", z + 1);
+            let label = if z == self.cursor.2 { label.bold().underlined() } else { label.stylize() };
+            queue!(out, MoveTo(left + 1, 3), PrintStyledContent(label))?;
+            for y in 0..3 {
+                queue!(out, MoveTo(left, 5 + y as u16))?;
+                for x in 0..3 {
+                    self.draw_cell(out, (x, y, z))?;
+                }
+            }
+        }
+
+        let mut row = 9;
+        if game.rules.line_scoring {
+            let scores = format!("Lines - you: {}  AI: {}", game.scores[Player::Human.index()], game.scores[Player::AI.index()]);
+            queue!(out, MoveTo(2, row), Print(scores))?;
+            row += 1;
+        }
+        queue!(out, MoveTo(2, row), Print(self.status()))?;
+        if !self.message.is_empty() {
+            queue!(out, MoveTo(2, row + 1), PrintStyledContent(self.message.as_str().yellow()))?;
+        }
+        queue!(out, MoveTo(2, row + 3), PrintStyledContent(KEYS.dark_grey()))?;
+        out.flush()
+    }
+
+    fn draw_cell(&self, out: &mut impl Write, position: Position) -> io::Result<()> {
+        let (x, y, z) = position;
+        let game = &self.game;
+        let mut symbol = match game.board[x][y][z] {
+            CellState::Empty => ".".stylize(),
+            CellState::Human => "X".green().bold(),
+            CellState::AI => "O".red().bold(),
+            CellState::Third => "T".blue().bold(),
+            CellState::Neutral => "*".yellow().bold(),
+            CellState::Blocked => "#".dark_grey(),
+        };
+        if game.winning_line.is_some_and(|line| line.contains(&position)) {
+            symbol = symbol.on(Color::DarkYellow);
+        } else if self.fades_next(position) {
+            symbol = symbol.attribute(Attribute::Dim);
+        }
+        if game.last_move == Some(position) {
+            symbol = symbol.underlined();
+        }
+
+        let (open, close) = if position == self.cursor { ("[", "]") } else { (" ", " ") };
+        queue!(out, Print(open), PrintStyledContent(symbol), Print(close))
+    }
+}
+
+fn main() -> io::Result<()> {
+    let options = Options::from_env();
+    let mut app = App::new(&options);
+
+    let _guard = TerminalGuard::new()?;
+    let mut out = io::stdout();
+    // Only redraw when something changed, to keep the traffic down over slow connections
+    let mut redraw = true;
+    loop {
+        redraw |= app.update_ai();
+        if redraw {
+            app.draw(&mut out)?;
+            redraw = false;
+        }
+
+        if !event::poll(TICK)? {
+            // Keep the spinner turning
+            if app.thinking.is_some() {
+                app.ticks += 1;
+                redraw = true;
+            }
+            continue;
+        }
+        match event::read()? {
+            // Some terminals also report releases; only presses count
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if !app.handle_key(key) {
+                    return Ok(());
+                }
+                redraw = true;
+            }
+            Event::Resize(..) => redraw = true,
+            _ => {}
+        }
+    }
+}